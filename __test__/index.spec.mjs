@@ -1,6 +1,10 @@
 import test from 'ava';
+import fs from 'fs';
 import os from 'os';
-import { activeWindow, activeWindowAsync, openWindows, openWindowsAsync, subscribeActiveWindow, unsubscribeActiveWindow, unsubscribeAllActiveWindow } from '../index.js';
+import path from 'path';
+import { spawn, spawnSync } from 'child_process';
+import { fileURLToPath } from 'url';
+import { activeWindow, activeWindowChanged, activeWindowForDisplay, activeWindowSubscriptionCount, activeWindowAsync, captureWindow, captureWindowAsync, captureWindows, checkPermissions, closeWindow, findWindowsByTitle, getActiveWindowId, getDesktopBounds, getMonitors, getPreviousActiveWindow, getProcessEnv, getWindowGeometry, getWindowManagerName, getWindowProperty, getWorkspaces, installExtension, isFullscreenActive, maximizeWindow, minimizeWindow, openApplications, openWindows, openWindowsAsync, restoreWindow, setActiveWindowCacheTtl, setActiveWindowPolling, setWindowPosition, shutdown, snapshot, subscribeActiveWindow, subscribeActiveWindowToFile, tryActiveWindow, tryOpenWindows, unsubscribeActiveWindow, unsubscribeAllActiveWindow, WindowWatcher, windowUnderCursor } from '../index.js';
 
 const defaultStruct = {
   os: os.platform(),
@@ -47,6 +51,100 @@ test('activeWindow', (t) => {
   return t.pass();
 })
 
+test('activeWindow with options', (t) => {
+  const data = activeWindow({ includeUrl: true });
+  compareStruct(t, data);
+  t.is(typeof data.url, 'string');
+  return t.pass();
+})
+
+test('activeWindow stableId', (t) => {
+  const data = activeWindow();
+  t.is(typeof data.stableId, 'string');
+  if (data.id) {
+    t.regex(data.stableId, /^[0-9a-f]{16}$/);
+    t.is(activeWindow().stableId, data.stableId);
+  } else {
+    t.is(data.stableId, '');
+  }
+  return t.pass();
+})
+
+test('activeWindow cache', (t) => {
+  const data = activeWindow();
+  t.is(activeWindow().id, data.id);
+  t.is(typeof activeWindow({ forceRefresh: true }).id, 'number');
+  setActiveWindowCacheTtl(0);
+  t.is(typeof activeWindow().id, 'number');
+  setActiveWindowCacheTtl(16);
+  return t.pass();
+})
+
+test('getMonitors', (t) => {
+  const monitors = getMonitors();
+  t.log(monitors);
+  for (const monitor of monitors) {
+    t.true(monitor.width > 0 && monitor.height > 0);
+    t.true(monitor.scaleFactor > 0);
+    t.is(typeof monitor.isPrimary, 'boolean');
+  }
+  t.true(monitors.filter((monitor) => monitor.isPrimary).length <= 1);
+  return t.pass();
+})
+
+test('getWorkspaces', (t) => {
+  const workspaces = getWorkspaces();
+  t.log(workspaces);
+  for (const workspace of workspaces) {
+    t.is(typeof workspace.id, 'number');
+    t.is(typeof workspace.isCurrent, 'boolean');
+  }
+  t.true(workspaces.filter((workspace) => workspace.isCurrent).length <= 1);
+  return t.pass();
+})
+
+test('activeWindow frame', (t) => {
+  const { frame, position } = activeWindow();
+  t.log(frame);
+  if (frame) {
+    t.true(frame.width > 0 && frame.height > 0);
+    if (process.platform === 'linux') {
+      t.true(frame.width >= position.width && frame.height >= position.height);
+    }
+  }
+  return t.pass();
+})
+
+test('activeWindow nativeHandle', (t) => {
+  const data = activeWindow();
+  t.true(Number.isSafeInteger(data.nativeHandle));
+  if (data.nativeHandle && os.platform() !== 'win32') {
+    t.is(data.nativeHandle, data.id);
+  }
+  return t.pass();
+})
+
+test('activeWindow monitor', (t) => {
+  const { monitor } = activeWindow();
+  t.log(monitor);
+  if (monitor) {
+    t.true(monitor.scaleFactor > 0);
+    t.true(monitor.width > 0 && monitor.height > 0);
+  }
+  return t.pass();
+})
+
+test('activeWindow with logicalPixels', (t) => {
+  const data = activeWindow({ logicalPixels: true });
+  compareStruct(t, data);
+  const scaleFactor = os.platform() === 'darwin' ? 1 : (data.monitor?.scaleFactor ?? 1);
+  const native = activeWindow();
+  if (native.id === data.id) {
+    t.true(Math.abs(data.position.width - native.position.width / scaleFactor) <= 1);
+  }
+  return t.pass();
+})
+
 test('openWindows', (t) => {
   console.time('openwindows');
   const list = openWindows();
@@ -59,6 +157,381 @@ test('openWindows', (t) => {
   return t.pass();
 })
 
+test('tryActiveWindow and tryOpenWindows', (t) => {
+  const active = tryActiveWindow();
+  t.true(active.ok);
+  compareStruct(t, active.value);
+  const open = tryOpenWindows();
+  t.true(open.ok);
+  t.not(open.value.length, 0);
+  return t.pass();
+})
+
+test('activeWindow process start time', (t) => {
+  const data = activeWindow();
+  if (data.id !== 0) {
+    t.true(data.info.startTime >= 0);
+    t.true(data.info.startTime <= Date.now());
+  }
+  return t.pass();
+})
+
+test('activeWindow instance name', (t) => {
+  const data = activeWindow();
+  t.true(typeof data.info.instanceName === 'string');
+  if (os.platform() !== 'linux') {
+    t.is(data.info.instanceName, '');
+  }
+  return t.pass();
+})
+
+test('activeWindow application bundle', (t) => {
+  const data = activeWindow();
+  if (os.platform() !== 'darwin') {
+    t.is(data.info.bundleId, '');
+    t.is(data.info.appName, '');
+  } else if (data.id !== 0) {
+    t.true(typeof data.info.bundleId === 'string');
+    t.not(data.info.appName, '');
+  }
+  return t.pass();
+})
+
+test('openWindows isFocused', (t) => {
+  const windows = openWindows();
+  const focused = windows.filter((data) => data.isFocused);
+  t.true(focused.length <= 1);
+  const activeWindows = windows.filter((data) => data.isActive);
+  t.true(activeWindows.length <= 1);
+  const active = activeWindow();
+  t.is(active.isActive, active.id !== 0);
+  if (activeWindows.length === 1 && active.id !== 0) {
+    t.is(activeWindows[0].id, active.id);
+  }
+  return t.pass();
+})
+
+test('openWindows opacity', (t) => {
+  for (const { opacity } of openWindows()) {
+    t.true(opacity >= 0 && opacity <= 1);
+  }
+  return t.pass();
+})
+
+test('activeWindowChanged', (t) => {
+  const current = activeWindow();
+  const changed = activeWindowChanged(current);
+  if (changed !== null) {
+    compareStruct(t, changed);
+  }
+  return t.pass();
+})
+
+test('getPreviousActiveWindow', (t) => {
+  const current = activeWindow();
+  const previous = getPreviousActiveWindow();
+  if (previous !== null) {
+    compareStruct(t, previous);
+    t.not(previous.id, current.id);
+  }
+  return t.pass();
+})
+
+test('activeWindow cpu usage is 0 for one-shot calls', (t) => {
+  t.is(activeWindow().usage.cpu, 0);
+  return t.pass();
+})
+
+test('activeWindow with focused element', (t) => {
+  const data = activeWindow({ includeFocusedElement: true });
+  compareStruct(t, data);
+  t.is(activeWindow().focusedElement, undefined);
+  if (data.focusedElement) {
+    t.is(typeof data.focusedElement.role, 'string');
+    t.is(typeof data.focusedElement.name, 'string');
+    t.is(typeof data.focusedElement.bounds.width, 'number');
+  }
+  return t.pass();
+})
+
+test('getActiveWindowId', (t) => {
+  const { id } = activeWindow();
+  t.is(getActiveWindowId(), id);
+  return t.pass();
+})
+
+test('snapshot', (t) => {
+  const { active, windows } = snapshot();
+  compareStruct(t, active);
+  t.truthy(windows.length);
+  const activeWindows = windows.filter((window) => window.isActive);
+  t.true(activeWindows.length <= 1);
+  if (activeWindows.length) {
+    t.is(activeWindows[0].id, active.id);
+  }
+  return t.pass();
+})
+
+test('openApplications', (t) => {
+  const applications = openApplications();
+  t.truthy(applications.length);
+  const processIds = applications.map(({ info }) => info.processId);
+  t.is(new Set(processIds).size, processIds.length);
+  for (const { info, frontmostWindow, windows } of applications) {
+    t.truthy(windows.length);
+    t.is(frontmostWindow.info.processId, info.processId);
+    t.true(windows.every((window) => window.info.processId === info.processId));
+    t.true(windows.some((window) => window.id === frontmostWindow.id));
+  }
+  return t.pass();
+})
+
+test('isFullscreenActive', (t) => {
+  const fullscreen = isFullscreenActive();
+  t.is(typeof fullscreen, 'boolean');
+  if (!activeWindow().id) {
+    t.false(fullscreen);
+  }
+  return t.pass();
+})
+
+test('getWindowManagerName', (t) => {
+  const name = getWindowManagerName();
+  t.log(name);
+  t.is(typeof name, 'string');
+  return t.pass();
+})
+
+test('getProcessEnv', (t) => {
+  const env = getProcessEnv(process.pid);
+  t.is(typeof env, 'object');
+  if (os.platform() !== 'win32') {
+    t.is(env.PATH, process.env.PATH);
+  }
+  t.deepEqual(getProcessEnv(-1), {});
+  return t.pass();
+})
+
+test('activeWindowForDisplay', (t) => {
+  const data = activeWindowForDisplay(0);
+  t.log(data);
+  if (data) {
+    compareStruct(t, data);
+  }
+  t.is(activeWindowForDisplay(1000), null);
+  return t.pass();
+})
+
+test('windowUnderCursor', (t) => {
+  const data = windowUnderCursor();
+  t.log(data);
+  if (data) {
+    compareStruct(t, data);
+  }
+  return t.pass();
+})
+
+test('checkPermissions', (t) => {
+  const permissions = checkPermissions();
+  t.log(permissions);
+  t.is(typeof permissions.screenRecording, 'boolean');
+  t.is(typeof permissions.accessibility, 'boolean');
+  if (os.platform() !== 'darwin') {
+    t.deepEqual(permissions, { screenRecording: true, accessibility: true });
+  }
+  return t.pass();
+})
+
+test('getDesktopBounds', (t) => {
+  const bounds = getDesktopBounds();
+  t.log(bounds);
+  t.true(bounds.width > 0);
+  t.true(bounds.height > 0);
+  t.false(bounds.isFullScreen);
+  return t.pass();
+})
+
+test('captureWindows', (t) => {
+  const ids = openWindows().map(({ id }) => id);
+  const captures = captureWindows([...ids, 0], 64);
+  t.log(captures.map(({ id, image }) => ({ id, size: image.length })));
+  t.true(captures.length <= ids.length);
+  for (const { id, image } of captures) {
+    t.true(ids.includes(id));
+    t.true(Buffer.isBuffer(image));
+  }
+  return t.pass();
+})
+
+test('captureWindowAsync', async (t) => {
+  await t.throwsAsync(captureWindowAsync(0));
+  const { id } = activeWindow();
+  try {
+    const image = await captureWindowAsync(id, 64);
+    t.true(Buffer.isBuffer(image));
+  } catch (error) {
+    // Occluded windows, Wayland sessions or missing permission cannot be captured
+    t.log(error.message);
+  }
+  return t.pass();
+})
+
+test('captureWindow rgba format', (t) => {
+  t.throws(() => captureWindow(0, undefined, { format: 'jpeg' }));
+  const { id } = activeWindow();
+  try {
+    const { width, height, stride, data } = captureWindow(id, 64, { format: 'rgba' });
+    t.true(width > 0 && width <= 64 && height > 0 && height <= 64);
+    t.is(stride, width * 4);
+    t.is(data.length, stride * height);
+  } catch (error) {
+    // Occluded windows, Wayland sessions or missing permission cannot be captured
+    t.log(error.message);
+  }
+  return t.pass();
+})
+
+test('openWindows with filter options', (t) => {
+  const filtered = openWindows({ excludeDesktopShell: true });
+  const untyped = openWindows({ includeUntyped: true });
+  const typed = openWindows({ windowTypes: ['normal', 'dialog'] });
+  const currentWorkspace = openWindows({ onlyCurrentWorkspace: true });
+  t.not(filtered.length, 0);
+  t.not(untyped.length, 0);
+  t.not(typed.length, 0);
+  t.true(currentWorkspace.length <= openWindows().length);
+  for (const data of [...filtered, ...untyped, ...typed, ...currentWorkspace]) {
+    compareStruct(t, data);
+  }
+  return t.pass();
+})
+
+test('openWindows with transient windows', (t) => {
+  const topLevel = openWindows();
+  const withTransient = openWindows({ includeTransient: true });
+  t.true(topLevel.length <= withTransient.length);
+  for (const data of topLevel) {
+    t.is(data.parentId, undefined);
+  }
+  return t.pass();
+})
+
+test('openWindows with override-redirect windows', (t) => {
+  const managed = openWindows({ includeUntyped: true });
+  const withOverrideRedirect = openWindows({ includeUntyped: true, includeOverrideRedirect: true });
+  t.true(managed.length <= withOverrideRedirect.length);
+  return t.pass();
+})
+
+test('openWindows with hidden windows', (t) => {
+  const stacked = openWindows();
+  const withHidden = openWindows({ includeHidden: true });
+  t.true(stacked.length <= withHidden.length);
+  for (const data of withHidden) {
+    t.is(typeof data.isMinimized, 'boolean');
+    t.is(typeof data.isVisible, 'boolean');
+  }
+  return t.pass();
+})
+
+test('findWindowsByTitle', (t) => {
+  const [first] = openWindows().filter((data) => data.title);
+  if (first) {
+    t.true(findWindowsByTitle(first.title).some((data) => data.id === first.id));
+    t.true(findWindowsByTitle(first.title.toUpperCase(), { caseInsensitive: true }).some((data) => data.id === first.id));
+    for (const data of findWindowsByTitle(first.title, { exact: true })) {
+      t.is(data.title, first.title);
+    }
+  }
+  t.deepEqual(findWindowsByTitle('\u0000x-win no such title\u0000'), []);
+  return t.pass();
+})
+
+test('openWindows stacking layer', (t) => {
+  for (const data of openWindows()) {
+    t.is(typeof data.alwaysOnTop, 'boolean');
+    t.is(typeof data.alwaysOnBottom, 'boolean');
+    t.false(data.alwaysOnTop && data.alwaysOnBottom);
+  }
+  return t.pass();
+})
+
+test('openWindows front to back order', (t) => {
+  const sorted = openWindows();
+  for (let i = 1; i < sorted.length; i++) {
+    t.true(sorted[i - 1].zOrder >= sorted[i].zOrder);
+  }
+  const raw = openWindows({ rawOrder: true });
+  t.deepEqual(
+    raw.map((data) => data.id).sort(),
+    sorted.map((data) => data.id).sort(),
+  );
+  return t.pass();
+})
+
+test('openWindows with role', (t) => {
+  for (const data of openWindows()) {
+    t.is(typeof data.role, 'string');
+    if (os.platform() !== 'linux') {
+      t.is(data.role, '');
+    }
+  }
+  return t.pass();
+})
+
+test('openWindows without usage', (t) => {
+  const list = openWindows({ includeUsage: false });
+  t.not(list.length, 0);
+  for (const data of list) {
+    t.is(data.usage.memory, 0);
+  }
+})
+
+test('activeWindow and openWindows with timeout', (t) => {
+  compareStruct(t, activeWindow({ timeoutMs: 5000 }));
+  t.not(openWindows({ timeoutMs: 5000 }).length, 0);
+})
+
+test.serial('activeWindowSubscriptionCount', (t) => {
+  const count = activeWindowSubscriptionCount();
+  const a = subscribeActiveWindow(() => { });
+  subscribeActiveWindow(() => { });
+  t.is(activeWindowSubscriptionCount(), count + 2);
+  unsubscribeActiveWindow(a);
+  t.is(activeWindowSubscriptionCount(), count + 1);
+  unsubscribeAllActiveWindow();
+  t.is(activeWindowSubscriptionCount(), 0);
+})
+
+test.serial('setActiveWindowPolling', (t) => {
+  t.notThrows(() => setActiveWindowPolling({ adaptive: true, minIntervalMs: 20, maxIntervalMs: 500, backoffFactor: 2 }));
+  const a = subscribeActiveWindow(() => { });
+  t.notThrows(() => setActiveWindowPolling({ adaptive: true }));
+  t.throws(() => setActiveWindowPolling({ adaptive: true, minIntervalMs: 500, maxIntervalMs: 20 }));
+  t.throws(() => setActiveWindowPolling({ adaptive: true, backoffFactor: 0.5 }));
+  t.throws(() => setActiveWindowPolling({ intervalMs: 0 }));
+  t.true(unsubscribeActiveWindow(a));
+  t.notThrows(() => setActiveWindowPolling({}));
+})
+
+test.serial('shutdown', (t) => {
+  subscribeActiveWindow(() => { });
+  subscribeActiveWindow(() => { });
+  t.true(shutdown());
+  t.is(activeWindowSubscriptionCount(), 0);
+  t.true(shutdown());
+  const a = subscribeActiveWindow(() => { });
+  t.is(activeWindowSubscriptionCount(), 1);
+  t.true(unsubscribeActiveWindow(a));
+})
+
+test.serial('unsubscribeActiveWindow with unknown id', (t) => {
+  const a = subscribeActiveWindow(() => { });
+  t.true(unsubscribeActiveWindow(a));
+  t.false(unsubscribeActiveWindow(a));
+  t.false(unsubscribeActiveWindow(999999));
+})
+
 test('subscribeActiveWindow', async (t) => {
   try {
     const data1 = await new Promise((resolve, reject) => {
@@ -113,6 +586,186 @@ test('subscribeActiveWindow', async (t) => {
 })
 
 
+test('subscribeActiveWindow with debounce options', async (t) => {
+  try {
+    const data = await new Promise((resolve, reject) => {
+      const r = subscribeActiveWindow((info, change) => {
+        t.log(r, info, change);
+        if (info?.id) {
+          unsubscribeActiveWindow(r);
+          t.is(change, 'focus');
+          resolve(info);
+        } else {
+          reject(new Error('Test failed! no id for active window!'));
+        }
+      }, { debounceMs: 300, titleChangeDebounceMs: 1000 });
+    });
+    compareStruct(t, data);
+    return t.pass();
+  } catch (error) {
+    unsubscribeAllActiveWindow();
+    throw error;
+  }
+})
+
+test('subscribeActiveWindow emits the initial window', async (t) => {
+  const start = Date.now();
+  const [info, change] = await new Promise((resolve) => {
+    const r = subscribeActiveWindow((...args) => {
+      unsubscribeActiveWindow(r);
+      resolve(args);
+    }, { debounceMs: 5000 });
+  });
+  // The debounce is not awaited for the initial window
+  t.true(Date.now() - start < 5000);
+  t.true(['focus', 'error'].includes(change));
+  if (change === 'focus') {
+    t.is(info.id, activeWindow({ forceRefresh: true }).id);
+  }
+  return t.pass();
+})
+
+test.serial('subscribeActiveWindow emits the initial window to a later observer', async (t) => {
+  // The shared poller waits up to the longest adaptive interval while the active window does not change
+  setActiveWindowPolling({ adaptive: true, minIntervalMs: 20, maxIntervalMs: 60000, backoffFactor: 100 });
+  try {
+    await new Promise((resolve) => {
+      const a = subscribeActiveWindow(() => resolve(a));
+    });
+    await new Promise((resolve) => setTimeout(resolve, 300));
+    const start = Date.now();
+    const change = await new Promise((resolve) => {
+      subscribeActiveWindow((info, change) => resolve(change));
+    });
+    t.true(Date.now() - start < 5000);
+    t.true(['focus', 'error'].includes(change));
+  } finally {
+    unsubscribeAllActiveWindow();
+    setActiveWindowPolling({});
+  }
+})
+
+test('subscribeActiveWindow without initial window', async (t) => {
+  const calls = [];
+  const r = subscribeActiveWindow((info, change) => calls.push(change), { emitInitial: false });
+  await new Promise((resolve) => setTimeout(resolve, 300));
+  unsubscribeActiveWindow(r);
+  // Only disruptions are reported while the active window does not change
+  t.true(calls.every((change) => change === 'error'));
+  return t.pass();
+})
+
+test('subscribeActiveWindow with watchedFields', async (t) => {
+  t.throws(() => subscribeActiveWindow(() => {}, { watchedFields: ['size'] }));
+  const calls = [];
+  const r = subscribeActiveWindow((info, change) => calls.push(change), { watchedFields: ['position'], emitInitial: false });
+  await new Promise((resolve) => setTimeout(resolve, 300));
+  unsubscribeActiveWindow(r);
+  // Focus and title changes are not watched
+  t.true(calls.every((change) => ['position', 'error'].includes(change)));
+  return t.pass();
+})
+
+test('subscribeActiveWindow initial window without previousFocusDurationMs', async (t) => {
+  const [info, change] = await new Promise((resolve) => {
+    const r = subscribeActiveWindow((...args) => {
+      unsubscribeActiveWindow(r);
+      resolve(args);
+    });
+  });
+  t.true(['focus', 'error'].includes(change));
+  t.true(info.previousFocusDurationMs == null);
+  return t.pass();
+})
+
+test('subscribeActiveWindow with titleHistoryLength', async (t) => {
+  try {
+    const data = await new Promise((resolve, reject) => {
+      const r = subscribeActiveWindow((info) => {
+        t.log(r, info);
+        if (info?.id) {
+          unsubscribeActiveWindow(r);
+          resolve(info);
+        } else {
+          reject(new Error('Test failed! no id for active window!'));
+        }
+      }, { titleHistoryLength: 5 });
+    });
+    compareStruct(t, data);
+    t.true(Array.isArray(data.titleHistory));
+    t.true(data.titleHistory.length >= 1 && data.titleHistory.length <= 5);
+    t.is(data.titleHistory[data.titleHistory.length - 1].title, data.title);
+    t.is(typeof data.titleHistory[0].timestamp, 'number');
+    return t.pass();
+  } catch (error) {
+    unsubscribeAllActiveWindow();
+    throw error;
+  }
+})
+
+test('subscribeActiveWindow with filter options', async (t) => {
+  const current = activeWindow();
+  try {
+    const data = await new Promise((resolve, reject) => {
+      const r = subscribeActiveWindow((info) => {
+        t.log(r, info);
+        if (info?.id) {
+          unsubscribeActiveWindow(r);
+          resolve(info);
+        } else {
+          reject(new Error('Test failed! no id for active window!'));
+        }
+      }, { processNames: [current.info.execName.toUpperCase()], titleRegex: '.*' });
+    });
+    compareStruct(t, data);
+    t.is(data.info.execName, current.info.execName);
+    t.throws(() => subscribeActiveWindow(() => {}, { titleRegex: '(' }));
+    return t.pass();
+  } catch (error) {
+    unsubscribeAllActiveWindow();
+    throw error;
+  }
+})
+
+test('subscribeActiveWindow error events', async (t) => {
+  const [info, change, error] = await new Promise((resolve) => {
+    const r = subscribeActiveWindow((...args) => {
+      unsubscribeActiveWindow(r);
+      resolve(args);
+    });
+  });
+  t.true(['focus', 'title', 'process', 'error'].includes(change));
+  if (change === 'error') {
+    t.is(info.id, 0);
+    t.is(typeof error, 'string');
+  } else {
+    t.is(error, undefined);
+  }
+  return t.pass();
+})
+
+test('subscribeActiveWindowToFile', async (t) => {
+  const file = path.join(os.tmpdir(), `x-win-${process.pid}.jsonl`);
+  const id = subscribeActiveWindowToFile(file);
+  try {
+    t.true(fs.existsSync(file));
+    await new Promise((resolve) => setTimeout(resolve, 500));
+    t.true(unsubscribeActiveWindow(id));
+    const lines = fs.readFileSync(file, 'utf8').split('\n').filter((line) => line.length > 0);
+    t.true(lines.length > 0);
+    const event = JSON.parse(lines[0]);
+    t.is(event.change, 'focus');
+    t.is(typeof event.timestamp, 'number');
+    compareStruct(t, event.window);
+    t.throws(() => subscribeActiveWindowToFile(path.join(file, 'missing', 'file.jsonl')));
+    t.throws(() => subscribeActiveWindowToFile(file, { titleRegex: '(' }));
+    return t.pass();
+  } finally {
+    unsubscribeActiveWindow(id);
+    fs.rmSync(file, { force: true });
+  }
+})
+
 test('unsubscribeAllActiveWindow', async (t) => {
   try {
     const data1 = await new Promise((resolve, reject) => {
@@ -167,6 +820,24 @@ test('activeWindowAsync', async (t) => {
   return t.pass();
 })
 
+test('activeWindowAsync rejects without X server', (t) => {
+  if (os.platform() !== 'linux') {
+    return t.pass();
+  }
+  const binding = fileURLToPath(new URL('../index.js', import.meta.url));
+  const script = `require(${JSON.stringify(binding)}).activeWindowAsync().then(
+    () => process.stdout.write('resolved'),
+    (error) => process.stdout.write('rejected: ' + error.message),
+  );`;
+  const { status, stdout } = spawnSync(process.execPath, ['-e', script], {
+    env: { ...process.env, DISPLAY: ':4242', WAYLAND_DISPLAY: '' },
+    encoding: 'utf8',
+  });
+  t.is(status, 0);
+  t.true(stdout.startsWith('rejected: '), stdout);
+  return t.pass();
+})
+
 test('openWindowsAsync', async (t) => {
   console.time('openWindowsAsync');
   const list = await openWindowsAsync();
@@ -179,6 +850,18 @@ test('openWindowsAsync', async (t) => {
   return t.pass();
 })
 
+test('openWindowsAsync with onWindow', async (t) => {
+  const gathered = [];
+  const list = await openWindowsAsync(undefined, (data) => gathered.push(data.id));
+  // Calls queued by the worker thread are all run before the next turn
+  await new Promise((resolve) => setImmediate(resolve));
+  t.is(gathered.length, list.length);
+  for (const data of list) {
+    t.true(gathered.includes(data.id));
+  }
+  return t.pass();
+})
+
 test('getIcon', (t) => {
   const data = activeWindow();
   console.time('getIcon');
@@ -189,6 +872,18 @@ test('getIcon', (t) => {
   return t.pass();
 })
 
+test('getDesktopEntry', (t) => {
+  const data = activeWindow();
+  const desktopEntry = data.getDesktopEntry();
+  t.is(typeof desktopEntry.displayName, 'string');
+  t.is(typeof desktopEntry.iconName, 'string');
+  if (os.platform() !== 'linux') {
+    t.is(desktopEntry.displayName, '');
+    t.is(desktopEntry.iconName, '');
+  }
+  return t.pass();
+})
+
 test('getIconAsync', async (t) => {
   const data = activeWindow();
   console.time('getIconAsync');
@@ -197,3 +892,173 @@ test('getIconAsync', async (t) => {
   compareIconStruct(t, iconInfo);
   return t.pass();
 })
+
+/**
+ * Open a new window with an application available by default on the platform
+ */
+function openTestWindow() {
+  switch (os.platform()) {
+    case 'win32':
+      return spawn('notepad.exe');
+    case 'darwin':
+      return spawn('open', ['-n', '-W', '-a', 'TextEdit']);
+    default:
+      return spawn('xterm');
+  }
+}
+
+/**
+ * Poll the watcher until an `opened` event is reported
+ */
+async function waitOpenedEvent(watcher) {
+  for (let i = 0; i < 50; i++) {
+    const events = watcher.poll();
+    const event = events.find((event) => event.kind === 'opened');
+    if (event) {
+      return event;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 100));
+  }
+  throw new Error('Test failed! no opened event reported by the watcher!');
+}
+
+test('WindowWatcher', async (t) => {
+  const watcher1 = new WindowWatcher();
+  const watcher2 = new WindowWatcher();
+  const child = openTestWindow();
+  try {
+    const event1 = await waitOpenedEvent(watcher1);
+    const event2 = await waitOpenedEvent(watcher2);
+    t.log(event1, event2);
+    t.is(event1.window.id, event2.window.id);
+    t.false(watcher1.poll().some((event) => event.kind === 'opened' && event.window.id === event1.window.id));
+  } finally {
+    child.kill();
+  }
+  return t.pass();
+})
+
+test('closeWindow', async (t) => {
+  const watcher = new WindowWatcher();
+  const child = openTestWindow();
+  try {
+    const { window } = await waitOpenedEvent(watcher);
+    let dispatched;
+    try {
+      dispatched = closeWindow(window.id);
+    } catch (error) {
+      /** Accessibility permission is required on darwin */
+      t.log(error);
+      return t.pass();
+    }
+    t.true(dispatched);
+    for (let i = 0; i < 50; i++) {
+      if (watcher.poll().some((event) => event.kind === 'closed' && event.window.id === window.id)) {
+        return t.pass();
+      }
+      await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+    t.fail('Test failed! window has not been closed!');
+  } finally {
+    child.kill();
+  }
+})
+
+test('setWindowPosition', async (t) => {
+  const watcher = new WindowWatcher();
+  const child = openTestWindow();
+  const position = { x: 100, y: 100, width: 400, height: 300, isFullScreen: false };
+  try {
+    const { window } = await waitOpenedEvent(watcher);
+    t.true(setWindowPosition(window.id, position));
+    t.false(setWindowPosition(0, position));
+  } catch (error) {
+    /** Accessibility permission is required on darwin */
+    if (os.platform() !== 'darwin') {
+      throw error;
+    }
+    t.log(error);
+  } finally {
+    child.kill();
+  }
+  return t.pass();
+})
+
+test('getWindowGeometry', (t) => {
+  const { id, position } = activeWindow();
+  if (id) {
+    const geometry = getWindowGeometry(id);
+    t.is(geometry.width, position.width);
+    t.is(geometry.height, position.height);
+  }
+  t.is(getWindowGeometry(0), null);
+  return t.pass();
+})
+
+test('getWindowProperty', (t) => {
+  const { id } = activeWindow();
+  if (id && os.platform() === 'linux' && !process.env.WAYLAND_DISPLAY) {
+    const property = getWindowProperty(id, 'WM_CLASS');
+    if (property !== null) {
+      t.is(property.format, 8);
+      t.true(Buffer.isBuffer(property.data));
+    }
+  } else {
+    t.is(getWindowProperty(id, 'WM_CLASS'), null);
+  }
+  t.is(getWindowProperty(id, ''), null);
+  return t.pass();
+})
+
+test('minimizeWindow, maximizeWindow and restoreWindow', async (t) => {
+  const watcher = new WindowWatcher();
+  const child = openTestWindow();
+  try {
+    const { window } = await waitOpenedEvent(watcher);
+    t.true(minimizeWindow(window.id));
+    t.true(restoreWindow(window.id));
+    t.true(maximizeWindow(window.id));
+    t.true(restoreWindow(window.id));
+    t.false(minimizeWindow(0));
+  } catch (error) {
+    /** Accessibility permission is required on darwin */
+    if (os.platform() !== 'darwin') {
+      throw error;
+    }
+    t.log(error);
+  } finally {
+    child.kill();
+  }
+  return t.pass();
+})
+
+test('installExtension', (t) => {
+  /** Writing extension files is only tested where it is a no-op */
+  if (os.platform() === 'linux') {
+    return t.pass();
+  }
+  t.throws(() => installExtension());
+})
+
+test('installExtension dryRun', (t) => {
+  if (os.platform() !== 'linux') {
+    const info = installExtension({ dryRun: true });
+    t.false(info.applicable);
+    t.false(info.installed);
+    t.is(info.files.length, 0);
+    return t.pass();
+  }
+  try {
+    const info = installExtension({ dryRun: true });
+    t.is(info.files.length, 2);
+    t.true(info.files.every((file) => file.startsWith(info.path)));
+    if (!info.applicable) {
+      t.false(info.installed);
+      t.false(info.needsReload);
+    }
+  } catch (error) {
+    /** Unsupported Gnome shell on Wayland */
+    t.log(error);
+  }
+  return t.pass();
+})
@@ -3,6 +3,121 @@
 
 /* auto-generated by NAPI-RS */
 
+/**
+ * Struct to store options used to recover the active window
+*/
+export interface ActiveWindowOptions {
+  /**
+  * Recover the url of the active tab when the window belongs to a supported browser (default: `false`)
+  */
+  includeUrl?: boolean
+  /**
+  * Recover the language of the focused document, requires accessibility permission on macOS (default: `false`)
+  */
+  includeContentLanguage?: boolean
+  /**
+  * Recover the focused UI element of the window with AT-SPI on Linux, UI Automation on Windows
+  * and the AX API on macOS, requires accessibility permission on macOS (default: `false`)
+  */
+  includeFocusedElement?: boolean
+  /**
+  * Return positions in logical pixels, physical geometry is divided by `scaleFactor` of the window monitor
+  * (positions are already logical on macOS) (default: `false`)
+  */
+  logicalPixels?: boolean
+  /**
+  * Maximum time in milliseconds to wait for the X server on Linux, an error is thrown when it is exceeded
+  * (default: no limit)
+  */
+  timeoutMs?: number
+  /**
+  * Recover the active window again instead of returning the one cached by a recent call of `activeWindow`
+  * (default: `false`)
+  */
+  forceRefresh?: boolean
+}
+/**
+ * Struct to store the open windows of an application (owning process)
+*/
+export interface AppInfo {
+  info: ProcessInfo
+  frontmostWindow: WindowInfo
+  windows: Array<WindowInfo>
+}
+/**
+ * Struct to store options used by `captureWindow`
+*/
+export interface CaptureOptions {
+  /**
+  * Output of the capture, PNG bytes or the raw RGBA pixels with their size (default: `'png'`)
+  */
+  format?: 'png' | 'rgba'
+}
+/**
+ * Struct to store the metadata of the freedesktop `.desktop` entry of an application, only resolved on Linux
+*/
+export interface DesktopEntry {
+  /**
+  * Localized `Name` of the entry (e.g. `Visual Studio Code`), empty when no entry matches
+  */
+  displayName: string
+  /**
+  * `Icon` of the entry, a theme icon name (e.g. `org.gnome.Nautilus`) or an absolute path, empty when no entry matches
+  */
+  iconName: string
+}
+/**
+ * Struct to store the result of the Gnome extension installation
+*/
+export interface ExtensionInstallInfo {
+  /**
+  * `true` when extension files are present in `path` after the call (already present with `dryRun`)
+  */
+  installed: boolean
+  /**
+  * Folder where extension files are written
+  */
+  path: string
+  /**
+  * `true` when extension files have been written or updated (would be with `dryRun`), the user session has to be
+  * restarted to load them
+  */
+  needsReload: boolean
+  /**
+  * `true` when the session is a Gnome Wayland desktop where the extension is needed to recover windows
+  */
+  applicable: boolean
+  /**
+  * Paths of the extension files written (or which would be written) by the installation
+  */
+  files: Array<string>
+}
+/**
+ * Struct to store options used by `installExtension`
+*/
+export interface ExtensionInstallOptions {
+  /**
+  * Only report the files which would be written, without touching the filesystem (default: `false`)
+  */
+  dryRun?: boolean
+}
+/**
+ * Struct to store the focused UI element of the active window recovered with the accessibility API
+*/
+export interface FocusedElement {
+  /**
+  * Role of the element, the AT-SPI role name on Linux and Windows (e.g. `push button`) and the AXRole on macOS (e.g. `AXButton`)
+  */
+  role: string
+  /**
+  * Accessible name of the element, empty when not set
+  */
+  name: string
+  /**
+  * Position and size of the element on screen
+  */
+  bounds: WindowPosition
+}
 /**
  * Struct to store process information of the window
 */
@@ -11,6 +126,125 @@ export interface IconInfo {
   height: number
   width: number
 }
+/**
+ * Struct to store position, size and name of a monitor
+*/
+export interface MonitorInfo {
+  id: number
+  name: string
+  x: number
+  y: number
+  width: number
+  height: number
+  isPrimary: boolean
+  /**
+  * Ratio between physical and logical pixels of the monitor (e.g. `2` on Retina displays), `1` when unknown
+  */
+  scaleFactor: number
+}
+/**
+ * Struct to store options used to recover open windows
+*/
+export interface OpenWindowsOptions {
+  /**
+  * Recover the url of the active tab when the window belongs to a supported browser (default: `false`)
+  */
+  includeUrl?: boolean
+  /**
+  * Keep windows without `_NET_WM_WINDOW_TYPE` on Linux, only typed windows are kept by default (default: `false`)
+  */
+  includeUntyped?: boolean
+  /**
+  * Exclude desktop shell windows (taskbar, dock, desktop, menu bar...) on Windows and macOS,
+  * they are always excluded on Linux (default: `false`)
+  */
+  excludeDesktopShell?: boolean
+  /**
+  * Return positions in logical pixels, physical geometry is divided by `scaleFactor` of the window monitor
+  * (positions are already logical on macOS) (default: `false`)
+  */
+  logicalPixels?: boolean
+  /**
+  * Accepted `_NET_WM_WINDOW_TYPE` on Linux (e.g. `dialog` for `_NET_WM_WINDOW_TYPE_DIALOG`),
+  * normal, dialog, utility and toolbar windows are kept when not set
+  */
+  windowTypes?: Array<string>
+  /**
+  * Recover the memory usage of windows, `usage.memory` is left at `0` and its reading is skipped when `false` (default: `true`)
+  */
+  includeUsage?: boolean
+  /**
+  * Only keep windows of the current workspace (virtual desktop) on Linux X11 and Windows,
+  * sticky windows shown on all workspaces are kept. macOS only lists windows of the active Space (default: `false`)
+  */
+  onlyCurrentWorkspace?: boolean
+  /**
+  * Keep transient windows (dialogs, tool palettes...) declaring a parent with `WM_TRANSIENT_FOR` on X11
+  * or owned by another window on Windows, their parent is set in `parentId` (default: `false`)
+  */
+  includeTransient?: boolean
+  /**
+  * Keep override-redirect windows (menus, tooltips, drag icons...) on X11 even when they have no
+  * `_NET_WM_WINDOW_TYPE`, and tool windows or popups without caption on Windows (default: `false`)
+  */
+  includeOverrideRedirect?: boolean
+  /**
+  * Also list client windows missing from `_NET_CLIENT_LIST_STACKING` on X11: windows of `_NET_CLIENT_LIST`
+  * (minimized windows are omitted from the stacking list by some minimal WMs) and, when the WM maintains
+  * neither list, client windows found with `QueryTree`. Minimized windows have `isMinimized` set (default: `false`)
+  */
+  includeHidden?: boolean
+  /**
+  * Keep the enumeration order of the platform (back to front stacking list on X11) instead of sorting windows
+  * from front to back with `zOrder` (default: `false`)
+  */
+  rawOrder?: boolean
+  /**
+  * Maximum time in milliseconds to wait for the X server on Linux, an error is thrown when it is exceeded
+  * (default: no limit)
+  */
+  timeoutMs?: number
+}
+/**
+ * Struct to store the states of the macOS permissions required to recover windows information,
+ * permissions are always granted on other platforms
+*/
+export interface PermissionStatus {
+  /**
+  * Screen Recording permission, required for titles of other applications and captures
+  */
+  screenRecording: boolean
+  /**
+  * Accessibility permission, required for content language and accessibility identifiers
+  */
+  accessibility: boolean
+}
+/**
+ * Struct to store options of the polling thread shared by active window subscriptions
+*/
+export interface PollingOptions {
+  /**
+  * Wait between two polls in milliseconds when `adaptive` is not set (default: `100`)
+  */
+  intervalMs?: number
+  /**
+  * Poll every `minIntervalMs` right after a change of the active window, then multiply the wait by
+  * `backoffFactor` after each poll without change up to `maxIntervalMs` (default: `false`)
+  */
+  adaptive?: boolean
+  /**
+  * Shortest wait between two polls in milliseconds in adaptive mode (default: `50`)
+  */
+  minIntervalMs?: number
+  /**
+  * Longest wait between two polls in milliseconds in adaptive mode (default: `1000`)
+  */
+  maxIntervalMs?: number
+  /**
+  * Factor applied to the wait after each poll without change in adaptive mode, at least `1` (default: `1.5`)
+  */
+  backoffFactor?: number
+}
 /**
  * Struct to store process information of the window
 */
@@ -19,15 +253,148 @@ export interface ProcessInfo {
   path: string
   name: string
   execName: string
+  /**
+  * Arguments used to launch the process separated by spaces, empty when the process is not readable
+  */
+  commandLine: string
+  /**
+  * Name of the user owning the process, empty when the process cannot be inspected
+  */
+  user: string
+  /**
+  * Start time of the process as a Unix timestamp in milliseconds, `0` when unavailable
+  */
+  startTime: number
+  /**
+  * Bundle identifier of the application (e.g. `com.microsoft.VSCode`), only set on macOS
+  */
+  bundleId: string
+  /**
+  * Localized display name of the application, only set on macOS
+  */
+  appName: string
+  /**
+  * Instance name of WM_CLASS (e.g. `Navigator` for Firefox), `name` holds its class name, only set on X11
+  */
+  instanceName: string
+}
+/**
+ * Struct to store the raw pixels of a window captured with the `rgba` format, 8 bits RGBA rows from top to bottom
+*/
+export interface RawImage {
+  width: number
+  height: number
+  /**
+  * Number of bytes of a row in `data`
+  */
+  stride: number
+  data: Buffer
+}
+/**
+ * Struct to store options used by active window subscriptions
+*/
+export interface SubscribeOptions {
+  /**
+  * Only call the callback once the active window has been focused for this duration in milliseconds (default: `0`)
+  */
+  debounceMs?: number
+  /**
+  * Only call the callback once the title of the active window has been stable for this duration in milliseconds,
+  * focus changes still use `debounceMs` (default: `debounceMs`)
+  */
+  titleChangeDebounceMs?: number
+  /**
+  * Keep the last titles of each active window, up to this number of entries per window,
+  * and set them in `titleHistory` of the emitted window (default: `0`, disabled)
+  */
+  titleHistoryLength?: number
+  /**
+  * Only call the callback when the executable or application name of the new active window is one of them
+  * (case-insensitive)
+  */
+  processNames?: Array<string>
+  /**
+  * Only call the callback when the title of the new active window matches this regular expression,
+  * an invalid expression throws an error at subscription time
+  */
+  titleRegex?: string
+  /**
+  * Call the callback with the current active window as a `focus` change on the first poll after subscription,
+  * without waiting for `debounceMs` (default: `true`)
+  */
+  emitInitial?: boolean
+  /**
+  * Fields of the active window triggering the callback when they change, among `id`, `process`, `title` and
+  * `position` (default: `["id", "process", "title"]`), an unknown field throws an error at subscription time
+  */
+  watchedFields?: Array<string>
+}
+/**
+ * Struct to store a title taken by a window, `timestamp` is the time in milliseconds since the Unix epoch
+ * when the title has been seen first
+*/
+export interface TitleEntry {
+  timestamp: number
+  title: string
+}
+/**
+ * Struct to store options used by `findWindowsByTitle`
+*/
+export interface TitleMatchOptions {
+  /**
+  * Compare titles ignoring case (default: `false`)
+  */
+  caseInsensitive?: boolean
+  /**
+  * Only match titles equal to the query instead of titles containing it (default: `false`)
+  */
+  exact?: boolean
+}
+/**
+ * Struct to store the outcome of `tryActiveWindow`, `value` is set when `ok` is `true` and `error` otherwise
+*/
+export interface ActiveWindowResult {
+  ok: boolean
+  value?: WindowInfo
+  error?: string
+}
+/**
+ * Struct to store the outcome of `tryOpenWindows`, `value` is set when `ok` is `true` and `error` otherwise
+*/
+export interface OpenWindowsResult {
+  ok: boolean
+  value?: Array<WindowInfo>
+  error?: string
 }
 /**
  * Struct to store usage data of the window
 */
 export interface UsageInfo {
   memory: number
+  /**
+  * CPU usage of the process in percent of one core since the previous poll of `subscribeActiveWindow`,
+  * `0` for one-shot calls which can't compute a rate from a single sample
+  */
+  cpu: number
+}
+/**
+ * Struct to store the PNG thumbnail of a window captured by `captureWindows`
+*/
+export interface WindowCapture {
+  id: number
+  image: Buffer
+}
+/**
+ * Struct to store a change of the open windows (`opened` or `closed`)
+*/
+export interface WindowEvent {
+  kind: 'opened' | 'closed'
+  window: WindowInfo
 }
 /**
- * Struct to store position and size of the window
+ * Struct to store position and size of the window.
+ * Values are physical pixels on Windows and X11, logical points on macOS and stage coordinates of the compositor on Wayland,
+ * use `scaleFactor` of the monitor to convert them.
 */
 export interface WindowPosition {
   x: number
@@ -36,9 +403,58 @@ export interface WindowPosition {
   height: number
   isFullScreen: boolean
 }
+/**
+ * Struct to store the raw value of a window property, only read on X11
+*/
+export interface WindowProperty {
+  /**
+  * Name of the type atom of the value (e.g. `UTF8_STRING`, `CARDINAL` or `ATOM`)
+  */
+  typeName: string
+  /**
+  * Size of the items of the value in bits, `8`, `16` or `32`
+  */
+  format: number
+  /**
+  * Bytes of the value, items of 16 and 32 bits are in the byte order of the host
+  */
+  data: Buffer
+}
+/**
+ * Struct to store the active window and the open windows recovered together
+*/
+export interface WindowSnapshot {
+  active: WindowInfo
+  windows: Array<WindowInfo>
+}
+/**
+ * Struct to store a workspace (virtual desktop) of the session, `id` matches `WorkspaceInfo.id` of its windows
+*/
+export interface Workspace {
+  id: number
+  /**
+  * Label of the workspace, `undefined` when it has not been named
+  */
+  name?: string
+  /**
+  * `true` for the workspace shown on screen
+  */
+  isCurrent: boolean
+}
+/**
+ * Struct to store the workspace (virtual desktop) of the window.
+ * `id` is `-1` and `name` is `undefined` for sticky windows shown on all workspaces.
+*/
+export interface WorkspaceInfo {
+  id: number
+  name?: string
+}
 /**
  * Retrieve information the about currently active window.
  * Returns an object of `WindowInfo`.
+ * Use `options.includeUrl` to recover the url of supported browsers.
+ * Repeated calls with the same options within 16ms return the same window (see `setActiveWindowCacheTtl`),
+ * use `options.forceRefresh` to recover it again.
  *
  * # Example
  *
@@ -64,10 +480,42 @@ export interface WindowPosition {
  *
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
 */
-export declare function activeWindow(): WindowInfo
+export declare function activeWindow(options?: ActiveWindowOptions | undefined | null): WindowInfo
+/**
+ * Same as `activeWindow` but never throws, the failure (missing permission, no display...) is returned
+ * as `{ ok: false, error }` and the window as `{ ok: true, value }`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { tryActiveWindow } from '@miniben90/x-win';
+ *
+ * const result = tryActiveWindow();
+ * if (result.ok) {
+ *   console.log(result.value.title);
+ * } else {
+ *   console.error(result.error);
+ * }
+ * ```
+*/
+export declare function tryActiveWindow(options?: ActiveWindowOptions | undefined | null): { ok: true, value: WindowInfo } | { ok: false, error: string }
+/**
+ * Change how long `activeWindow` returns the same window to repeated calls, in milliseconds (default: `16`).
+ * `0` disables the cache, use `forceRefresh` of `activeWindow` to bypass it for one call.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { setActiveWindowCacheTtl } from '@miniben90/x-win';
+ *
+ * setActiveWindowCacheTtl(50);
+ * ```
+*/
+export declare function setActiveWindowCacheTtl(ttlMs: number): void
 /**
  * Retrieve information about the currently active window as a promise.
  * Returns an object of `WindowInfo`.
+ * Use `options.includeUrl` to recover the url of supported browsers.
  *
  * # Example
  *
@@ -91,14 +539,147 @@ export declare function activeWindow(): WindowInfo
  * });
  * ```
  *
- * # Information about Electron
+ * # Information about Electron
+ *
+ * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
+*/
+export declare function activeWindowAsync(options?: ActiveWindowOptions | undefined | null): Promise<WindowInfo>
+/**
+ * Retrieve only the id of the active window, `0` when no window is focused.
+ * Lighter than `activeWindow` to detect focus changes in a tight polling loop,
+ * title, process and memory are not recovered on X11, Windows and macOS (the whole active window is on Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getActiveWindowId } from '@miniben90/x-win';
+ *
+ * let currentId = getActiveWindowId();
+ * setInterval(() => {
+ *   const id = getActiveWindowId();
+ *   if (id !== currentId) {
+ *     currentId = id;
+ *     console.log(activeWindow());
+ *   }
+ * }, 100);
+ * ```
+*/
+export declare function getActiveWindowId(): number
+/**
+ * Check if the active window is full screen, e.g. to pause notifications during a presentation.
+ * Reads `_NET_WM_STATE_FULLSCREEN` of the active window on X11, checks that the foreground window covers
+ * its whole monitor without caption on Windows and compares the frontmost window with the screen on macOS.
+ * Return `false` when no window is focused.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { isFullscreenActive } from '@miniben90/x-win';
+ *
+ * if (isFullscreenActive()) {
+ *   console.log('Do not disturb');
+ * }
+ * ```
+*/
+export declare function isFullscreenActive(): boolean
+/**
+ * Retrieve the active window and compare it with `previous` the same way as `subscribeActiveWindow` without debounce.
+ * Return the new active window when the focus, the process or the title changed, `null` otherwise.
+ * Allows to detect changes from an existing timer or event loop without a dedicated thread.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, activeWindowChanged } from '@miniben90/x-win';
+ *
+ * let current = activeWindow();
+ * setInterval(() => {
+ *   const changed = activeWindowChanged(current);
+ *   if (changed) {
+ *     current = changed;
+ *     console.log(current);
+ *   }
+ * }, 100);
+ * ```
+*/
+export declare function activeWindowChanged(previous: WindowInfo): WindowInfo | null
+/**
+ * Retrieve the window focused before the current active window, e.g. to switch back to the last window.
+ * Focus changes are recorded by every recovery of the active window in this process (`activeWindow`,
+ * `subscribeActiveWindow` polls, ...), the most recent window distinct from the current one is returned.
+ * Return `null` when no other window has been recovered, the window can have been closed since.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getPreviousActiveWindow, subscribeActiveWindow } from '@miniben90/x-win';
+ *
+ * subscribeActiveWindow(() => {});
+ * const previous = getPreviousActiveWindow();
+ * console.log(previous?.title);
+ * ```
+*/
+export declare function getPreviousActiveWindow(): WindowInfo | null
+/**
+ * Retrieve the frontmost window intersecting the monitor at `monitorIndex`, following the order of the monitors list.
+ * Return `null` when no window overlaps this monitor or when the monitor does not exist (always the case on Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindowForDisplay } from '@miniben90/x-win';
+ *
+ * const windowOnSecondMonitor = activeWindowForDisplay(1);
+ * console.log(windowOnSecondMonitor);
+ * ```
+*/
+export declare function activeWindowForDisplay(monitorIndex: number): WindowInfo | null
+/**
+ * Retrieve the connected monitors with their position, size and `scaleFactor`.
+ * It will use RandR on X11, `EnumDisplayMonitors` on Windows and the CoreGraphics displays on macOS,
+ * an empty array is returned on Wayland.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getMonitors } from '@miniben90/x-win';
+ *
+ * const primary = getMonitors().find((monitor) => monitor.isPrimary);
+ * ```
+*/
+export declare function getMonitors(): Array<MonitorInfo>
+/**
+ * Retrieve the workspaces (virtual desktops) of the session in their display order, the current one has `isCurrent` set.
+ * It will use `_NET_NUMBER_OF_DESKTOPS`, `_NET_DESKTOP_NAMES` and `_NET_CURRENT_DESKTOP` on X11 and the virtual desktops
+ * of explorer on Windows, an empty array is returned on macOS and Wayland.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getWorkspaces } from '@miniben90/x-win';
+ *
+ * const current = getWorkspaces().find((workspace) => workspace.isCurrent);
+ * ```
+*/
+export declare function getWorkspaces(): Array<Workspace>
+/**
+ * Retrieve the bounds of the virtual desktop, the combined extent of all monitors.
+ * `x` and `y` are negative when a monitor is placed left of or above the primary monitor.
+ *
+ * # Example
  *
- * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
+ * ```typescript
+ * import { getDesktopBounds } from '@miniben90/x-win';
+ *
+ * const { x, y, width, height } = getDesktopBounds();
+ * console.log(x, y, width, height);
+ * ```
 */
-export declare function activeWindowAsync(): Promise<WindowInfo>
+export declare function getDesktopBounds(): WindowPosition
 /**
  * Retrieve information about the currently open windows.
  * Returns an array of `WindowInfo`, each containing details about a specific open window.
+ * Use `options.includeUrl` to recover the url of supported browsers.
  *
  * # Example
  *
@@ -128,10 +709,27 @@ export declare function activeWindowAsync(): Promise<WindowInfo>
  *
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
 */
-export declare function openWindows(): Array<WindowInfo>
+export declare function openWindows(options?: OpenWindowsOptions | undefined | null): Array<WindowInfo>
+/**
+ * Same as `openWindows` but never throws, the failure (missing permission, no display...) is returned
+ * as `{ ok: false, error }` and the windows as `{ ok: true, value }`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { tryOpenWindows } from '@miniben90/x-win';
+ *
+ * const result = tryOpenWindows();
+ * const windows = result.ok ? result.value : [];
+ * ```
+*/
+export declare function tryOpenWindows(options?: OpenWindowsOptions | undefined | null): { ok: true, value: Array<WindowInfo> } | { ok: false, error: string }
 /**
  * Retrieve information about the currently open windows as a promise.
  * Returns an array of `WindowInfo`, each containing details about a specific open window.
+ * Use `options.includeUrl` to recover the url of supported browsers.
+ * `onWindow` is called with each window as soon as it is gathered (during the recovery on X11 and Windows) to render
+ * long lists incrementally, the promise still resolves with the whole list.
  *
  * # Example
  *
@@ -165,9 +763,284 @@ export declare function openWindows(): Array<WindowInfo>
  *
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
 */
-export declare function openWindowsAsync(): Promise<Array<WindowInfo>>
+export declare function openWindowsAsync(options?: OpenWindowsOptions, onWindow?: (info: WindowInfo) => void): Promise<Array<WindowInfo>>
+/**
+ * Retrieve the active window and the open windows in one call, the windows are recovered once
+ * and the active one is marked with `isActive` in the list.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { snapshot } from '@miniben90/x-win';
+ *
+ * const { active, windows } = snapshot();
+ * console.log(active.title, windows.length);
+ * ```
+*/
+export declare function snapshot(): WindowSnapshot
+/**
+ * Retrieve the open windows grouped by their owning process, applications follow the order of their
+ * frontmost window in `openWindows`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { openApplications } from '@miniben90/x-win';
+ *
+ * for (const { info, frontmostWindow, windows } of openApplications()) {
+ *   console.log(info.name, frontmostWindow.title, windows.length);
+ * }
+ * ```
+*/
+export declare function openApplications(): Array<AppInfo>
+/**
+ * Retrieve information about the currently open windows owned by a process.
+ * Returns an array of `WindowInfo` in the same order as `openWindows`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getWindowsByPid } from '@miniben90/x-win';
+ *
+ * const windows = getWindowsByPid(1234);
+ * for (let i = 0; i < windows.length; i++) {
+ *   console.log(i, windows[i]);
+ * }
+ * ```
+*/
+export declare function getWindowsByPid(pid: number): Array<WindowInfo>
+/**
+ * Retrieve the environment variables of a process (e.g. `VIRTUAL_ENV`, `TERM_PROGRAM`).
+ * Only processes of the same user are readable, only the current process is supported on Windows.
+ * An empty object is returned when the environment is not accessible.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getProcessEnv } from '@miniben90/x-win';
+ *
+ * const env = getProcessEnv(activeWindow().info.processId);
+ * console.log(env.VIRTUAL_ENV);
+ * ```
+*/
+export declare function getProcessEnv(pid: number): Record<string, string>
+/**
+ * Retrieve information about the open windows with a title containing `query`, the titles are matched
+ * in Rust so only matching windows are returned to JavaScript.
+ * Use `options.caseInsensitive` to ignore case and `options.exact` to only match titles equal to `query`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { findWindowsByTitle } from '@miniben90/x-win';
+ *
+ * const windows = findWindowsByTitle('visual studio code', { caseInsensitive: true });
+ * ```
+*/
+export declare function findWindowsByTitle(query: string, options?: TitleMatchOptions | undefined | null): Array<WindowInfo>
+/**
+ * Retrieve information about the open windows matching an accessibility identifier.
+ * It will use the AX identifier on macOS (accessibility permission required) and the UIAutomation `AutomationId` on Windows.
+ * Returns an empty array on Linux.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { findWindowsByAxIdentifier } from '@miniben90/x-win';
+ *
+ * const windows = findWindowsByAxIdentifier('MainWindow');
+ * ```
+*/
+export declare function findWindowsByAxIdentifier(identifier: string): Array<WindowInfo>
+/**
+ * Capture a thumbnail of the window as PNG bytes, or as raw RGBA pixels with their `width`, `height` and `stride`
+ * when the `format` option is `'rgba'` (no encoding, the buffer can be uploaded to a texture or given to OpenCV).
+ * The image will be downscaled to fit in `maxDimension` (in pixels) when given.
+ * Throws an error when the window is minimized, occluded (X11) or cannot be captured (e.g. Wayland),
+ * screen recording permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, captureWindow } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const { id } = activeWindow();
+ * writeFileSync('thumbnail.png', captureWindow(id, 256));
+ *
+ * const { width, height, stride, data } = captureWindow(id, undefined, { format: 'rgba' });
+ * ```
+*/
+export declare function captureWindow<F extends 'png' | 'rgba' = 'png'>(id: number, maxDimension?: number | undefined | null, options?: CaptureOptions & { format?: F }): F extends 'rgba' ? RawImage : Buffer
+/**
+ * Capture a thumbnail of the window without blocking the main thread,
+ * the capture and the PNG encoding run on the thread pool of libuv.
+ * Same as `captureWindow` but returns a promise.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, captureWindowAsync } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const { id } = activeWindow();
+ * captureWindowAsync(id, 256).then(thumbnail => writeFileSync('thumbnail.png', thumbnail));
+ * ```
+*/
+export declare function captureWindowAsync<F extends 'png' | 'rgba' = 'png'>(id: number, maxDimension?: number | undefined | null, options?: CaptureOptions & { format?: F }): Promise<F extends 'rgba' ? RawImage : Buffer>
+/**
+ * Capture thumbnails of many windows as PNG bytes with a single session, faster than calling `captureWindow` in a loop.
+ * The images will be downscaled to fit in `maxDimension` (in pixels) when given.
+ * Windows that cannot be captured (minimized, occluded on X11...) are skipped, screen recording permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { captureWindows, openWindows } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const captures = captureWindows(openWindows().map(({ id }) => id), 256);
+ * for (const { id, image } of captures) {
+ *   writeFileSync(`thumbnail-${id}.png`, image);
+ * }
+ * ```
+*/
+export declare function captureWindows(ids: Array<number>, maxDimension?: number | undefined | null): Array<WindowCapture>
+/**
+ * Ask the window to close like the user would do, the application can still prompt to save changes and the process is never killed.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, closeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * closeWindow(id);
+ * ```
+*/
+export declare function closeWindow(id: number): boolean
+/**
+ * Move and resize the window, fullscreen is toggled when `isFullScreen` differs from the current state.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, setWindowPosition } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * setWindowPosition(id, { x: 0, y: 0, width: 800, height: 600, isFullScreen: false });
+ * ```
+*/
+export declare function setWindowPosition(id: number, position: WindowPosition): boolean
+/**
+ * Retrieve the position of the window only, cheaper than `openWindows` to follow a window frequently
+ * (e.g. to reposition an overlay). Return `null` when the window no longer exists.
+ * Throws an error when it is not supported (e.g. Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getWindowGeometry } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * const position = getWindowGeometry(id);
+ * ```
+*/
+export declare function getWindowGeometry(id: number): WindowPosition | null
+/**
+ * Read the raw value of a window property not modeled by `WindowInfo` (e.g. `_GTK_APPLICATION_ID`, `_STEAM_GAME`)
+ * to decode it in JavaScript. Return the bytes with the name of the type atom and the format, `null` when the property
+ * is not set or the window no longer exists. Only supported on X11, `null` is returned on Wayland and other platforms.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getWindowProperty } from '@miniben90/x-win';
+ *
+ * const property = getWindowProperty(activeWindow().id, '_GTK_APPLICATION_ID');
+ * if (property?.typeName === 'UTF8_STRING') {
+ *   console.log(property.data.toString('utf8'));
+ * }
+ * ```
+*/
+export declare function getWindowProperty(id: number, name: string): WindowProperty | null
+/**
+ * Minimize the window.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, minimizeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * minimizeWindow(id);
+ * ```
+*/
+export declare function minimizeWindow(id: number): boolean
+/**
+ * Maximize the window.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, maximizeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * maximizeWindow(id);
+ * ```
+*/
+export declare function maximizeWindow(id: number): boolean
+/**
+ * Restore the minimized or maximized window to its normal state.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, restoreWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * restoreWindow(id);
+ * ```
+*/
+export declare function restoreWindow(id: number): boolean
+/**
+ * Retrieve the topmost window under the mouse cursor.
+ * Return `null` when the cursor is over the desktop, throws an error when it is not supported (e.g. Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { windowUnderCursor } from '@miniben90/x-win';
+ *
+ * const hoveredWindow = windowUnderCursor();
+ * console.log(hoveredWindow);
+ * ```
+*/
+export declare function windowUnderCursor(): WindowInfo | null
 /**
- * Subscribe an observer thread to monitor changes in the active window.
+ * Subscribe an observer to monitor changes in the active window, the active window is polled by a single thread
+ * shared by all observers after each change notified by the X server on Linux and every 100ms otherwise
+ * (see `setActiveWindowPolling`).
+ * The callback receives the new active window and the kind of change (`focus`, `title`, `process` or `position`
+ * when it is watched).
+ * The first successfully polled window is emitted as a `focus` change without debounce unless `emitInitial` is `false`.
+ * When the active window can't be recovered (e.g. lost connection to the X server) the callback is called once
+ * with an empty window, the `error` change and the error message, polls are retried with a backoff until
+ * the active window is recovered and emitted again as a `focus` change.
+ * Use `debounceMs` and `titleChangeDebounceMs` options to only be notified once the active window
+ * or its title has been stable for the duration. Set `titleHistoryLength` to receive the recent titles
+ * of the window in `titleHistory`. Use `processNames` and `titleRegex` to only be notified when the new
+ * active window matches, an invalid `titleRegex` throws an error.
  *
  * # Example
  *
@@ -185,6 +1058,9 @@ export declare function openWindowsAsync(): Promise<Array<WindowInfo>>
  * const c = subscribeActiveWindow((info) => {
  *   t.log(c, info);
  * });
+ * const d = subscribeActiveWindow((info) => {
+ *   t.log(d, info);
+ * }, { debounceMs: 300, titleChangeDebounceMs: 1000 });
  *
  * unsubscribeAllActiveWindow();
  * ```
@@ -203,14 +1079,39 @@ export declare function openWindowsAsync(): Promise<Array<WindowInfo>>
  * const c = subscribeActiveWindow((info) => {
  *   t.log(c, info);
  * });
+ * const d = subscribeActiveWindow((info) => {
+ *   t.log(d, info);
+ * }, { debounceMs: 300, titleChangeDebounceMs: 1000 });
  *
  * unsubscribeAllActiveWindow();
  * ```
  *
 */
-export declare function subscribeActiveWindow(callback: (info: WindowInfo) => void): number
+export declare function subscribeActiveWindow(callback: (info: WindowInfo, change: 'focus' | 'title' | 'process' | 'position' | 'error', error?: string) => void, options?: SubscribeOptions): number
+/**
+ * Subscribe an observer appending each change of the active window to the file at `path` as a line of JSON
+ * (newline-delimited JSON), written from the polling thread without calling JavaScript.
+ * Each line holds the `timestamp` in milliseconds since the Unix epoch, the kind of `change` and the `window`
+ * with the same fields as `WindowInfo` (`undefined` fields left out), `error` changes hold the `error` message too.
+ * The file is created when it does not exist and is never truncated or rotated, use `/dev/stdout` or `/dev/fd/<fd>`
+ * to write to a file descriptor on Linux and macOS.
+ * Options are the same as `subscribeActiveWindow`, an error is thrown when the file can't be opened.
+ * Return the id of the observer to use with `unsubscribeActiveWindow`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { subscribeActiveWindowToFile, unsubscribeActiveWindow } from '@miniben90/x-win';
+ *
+ * const id = subscribeActiveWindowToFile('/tmp/active-windows.jsonl', { debounceMs: 300 });
+ *
+ * unsubscribeActiveWindow(id);
+ * ```
+*/
+export declare function subscribeActiveWindowToFile(path: string, options?: SubscribeOptions | undefined | null): number
 /**
  * Terminate and unsubscribe a specific observer using their ID.
+ * Return `false` when no observer is subscribed with this ID (e.g. already unsubscribed).
  *
  * # Example
  *
@@ -254,9 +1155,9 @@ export declare function subscribeActiveWindow(callback: (info: WindowInfo) => vo
  * unsubscribeActiveWindow(c);
  * ```
 */
-export declare function unsubscribeActiveWindow(threadId: number): void
+export declare function unsubscribeActiveWindow(threadId: number): boolean
 /**
- * Terminate and unsubscribe all observer threads monitoring changes in the active window.
+ * Unsubscribe all observers monitoring changes in the active window.
  *
  * # Example
  *
@@ -297,12 +1198,82 @@ export declare function unsubscribeActiveWindow(threadId: number): void
  * ```
 */
 export declare function unsubscribeAllActiveWindow(): void
+/**
+ * Unsubscribe all observers and wait until the polling thread is finished, so no callback is called once it returns
+ * (e.g. before an Electron renderer reloads or the module is required again).
+ * New subscriptions can be started afterwards and it is safe to call it multiple times.
+ * Return `false` when the polling thread did not finish within a second.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { shutdown } from '@miniben90/x-win';
+ *
+ * window.addEventListener('beforeunload', () => shutdown());
+ * ```
+*/
+export declare function shutdown(): boolean
+/**
+ * Get the number of observers subscribed with `subscribeActiveWindow` and not unsubscribed yet.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindowSubscriptionCount, unsubscribeAllActiveWindow } from '@miniben90/x-win';
+ *
+ * unsubscribeAllActiveWindow();
+ * console.log(activeWindowSubscriptionCount()); // 0
+ * ```
+*/
+export declare function activeWindowSubscriptionCount(): number
+/**
+ * Change how often the thread shared by active window subscriptions polls the active window (default: every `100ms`).
+ * With `adaptive`, polls are done every `minIntervalMs` right after a change of the active window, its title or its
+ * position (the user is likely switching windows or dragging one), then the wait is multiplied by `backoffFactor` after each poll without change up to
+ * `maxIntervalMs`, reducing wakeups while the focus is stable.
+ * Pending `debounceMs` changes are checked every `minIntervalMs`. The running thread uses the new options after its
+ * current wait, they also apply to the polls done when the events of the X server are not available on Linux.
+ * An error is thrown for invalid options (e.g. `minIntervalMs` greater than `maxIntervalMs`).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { setActiveWindowPolling } from '@miniben90/x-win';
+ *
+ * setActiveWindowPolling({ adaptive: true, minIntervalMs: 50, maxIntervalMs: 2000, backoffFactor: 2 });
+ * ```
+*/
+export declare function setActiveWindowPolling(options: PollingOptions): void
+/**
+ * Return the states of the permissions required to recover titles, urls and captures without prompting the user.
+ * On macOS titles and captures are silently empty without Screen Recording permission, this allows to tell
+ * a permission wall from a genuine empty result. Permissions are always granted on other platforms.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { checkPermissions } from '@miniben90/x-win';
+ *
+ * const { screenRecording, accessibility } = checkPermissions();
+ * ```
+*/
+export declare function checkPermissions(): PermissionStatus
+/**
+ * Show the system prompts of the macOS permissions not granted yet and return their current states,
+ * the application has to be restarted once a permission is granted. Nothing is prompted on other platforms.
+*/
+export declare function requestPermissions(): PermissionStatus
 /**
  * Install "@mininben90/x-win" Gnome extension required for Linux using Gnome > 41.
  * This function will write extension files needed to correctly detect working windows with Wayland desktop environment.
- * **Restart session will be require to install the gnome extension.**
+ * Return `ExtensionInstallInfo` with the extension folder, `installed` is `false` on X11 sessions which don't need the extension.
+ * An error describing the detected version is thrown when the running Gnome shell can't load the extension.
+ * **Restart session will be require to install the gnome extension** when `needsReload` is `true`.
+ * With `options.dryRun` nothing is written, the planned `files` are returned with `applicable` telling if the
+ * session needs the extension and `needsReload` if files would be written. A dry run never throws where the
+ * extension is not applicable (other platforms, Wayland without Gnome shell).
 */
-export declare function installExtension(): boolean
+export declare function installExtension(options?: ExtensionInstallOptions | undefined | null): ExtensionInstallInfo
 /**
  * Uninstall "@mininben90/x-win" Gnome extension.
  * This function will disable and remove extension files.
@@ -319,6 +1290,45 @@ export declare function enableExtension(): boolean
  * This function will disable extension needed to correctly detect working windows with Wayland desktop environment.
 */
 export declare function disableExtension(): boolean
+/**
+ * Return a JSON dump of the environment (os, session type, window manager, permissions, monitors and windows) to join with bug reports.
+ * Use `redactTitles` to replace window titles by `<redacted>`.
+ *
+ * # Example
+ * ```javascript
+ * import { diagnostics } from '@miniben90/x-win';
+ *
+ * console.log(diagnostics(true));
+ * ```
+*/
+export declare function diagnostics(redactTitles?: boolean | undefined | null): string
+/**
+ * Return the name of the running window manager (e.g. `Mutter`, `KWin`, `i3`), `""` when it can't be recovered.
+ * Read from `_NET_WM_NAME` of the `_NET_SUPPORTING_WM_CHECK` window on Linux.
+ *
+ * # Example
+ * ```javascript
+ * import { getWindowManagerName } from '@miniben90/x-win';
+ *
+ * console.log(getWindowManagerName());
+ * ```
+*/
+export declare function getWindowManagerName(): string
+/**
+ * Stateful watcher of open windows, each instance keeps its own previous state
+ * so several consumers can track changes independently.
+*/
+export declare class WindowWatcher {
+  /**
+  * Create a watcher from the current open windows.
+  * A window must be missing for `closeGracePeriod` milliseconds (default: `0`) before being reported as closed.
+  */
+  constructor(closeGracePeriod?: number | undefined | null)
+  /**
+  * Return windows opened and closed since the last call (or the creation of the watcher).
+  */
+  poll(): Array<WindowEvent>
+}
 /**
  * Struct to store all informations of the window
 */
@@ -330,7 +1340,88 @@ export declare class WindowInfo {
   info: ProcessInfo
   usage: UsageInfo
   url: string
-  constructor(id: number, os: string, title: string, position: WindowPosition, info: ProcessInfo, usage: UsageInfo, url: string)
+  zOrder: number
+  onExternalDisplay?: boolean
+  workspace?: WorkspaceInfo
+  contentLanguage?: string
+  /**
+  * Monitor containing the center of the window, `undefined` when unknown
+  */
+  monitor?: MonitorInfo
+  /**
+  * `true` for the window owning the input focus (`GetInputFocus` on X11), the active window on other platforms.
+  * It can differ from `isActive` on X11 (e.g. focus-follows-mouse), at most one window of `openWindows` has it set
+  */
+  isFocused: boolean
+  /**
+  * `true` for the active window (`_NET_ACTIVE_WINDOW` on X11, foreground window on Windows, key window of the
+  * frontmost application on macOS), at most one window of `openWindows` has it set
+  */
+  isActive: boolean
+  /**
+  * Opacity of the window from `0` (transparent) to `1` (opaque), `1` when unknown
+  */
+  opacity: number
+  /**
+  * Id of the window this one is transient for (`WM_TRANSIENT_FOR` on X11, owner window on Windows),
+  * `undefined` for top-level windows or when unknown
+  */
+  parentId?: number
+  /**
+  * `true` when the window is minimized (`_NET_WM_STATE_HIDDEN` or iconic `WM_STATE` on X11),
+  * minimized windows are not listed on macOS
+  */
+  isMinimized: boolean
+  /**
+  * `true` when the window is mapped (`Viewable` map state on X11, `IsWindowVisible` on Windows, on screen on macOS),
+  * mapped windows can still be off-screen or covered. Always `true` on Wayland where the state is not exposed
+  */
+  isVisible: boolean
+  /**
+  * Role of the window from `WM_WINDOW_ROLE` on X11 (e.g. `browser` or `pop-up`) to match windows across sessions,
+  * empty when not set or on other platforms
+  */
+  role: string
+  /**
+  * Focused UI element of the window, only recovered with `includeFocusedElement` of `activeWindow`,
+  * `undefined` when accessibility is unavailable
+  */
+  focusedElement?: FocusedElement
+  /**
+  * Visible bounds of the window including the decorations of the window manager (title bar and borders),
+  * `position` keeps the client bounds. `undefined` when the frame is unknown
+  */
+  frame?: WindowPosition
+  /**
+  * Window pinned above the other windows: `_NET_WM_STATE_ABOVE` on X11, `WS_EX_TOPMOST` on Windows
+  * and a window level above the normal one on macOS
+  */
+  alwaysOnTop: boolean
+  /**
+  * Window kept below the other windows with `_NET_WM_STATE_BELOW` on X11, always `false` on other platforms
+  */
+  alwaysOnBottom: boolean
+  /**
+  * Raw platform handle of the window for interop with other native libraries: the XID on X11, the `HWND` value
+  * on Windows and the `CGWindowID` on macOS, `0` when not available (e.g. Wayland). Handles fit in a safe integer
+  * on every platform (`HWND` values only use their lower 32 bits)
+  */
+  nativeHandle: number
+  /**
+  * Best-effort identity of the window preserved across title changes, hashed from the window class,
+  * the role and the process id with its start time. Windows of a process sharing the same class and role share it
+  */
+  stableId: string
+  /**
+  * Time in milliseconds the previous window has been focused, only set by `subscribeActiveWindow` on `focus` and
+  * `process` changes once a previous focus change is known (accurate to the poll interval)
+  */
+  previousFocusDurationMs?: number
+  /**
+  * Recent titles of the window, oldest first, only set by `subscribeActiveWindow` when `titleHistoryLength` is set
+  */
+  titleHistory?: Array<TitleEntry>
+  constructor(id: number, os: string, title: string, position: WindowPosition, info: ProcessInfo, usage: UsageInfo, url: string, zOrder: number, onExternalDisplay?: boolean, workspace?: WorkspaceInfo, contentLanguage?: string, monitor?: MonitorInfo, isFocused: boolean, isActive: boolean, opacity: number, parentId?: number, isMinimized: boolean, isVisible: boolean, role: string, focusedElement?: FocusedElement, frame?: WindowPosition, alwaysOnTop: boolean, alwaysOnBottom: boolean, nativeHandle: number, stableId: string, previousFocusDurationMs?: number, titleHistory?: Array<TitleEntry>)
   /**
   * Funciton who help to recover icon of application and will return `IconInfo`.
   */
@@ -339,4 +1430,10 @@ export declare class WindowInfo {
   * Promise funciton who help to recover icon of application and will return `IconInfo`.
   */
   getIconAsync(): Promise<IconInfo>
+  /**
+  * Resolve the freedesktop `.desktop` entry of the application to get its display name and icon name,
+  * matched with `StartupWMClass`, the desktop file id or the program of `Exec` (Linux only).
+  * Return empty names on other platforms and when no entry matches.
+  */
+  getDesktopEntry(): DesktopEntry
 }
@@ -310,17 +310,54 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { WindowInfo, activeWindow, activeWindowAsync, openWindows, openWindowsAsync, subscribeActiveWindow, unsubscribeActiveWindow, unsubscribeAllActiveWindow, installExtension, uninstallExtension, enableExtension, disableExtension } = nativeBinding
+const { WindowWatcher, WindowInfo, activeWindow, tryActiveWindow, setActiveWindowCacheTtl, activeWindowAsync, getActiveWindowId, isFullscreenActive, activeWindowChanged, getPreviousActiveWindow, activeWindowForDisplay, getMonitors, getWorkspaces, getDesktopBounds, openWindows, tryOpenWindows, openWindowsAsync, snapshot, openApplications, getWindowsByPid, getProcessEnv, findWindowsByTitle, findWindowsByAxIdentifier, captureWindow, captureWindowAsync, captureWindows, closeWindow, setWindowPosition, getWindowGeometry, getWindowProperty, minimizeWindow, maximizeWindow, restoreWindow, windowUnderCursor, subscribeActiveWindow, subscribeActiveWindowToFile, unsubscribeActiveWindow, unsubscribeAllActiveWindow, shutdown, activeWindowSubscriptionCount, setActiveWindowPolling, checkPermissions, requestPermissions, installExtension, uninstallExtension, enableExtension, disableExtension, diagnostics, getWindowManagerName } = nativeBinding
 
+module.exports.WindowWatcher = WindowWatcher
 module.exports.WindowInfo = WindowInfo
 module.exports.activeWindow = activeWindow
+module.exports.tryActiveWindow = tryActiveWindow
+module.exports.setActiveWindowCacheTtl = setActiveWindowCacheTtl
 module.exports.activeWindowAsync = activeWindowAsync
+module.exports.getActiveWindowId = getActiveWindowId
+module.exports.isFullscreenActive = isFullscreenActive
+module.exports.activeWindowChanged = activeWindowChanged
+module.exports.getPreviousActiveWindow = getPreviousActiveWindow
+module.exports.activeWindowForDisplay = activeWindowForDisplay
+module.exports.getMonitors = getMonitors
+module.exports.getWorkspaces = getWorkspaces
+module.exports.getDesktopBounds = getDesktopBounds
 module.exports.openWindows = openWindows
+module.exports.tryOpenWindows = tryOpenWindows
 module.exports.openWindowsAsync = openWindowsAsync
+module.exports.snapshot = snapshot
+module.exports.openApplications = openApplications
+module.exports.getWindowsByPid = getWindowsByPid
+module.exports.getProcessEnv = getProcessEnv
+module.exports.findWindowsByTitle = findWindowsByTitle
+module.exports.findWindowsByAxIdentifier = findWindowsByAxIdentifier
+module.exports.captureWindow = captureWindow
+module.exports.captureWindowAsync = captureWindowAsync
+module.exports.captureWindows = captureWindows
+module.exports.closeWindow = closeWindow
+module.exports.setWindowPosition = setWindowPosition
+module.exports.getWindowGeometry = getWindowGeometry
+module.exports.getWindowProperty = getWindowProperty
+module.exports.minimizeWindow = minimizeWindow
+module.exports.maximizeWindow = maximizeWindow
+module.exports.restoreWindow = restoreWindow
+module.exports.windowUnderCursor = windowUnderCursor
 module.exports.subscribeActiveWindow = subscribeActiveWindow
+module.exports.subscribeActiveWindowToFile = subscribeActiveWindowToFile
 module.exports.unsubscribeActiveWindow = unsubscribeActiveWindow
 module.exports.unsubscribeAllActiveWindow = unsubscribeAllActiveWindow
+module.exports.shutdown = shutdown
+module.exports.activeWindowSubscriptionCount = activeWindowSubscriptionCount
+module.exports.setActiveWindowPolling = setActiveWindowPolling
+module.exports.checkPermissions = checkPermissions
+module.exports.requestPermissions = requestPermissions
 module.exports.installExtension = installExtension
 module.exports.uninstallExtension = uninstallExtension
 module.exports.enableExtension = enableExtension
 module.exports.disableExtension = disableExtension
+module.exports.diagnostics = diagnostics
+module.exports.getWindowManagerName = getWindowManagerName
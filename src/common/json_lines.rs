@@ -0,0 +1,67 @@
+#![deny(unused_imports)]
+
+use std::{
+  fs::{File, OpenOptions},
+  io::{self, Write},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use super::x_win_struct::window_info::WindowInfo;
+
+/**
+ * Line written for each change, with the same fields as the arguments of the subscription callback
+ */
+#[derive(Serialize)]
+struct ActiveWindowEvent<'a> {
+  /**
+   * Time in milliseconds since the Unix epoch when the change has been emitted
+   */
+  timestamp: i64,
+  change: &'a str,
+  window: &'a WindowInfo,
+  /**
+   * Message of the error for `error` changes
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<&'a str>,
+}
+
+/**
+ * Append active window changes to a file as newline-delimited JSON
+ */
+pub struct JsonLinesWriter {
+  file: File,
+}
+
+impl JsonLinesWriter {
+  /**
+   * Open the file in append mode, it is created when it does not exist
+   */
+  pub fn open(path: &str) -> io::Result<Self> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(Self { file })
+  }
+
+  pub fn write(
+    &mut self,
+    window_info: &WindowInfo,
+    change: &str,
+    error: Option<&str>,
+  ) -> io::Result<()> {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_millis() as i64)
+      .unwrap_or(0);
+    let mut line = serde_json::to_vec(&ActiveWindowEvent {
+      timestamp,
+      change,
+      window: window_info,
+      error,
+    })?;
+    line.push(b'\n');
+    // A single write keeps lines of concurrent writers of the same file whole
+    self.file.write_all(&line)
+  }
+}
@@ -1,4 +1,6 @@
 #![deny(unused_imports)]
 
+pub mod json_lines;
 pub mod thread;
+pub mod window_watcher;
 pub mod x_win_struct;
@@ -1,72 +1,446 @@
 #![deny(unused_imports)]
 
 use std::{
-  collections::HashMap,
-  sync::{mpsc, Arc, Mutex},
+  collections::BTreeMap,
+  panic::{catch_unwind, AssertUnwindSafe},
+  sync::{mpsc, Arc, Condvar, Mutex},
   thread,
+  time::Duration,
 };
 
-pub struct ThreadManager {
-  id: Arc<Mutex<u32>>,
-  threads: Arc<Mutex<HashMap<u32, mpsc::Sender<()>>>>,
+use x_win::{lock_unpoisoned, ChangeNotifier};
+
+/**
+ * Longest wait between two polls while they keep failing
+ */
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/**
+ * Called with every polled value or error, return `true` while a change is pending (e.g. debounced)
+ * so the next poll is not delayed until the next change notification
+ */
+type Subscriber<T, E> = Box<dyn FnMut(Result<&T, &E>) -> bool + Send>;
+
+/**
+ * Subscriber with its own lock so it is called without locking the subscriptions
+ */
+type SharedSubscriber<T, E> = Arc<Mutex<Subscriber<T, E>>>;
+
+/**
+ * Message sent to the polling thread
+ */
+enum PollerMessage {
+  /**
+   * Poll right away instead of waiting for the next change or interval, e.g. for the initial value of a new subscriber
+   */
+  Poll,
+  Stop,
+}
+
+/**
+ * Wait between two successful polls
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PollInterval {
+  Fixed(Duration),
+  /**
+   * `min` right after a change (or while a change is pending), then multiplied by `backoff_factor`
+   * after each poll without change up to `max`
+   */
+  Adaptive {
+    min: Duration,
+    max: Duration,
+    backoff_factor: f64,
+  },
+}
+
+impl PollInterval {
+  /**
+   * Interval following `current` after a poll, `changed` when the polled value changed or a change is pending
+   */
+  fn next(&self, current: Duration, changed: bool) -> Duration {
+    match *self {
+      PollInterval::Fixed(interval) => interval,
+      PollInterval::Adaptive {
+        min,
+        max,
+        backoff_factor,
+      } => {
+        if changed {
+          min
+        } else {
+          Duration::try_from_secs_f64(current.as_secs_f64() * backoff_factor)
+            .unwrap_or(max)
+            .clamp(min, max)
+        }
+      }
+    }
+  }
+}
+
+struct Subscriptions<T, E> {
+  next_id: u32,
+  subscribers: BTreeMap<u32, SharedSubscriber<T, E>>,
+  /**
+   * Sender waking or stopping the polling thread, `None` when it is not started
+   */
+  poller: Option<mpsc::Sender<PollerMessage>>,
 }
 
-impl ThreadManager {
-  pub fn new() -> Self {
+/**
+ * Share a single polling thread between all subscriptions, the value is polled once per tick and passed
+ * to every subscriber in subscription order (a panicking subscriber is removed). The thread is started with the first subscription and
+ * stopped with the last one. Errors are passed to subscribers too and the next polls are retried with
+ * a backoff (the interval doubled after each failure up to `MAX_RETRY_INTERVAL`) until a poll succeeds.
+ * With a change notifier, the next poll waits for a change reported by the display server instead of the
+ * interval while no subscriber has a pending change, polls are timed when the notifier is not available.
+ * With an adaptive interval, changes between two polled values are detected with `is_change`.
+ */
+pub struct ThreadManager<T, E> {
+  poll: fn() -> Result<T, E>,
+  interval: Arc<Mutex<PollInterval>>,
+  is_change: fn(&T, &T) -> bool,
+  change_notifier: Option<fn() -> Option<ChangeNotifier>>,
+  subscriptions: Arc<Mutex<Subscriptions<T, E>>>,
+  /**
+   * Number of polling threads not finished yet, stopped threads included
+   */
+  running: Arc<(Mutex<u32>, Condvar)>,
+}
+
+/**
+ * Decrease the number of running threads when the polling thread ends, even on panic
+ */
+struct RunningGuard(Arc<(Mutex<u32>, Condvar)>);
+
+impl Drop for RunningGuard {
+  fn drop(&mut self) {
+    let (count, finished) = &*self.0;
+    *lock_unpoisoned(count) -= 1;
+    finished.notify_all();
+  }
+}
+
+impl<T: 'static, E: 'static> ThreadManager<T, E> {
+  pub fn new(
+    poll: fn() -> Result<T, E>,
+    interval: PollInterval,
+    is_change: fn(&T, &T) -> bool,
+  ) -> Self {
     ThreadManager {
-      id: Arc::new(Mutex::new(1)),
-      threads: Arc::new(Mutex::new(HashMap::new())),
+      poll,
+      interval: Arc::new(Mutex::new(interval)),
+      is_change,
+      change_notifier: None,
+      subscriptions: Arc::new(Mutex::new(Subscriptions {
+        next_id: 1,
+        subscribers: BTreeMap::new(),
+        poller: None,
+      })),
+      running: Arc::new((Mutex::new(0), Condvar::new())),
     }
   }
 
-  pub fn start_thread<F>(&self, work: F) -> Result<u32, String>
+  /**
+   * Wait for the changes notified by `change_notifier` between polls, it is created by each polling thread
+   */
+  pub fn with_change_notifier(mut self, change_notifier: fn() -> Option<ChangeNotifier>) -> Self {
+    self.change_notifier = Some(change_notifier);
+    self
+  }
+
+  /**
+   * Change the interval between polls, a running thread uses it after its current wait
+   */
+  pub fn set_interval(&self, interval: PollInterval) {
+    *lock_unpoisoned(&self.interval) = interval;
+  }
+
+  /**
+   * Register a subscriber called with every polled value or error and return its id,
+   * the polling thread is started when it is not running and woken up to poll its first value otherwise
+   */
+  pub fn subscribe<F>(&self, subscriber: F) -> u32
   where
-    F: Fn(mpsc::Receiver<()>) + Send + 'static,
+    F: FnMut(Result<&T, &E>) -> bool + Send + 'static,
   {
-    let key = {
-      let mut id = self.id.lock().unwrap();
-      let key = *id;
-      *id += 1;
-      key
-    };
-    let (sender, receiver) = mpsc::channel::<()>();
-    let threads_clone = Arc::clone(&self.threads);
-
-    let sender_ = sender.clone();
+    let mut subscriptions = lock_unpoisoned(&self.subscriptions);
+    let id = subscriptions.next_id;
+    subscriptions.next_id += 1;
+    subscriptions
+      .subscribers
+      .insert(id, Arc::new(Mutex::new(Box::new(subscriber))));
+    match subscriptions.poller.as_ref() {
+      // An idle thread can wait for a change or the longest adaptive interval
+      Some(poller) => {
+        let _ = poller.send(PollerMessage::Poll);
+      }
+      None => subscriptions.poller = Some(self.start_poller()),
+    }
+    id
+  }
 
-    let handle = thread::spawn(move || {
-      work(receiver);
-    });
-    threads_clone.lock().unwrap().insert(key, sender_);
-    let threads_clone_for_cleanup = Arc::clone(&self.threads);
+  fn start_poller(&self) -> mpsc::Sender<PollerMessage> {
+    let (sender, receiver) = mpsc::channel::<PollerMessage>();
+    let poll = self.poll;
+    let poll_interval = Arc::clone(&self.interval);
+    let is_change = self.is_change;
+    let change_notifier = self.change_notifier;
+    let subscriptions = Arc::clone(&self.subscriptions);
+    *lock_unpoisoned(&self.running.0) += 1;
+    let guard = RunningGuard(Arc::clone(&self.running));
     thread::spawn(move || {
-      let _ = handle.join();
-      threads_clone_for_cleanup.lock().unwrap().remove(&key);
+      let _guard = guard;
+      let mut notifier = change_notifier.and_then(|change_notifier| change_notifier());
+      let mut failures: u32 = 0;
+      let mut interval = Duration::ZERO;
+      let mut previous: Option<T> = None;
+      loop {
+        let value = poll();
+        failures = if value.is_ok() { 0 } else { failures + 1 };
+        let mut pending = false;
+        let subscribers: Vec<(u32, SharedSubscriber<T, E>)> = lock_unpoisoned(&subscriptions)
+          .subscribers
+          .iter()
+          .map(|(id, subscriber)| (*id, Arc::clone(subscriber)))
+          .collect();
+        for (id, subscriber) in subscribers {
+          // Subscribers removed before their turn are not called, the subscriptions are not locked
+          // during the call so a slow subscriber doesn't block unsubscriptions
+          if !lock_unpoisoned(&subscriptions)
+            .subscribers
+            .contains_key(&id)
+          {
+            continue;
+          }
+          let mut subscriber = lock_unpoisoned(&subscriber);
+          match catch_unwind(AssertUnwindSafe(|| subscriber(value.as_ref()))) {
+            Ok(subscriber_pending) => pending |= subscriber_pending,
+            // A panicking subscriber is removed, the other ones keep receiving values
+            Err(_) => {
+              let mut subscriptions = lock_unpoisoned(&subscriptions);
+              subscriptions.subscribers.remove(&id);
+              if subscriptions.subscribers.is_empty() {
+                stop_poller(&mut subscriptions);
+              }
+            }
+          }
+        }
+        // The first poll counts as a change, the value is likely to change right after a subscription
+        let changed = match (&previous, &value) {
+          (Some(previous), Ok(value)) => is_change(previous, value),
+          (None, Ok(_)) => true,
+          _ => false,
+        };
+        interval = lock_unpoisoned(&poll_interval).next(interval, changed || pending);
+        if let Ok(value) = value {
+          previous = Some(value);
+        }
+        let stopped = match notifier.as_mut() {
+          // Failed polls are retried with the backoff and pending changes at the interval
+          Some(change_notifier) if failures == 0 && !pending => {
+            match wait_for_change(change_notifier, &receiver, interval) {
+              Ok(stopped) => stopped,
+              // Lost connection, the next polls are timed
+              Err(_) => {
+                notifier = None;
+                false
+              }
+            }
+          }
+          // An unsubscription during the backoff still stops the thread immediately
+          _ => !matches!(
+            receiver.recv_timeout(retry_interval(interval, failures)),
+            Ok(PollerMessage::Poll) | Err(mpsc::RecvTimeoutError::Timeout)
+          ),
+        };
+        if stopped {
+          break;
+        }
+      }
     });
-    Ok(key)
+    sender
   }
 
-  pub fn stop_thread(&self, key: u32) -> Result<(), String> {
-    let sender_mutex = {
-      let threads = self.threads.lock().unwrap();
-      threads
-        .get(&key)
-        .cloned()
-        .ok_or_else(|| "Thread not found.".to_string())?
-    };
-    sender_mutex
-      .send(())
-      .map_err(|_| "Failed to send stop signal.".to_string())?;
-    Ok(())
-  }
-
-  pub fn stop_all_threads(&self) -> Result<(), String> {
-    let threads = self.threads.lock().unwrap();
-    for (_, sender) in threads.iter() {
-      sender
-        .send(())
-        .map_err(|_| "Failed to send stop signal.".to_string())?;
+  /**
+   * Remove the subscriber, return `false` when no subscriber is registered with this id.
+   * A call of the subscriber already running is not awaited
+   * The polling thread is stopped with the last subscriber
+   */
+  pub fn unsubscribe(&self, id: u32) -> bool {
+    let mut subscriptions = lock_unpoisoned(&self.subscriptions);
+    let removed = subscriptions.subscribers.remove(&id).is_some();
+    if subscriptions.subscribers.is_empty() {
+      stop_poller(&mut subscriptions);
+    }
+    removed
+  }
+
+  pub fn unsubscribe_all(&self) {
+    let mut subscriptions = lock_unpoisoned(&self.subscriptions);
+    subscriptions.subscribers.clear();
+    stop_poller(&mut subscriptions);
+  }
+
+  /**
+   * Remove all subscribers and wait until the polling thread is finished, at most `timeout`.
+   * Return `false` when it is still running after the timeout
+   */
+  pub fn shutdown(&self, timeout: Duration) -> bool {
+    self.unsubscribe_all();
+    let (count, finished) = &*self.running;
+    let (count, _) = finished
+      .wait_timeout_while(lock_unpoisoned(count), timeout, |count| *count > 0)
+      .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *count == 0
+  }
+
+  /**
+   * Number of subscribers registered and not removed yet
+   */
+  pub fn subscription_count(&self) -> u32 {
+    lock_unpoisoned(&self.subscriptions).subscribers.len() as u32
+  }
+}
+
+/**
+ * Wait before the next poll, doubled after each consecutive failure up to `MAX_RETRY_INTERVAL`
+ */
+fn retry_interval(interval: Duration, failures: u32) -> Duration {
+  if failures == 0 {
+    return interval;
+  }
+  interval
+    .checked_mul(1 << failures.min(16))
+    .unwrap_or(MAX_RETRY_INTERVAL)
+    .min(MAX_RETRY_INTERVAL)
+    .max(interval)
+}
+
+/**
+ * Wait until a change is notified or a poll is requested, the messages of the thread are checked every `interval`.
+ * Return `true` when the thread is stopped
+ */
+fn wait_for_change(
+  change_notifier: &mut ChangeNotifier,
+  receiver: &mpsc::Receiver<PollerMessage>,
+  interval: Duration,
+) -> Result<bool, x_win::XWinError> {
+  loop {
+    match receiver.try_recv() {
+      Err(mpsc::TryRecvError::Empty) => {}
+      Ok(PollerMessage::Poll) => return Ok(false),
+      // Stopped or dropped sender
+      _ => return Ok(true),
     }
-    Ok(())
+    if change_notifier.wait(interval)? {
+      return Ok(false);
+    }
+  }
+}
+
+fn stop_poller<T, E>(subscriptions: &mut Subscriptions<T, E>) {
+  if let Some(poller) = subscriptions.poller.take() {
+    // Thread can already be finished, dropped sender will stop it otherwise
+    let _ = poller.send(PollerMessage::Stop);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ADAPTIVE: PollInterval = PollInterval::Adaptive {
+    min: Duration::from_millis(100),
+    max: Duration::from_secs(2),
+    backoff_factor: 2.0,
+  };
+
+  #[test]
+  fn test_fixed_interval() {
+    let interval = PollInterval::Fixed(Duration::from_millis(100));
+    assert_eq!(
+      interval.next(Duration::ZERO, false),
+      Duration::from_millis(100)
+    );
+    assert_eq!(
+      interval.next(Duration::from_secs(1), true),
+      Duration::from_millis(100)
+    );
+  }
+
+  #[test]
+  fn test_adaptive_interval_after_change() {
+    assert_eq!(
+      ADAPTIVE.next(Duration::from_secs(2), true),
+      Duration::from_millis(100)
+    );
+    assert_eq!(
+      ADAPTIVE.next(Duration::ZERO, true),
+      Duration::from_millis(100)
+    );
+  }
+
+  #[test]
+  fn test_adaptive_interval_backoff() {
+    assert_eq!(
+      ADAPTIVE.next(Duration::from_millis(100), false),
+      Duration::from_millis(200)
+    );
+    assert_eq!(
+      ADAPTIVE.next(Duration::from_millis(300), false),
+      Duration::from_millis(600)
+    );
+    // The first interval of a thread is raised to `min`
+    assert_eq!(
+      ADAPTIVE.next(Duration::ZERO, false),
+      Duration::from_millis(100)
+    );
+  }
+
+  #[test]
+  fn test_adaptive_interval_clamped_to_max() {
+    assert_eq!(
+      ADAPTIVE.next(Duration::from_millis(1500), false),
+      Duration::from_secs(2)
+    );
+    assert_eq!(
+      ADAPTIVE.next(Duration::from_secs(2), false),
+      Duration::from_secs(2)
+    );
+  }
+
+  #[test]
+  fn test_adaptive_interval_overflow() {
+    let interval = PollInterval::Adaptive {
+      min: Duration::from_millis(100),
+      max: Duration::from_secs(60),
+      backoff_factor: f64::MAX,
+    };
+    // Products out of the range of `Duration` are clamped to `max`
+    assert_eq!(
+      interval.next(Duration::from_secs(1), false),
+      Duration::from_secs(60)
+    );
+  }
+
+  #[test]
+  fn test_panicking_subscriber_removed() {
+    let manager: ThreadManager<u32, ()> = ThreadManager::new(
+      || Ok(1),
+      PollInterval::Fixed(Duration::from_millis(10)),
+      |previous, current| previous != current,
+    );
+    let (sender, receiver) = mpsc::channel::<Option<u32>>();
+    manager.subscribe(|_| panic!("subscriber failure"));
+    let id = manager.subscribe(move |value| {
+      let _ = sender.send(value.ok().copied());
+      false
+    });
+    // The panicking subscriber is removed before the next ones are called
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(Some(1)));
+    assert_eq!(manager.subscription_count(), 1);
+    assert!(manager.unsubscribe(id));
+    assert!(manager.shutdown(Duration::from_secs(5)));
   }
 }
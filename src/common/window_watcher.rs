@@ -0,0 +1,50 @@
+#![deny(unused_imports)]
+
+use std::time::Duration;
+
+use napi::Result;
+use x_win::{get_open_windows, WindowTracker};
+
+use crate::to_napi_error;
+
+use super::x_win_struct::window_event::WindowEvent;
+
+/**
+ * Stateful watcher of open windows, each instance keeps its own previous state
+ * so several consumers can track changes independently.
+ */
+#[napi]
+pub struct WindowWatcher {
+  tracker: WindowTracker,
+}
+
+#[napi]
+impl WindowWatcher {
+  /**
+   * Create a watcher from the current open windows.
+   * A window must be missing for `closeGracePeriod` milliseconds (default: `0`) before being reported as closed.
+   */
+  #[napi(constructor)]
+  pub fn new(close_grace_period: Option<u32>) -> Result<Self> {
+    let mut tracker =
+      WindowTracker::new(Duration::from_millis(close_grace_period.unwrap_or(0) as u64));
+    tracker.update(&get_open_windows().map_err(to_napi_error)?);
+    Ok(Self { tracker })
+  }
+
+  /**
+   * Return windows opened and closed since the last call (or the creation of the watcher).
+   */
+  #[napi]
+  pub fn poll(&mut self) -> Result<Vec<WindowEvent>> {
+    let windows = get_open_windows().map_err(to_napi_error)?;
+    Ok(
+      self
+        .tracker
+        .update(&windows)
+        .into_iter()
+        .map(WindowEvent::from)
+        .collect(),
+    )
+  }
+}
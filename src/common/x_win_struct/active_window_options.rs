@@ -0,0 +1,50 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to recover the active window
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct ActiveWindowOptions {
+  /**
+   * Recover the url of the active tab when the window belongs to a supported browser (default: `false`)
+   */
+  pub include_url: Option<bool>,
+  /**
+   * Recover the language of the focused document, requires accessibility permission on macOS (default: `false`)
+   */
+  pub include_content_language: Option<bool>,
+  /**
+   * Recover the focused UI element of the window with AT-SPI on Linux, UI Automation on Windows
+   * and the AX API on macOS, requires accessibility permission on macOS (default: `false`)
+   */
+  pub include_focused_element: Option<bool>,
+  /**
+   * Return positions in logical pixels, physical geometry is divided by `scaleFactor` of the window monitor
+   * (positions are already logical on macOS) (default: `false`)
+   */
+  pub logical_pixels: Option<bool>,
+  /**
+   * Maximum time in milliseconds to wait for the X server on Linux, an error is thrown when it is exceeded
+   * (default: no limit)
+   */
+  pub timeout_ms: Option<u32>,
+  /**
+   * Recover the active window again instead of returning the one cached by a recent call of `activeWindow`
+   * (default: `false`)
+   */
+  pub force_refresh: Option<bool>,
+}
+
+impl From<ActiveWindowOptions> for x_win::ActiveWindowOptions {
+  fn from(value: ActiveWindowOptions) -> Self {
+    x_win::ActiveWindowOptions {
+      include_url: value.include_url.unwrap_or(false),
+      include_content_language: value.include_content_language.unwrap_or(false),
+      include_focused_element: value.include_focused_element.unwrap_or(false),
+      logical_pixels: value.logical_pixels.unwrap_or(false),
+      timeout_ms: value.timeout_ms,
+      display: None,
+    }
+  }
+}
@@ -0,0 +1,24 @@
+#![deny(unused_imports)]
+
+use super::{process_info::ProcessInfo, window_info::WindowInfo};
+
+/**
+ * Struct to store the open windows of an application (owning process)
+ */
+#[derive(Debug, Clone)]
+#[napi(object, object_from_js = false)]
+pub struct AppInfo {
+  pub info: ProcessInfo,
+  pub frontmost_window: WindowInfo,
+  pub windows: Vec<WindowInfo>,
+}
+
+impl From<x_win::AppInfo> for AppInfo {
+  fn from(value: x_win::AppInfo) -> Self {
+    AppInfo {
+      info: value.info.into(),
+      frontmost_window: value.frontmost_window.into(),
+      windows: value.windows.into_iter().map(WindowInfo::from).collect(),
+    }
+  }
+}
@@ -0,0 +1,32 @@
+#![deny(unused_imports)]
+
+use napi::Result;
+
+/**
+ * Struct to store options used by `captureWindow`
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct CaptureOptions {
+  /**
+   * Output of the capture, PNG bytes or the raw RGBA pixels with their size (default: `'png'`)
+   */
+  #[napi(ts_type = "'png' | 'rgba'")]
+  pub format: Option<String>,
+}
+
+impl CaptureOptions {
+  /**
+   * Return `true` when the raw RGBA pixels are requested, an unknown format throws an error
+   */
+  pub fn is_rgba(&self) -> Result<bool> {
+    match self.format.as_deref() {
+      None | Some("png") => Ok(false),
+      Some("rgba") => Ok(true),
+      Some(format) => Err(napi::Error::from_reason(format!(
+        "unknown capture format: {}",
+        format
+      ))),
+    }
+  }
+}
@@ -0,0 +1,26 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the metadata of the freedesktop `.desktop` entry of an application, only resolved on Linux
+ */
+#[derive(Debug, Clone)]
+#[napi(object, object_from_js = false)]
+pub struct DesktopEntry {
+  /**
+   * Localized `Name` of the entry (e.g. `Visual Studio Code`), empty when no entry matches
+   */
+  pub display_name: String,
+  /**
+   * `Icon` of the entry, a theme icon name (e.g. `org.gnome.Nautilus`) or an absolute path, empty when no entry matches
+   */
+  pub icon_name: String,
+}
+
+impl From<x_win::DesktopEntry> for DesktopEntry {
+  fn from(value: x_win::DesktopEntry) -> Self {
+    DesktopEntry {
+      display_name: value.display_name,
+      icon_name: value.icon_name,
+    }
+  }
+}
@@ -0,0 +1,42 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the result of the Gnome extension installation
+ */
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct ExtensionInstallInfo {
+  /**
+   * `true` when extension files are present in `path` after the call (already present with `dryRun`)
+   */
+  pub installed: bool,
+  /**
+   * Folder where extension files are written
+   */
+  pub path: String,
+  /**
+   * `true` when extension files have been written or updated (would be with `dryRun`), the user session has to be
+   * restarted to load them
+   */
+  pub needs_reload: bool,
+  /**
+   * `true` when the session is a Gnome Wayland desktop where the extension is needed to recover windows
+   */
+  pub applicable: bool,
+  /**
+   * Paths of the extension files written (or which would be written) by the installation
+   */
+  pub files: Vec<String>,
+}
+
+impl From<x_win::ExtensionInstallInfo> for ExtensionInstallInfo {
+  fn from(value: x_win::ExtensionInstallInfo) -> Self {
+    ExtensionInstallInfo {
+      installed: value.installed,
+      path: value.path,
+      needs_reload: value.needs_reload,
+      applicable: value.applicable,
+      files: value.files,
+    }
+  }
+}
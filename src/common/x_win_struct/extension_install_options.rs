@@ -0,0 +1,21 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used by `installExtension`
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct ExtensionInstallOptions {
+  /**
+   * Only report the files which would be written, without touching the filesystem (default: `false`)
+   */
+  pub dry_run: Option<bool>,
+}
+
+impl From<ExtensionInstallOptions> for x_win::ExtensionInstallOptions {
+  fn from(value: ExtensionInstallOptions) -> Self {
+    x_win::ExtensionInstallOptions {
+      dry_run: value.dry_run.unwrap_or(false),
+    }
+  }
+}
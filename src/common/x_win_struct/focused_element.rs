@@ -0,0 +1,46 @@
+#![deny(unused_imports)]
+
+use serde::Serialize;
+
+use super::window_position::WindowPosition;
+
+/**
+ * Struct to store the focused UI element of the active window recovered with the accessibility API
+ */
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[napi(object)]
+pub struct FocusedElement {
+  /**
+   * Role of the element, the AT-SPI role name on Linux and Windows (e.g. `push button`) and the AXRole on macOS (e.g. `AXButton`)
+   */
+  pub role: String,
+  /**
+   * Accessible name of the element, empty when not set
+   */
+  pub name: String,
+  /**
+   * Position and size of the element on screen
+   */
+  pub bounds: WindowPosition,
+}
+
+impl From<x_win::FocusedElement> for FocusedElement {
+  fn from(value: x_win::FocusedElement) -> Self {
+    FocusedElement {
+      role: value.role,
+      name: value.name,
+      bounds: value.bounds.into(),
+    }
+  }
+}
+
+impl From<FocusedElement> for x_win::FocusedElement {
+  fn from(value: FocusedElement) -> Self {
+    x_win::FocusedElement {
+      role: value.role,
+      name: value.name,
+      bounds: value.bounds.into(),
+    }
+  }
+}
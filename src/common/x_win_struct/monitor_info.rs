@@ -0,0 +1,53 @@
+#![deny(unused_imports)]
+
+use serde::Serialize;
+
+/**
+ * Struct to store position, size and name of a monitor
+ */
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[napi(object)]
+pub struct MonitorInfo {
+  pub id: u32,
+  pub name: String,
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+  pub is_primary: bool,
+  /**
+   * Ratio between physical and logical pixels of the monitor (e.g. `2` on Retina displays), `1` when unknown
+   */
+  pub scale_factor: f64,
+}
+
+impl From<x_win::MonitorInfo> for MonitorInfo {
+  fn from(value: x_win::MonitorInfo) -> Self {
+    MonitorInfo {
+      id: value.id,
+      name: value.name,
+      x: value.x,
+      y: value.y,
+      width: value.width,
+      height: value.height,
+      is_primary: value.is_primary,
+      scale_factor: value.scale_factor,
+    }
+  }
+}
+
+impl From<MonitorInfo> for x_win::MonitorInfo {
+  fn from(value: MonitorInfo) -> Self {
+    x_win::MonitorInfo {
+      id: value.id,
+      name: value.name,
+      x: value.x,
+      y: value.y,
+      width: value.width,
+      height: value.height,
+      is_primary: value.is_primary,
+      scale_factor: value.scale_factor,
+    }
+  }
+}
@@ -0,0 +1,87 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to recover open windows
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct OpenWindowsOptions {
+  /**
+   * Recover the url of the active tab when the window belongs to a supported browser (default: `false`)
+   */
+  pub include_url: Option<bool>,
+  /**
+   * Keep windows without `_NET_WM_WINDOW_TYPE` on Linux, only typed windows are kept by default (default: `false`)
+   */
+  pub include_untyped: Option<bool>,
+  /**
+   * Exclude desktop shell windows (taskbar, dock, desktop, menu bar...) on Windows and macOS,
+   * they are always excluded on Linux (default: `false`)
+   */
+  pub exclude_desktop_shell: Option<bool>,
+  /**
+   * Return positions in logical pixels, physical geometry is divided by `scaleFactor` of the window monitor
+   * (positions are already logical on macOS) (default: `false`)
+   */
+  pub logical_pixels: Option<bool>,
+  /**
+   * Accepted `_NET_WM_WINDOW_TYPE` on Linux (e.g. `dialog` for `_NET_WM_WINDOW_TYPE_DIALOG`),
+   * normal, dialog, utility and toolbar windows are kept when not set
+   */
+  pub window_types: Option<Vec<String>>,
+  /**
+   * Recover the memory usage of windows, `usage.memory` is left at `0` and its reading is skipped when `false` (default: `true`)
+   */
+  pub include_usage: Option<bool>,
+  /**
+   * Only keep windows of the current workspace (virtual desktop) on Linux X11 and Windows,
+   * sticky windows shown on all workspaces are kept. macOS only lists windows of the active Space (default: `false`)
+   */
+  pub only_current_workspace: Option<bool>,
+  /**
+   * Keep transient windows (dialogs, tool palettes...) declaring a parent with `WM_TRANSIENT_FOR` on X11
+   * or owned by another window on Windows, their parent is set in `parentId` (default: `false`)
+   */
+  pub include_transient: Option<bool>,
+  /**
+   * Keep override-redirect windows (menus, tooltips, drag icons...) on X11 even when they have no
+   * `_NET_WM_WINDOW_TYPE`, and tool windows or popups without caption on Windows (default: `false`)
+   */
+  pub include_override_redirect: Option<bool>,
+  /**
+   * Also list client windows missing from `_NET_CLIENT_LIST_STACKING` on X11: windows of `_NET_CLIENT_LIST`
+   * (minimized windows are omitted from the stacking list by some minimal WMs) and, when the WM maintains
+   * neither list, client windows found with `QueryTree`. Minimized windows have `isMinimized` set (default: `false`)
+   */
+  pub include_hidden: Option<bool>,
+  /**
+   * Keep the enumeration order of the platform (back to front stacking list on X11) instead of sorting windows
+   * from front to back with `zOrder` (default: `false`)
+   */
+  pub raw_order: Option<bool>,
+  /**
+   * Maximum time in milliseconds to wait for the X server on Linux, an error is thrown when it is exceeded
+   * (default: no limit)
+   */
+  pub timeout_ms: Option<u32>,
+}
+
+impl From<OpenWindowsOptions> for x_win::OpenWindowsOptions {
+  fn from(value: OpenWindowsOptions) -> Self {
+    x_win::OpenWindowsOptions {
+      include_url: value.include_url.unwrap_or(false),
+      include_untyped: value.include_untyped.unwrap_or(false),
+      exclude_desktop_shell: value.exclude_desktop_shell.unwrap_or(false),
+      logical_pixels: value.logical_pixels.unwrap_or(false),
+      window_types: value.window_types,
+      include_usage: value.include_usage.unwrap_or(true),
+      only_current_workspace: value.only_current_workspace.unwrap_or(false),
+      include_transient: value.include_transient.unwrap_or(false),
+      include_override_redirect: value.include_override_redirect.unwrap_or(false),
+      include_hidden: value.include_hidden.unwrap_or(false),
+      raw_order: value.raw_order.unwrap_or(false),
+      timeout_ms: value.timeout_ms,
+      display: None,
+    }
+  }
+}
@@ -0,0 +1,27 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the states of the macOS permissions required to recover windows information,
+ * permissions are always granted on other platforms
+ */
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct PermissionStatus {
+  /**
+   * Screen Recording permission, required for titles of other applications and captures
+   */
+  pub screen_recording: bool,
+  /**
+   * Accessibility permission, required for content language and accessibility identifiers
+   */
+  pub accessibility: bool,
+}
+
+impl From<x_win::PermissionStatus> for PermissionStatus {
+  fn from(value: x_win::PermissionStatus) -> Self {
+    PermissionStatus {
+      screen_recording: value.screen_recording,
+      accessibility: value.accessibility,
+    }
+  }
+}
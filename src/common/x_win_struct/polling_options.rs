@@ -0,0 +1,73 @@
+#![deny(unused_imports)]
+
+use std::time::Duration;
+
+use napi::Result;
+
+use crate::common::thread::PollInterval;
+
+/**
+ * Struct to store options of the polling thread shared by active window subscriptions
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct PollingOptions {
+  /**
+   * Wait between two polls in milliseconds when `adaptive` is not set (default: `100`)
+   */
+  pub interval_ms: Option<u32>,
+  /**
+   * Poll every `minIntervalMs` right after a change of the active window, then multiply the wait by
+   * `backoffFactor` after each poll without change up to `maxIntervalMs` (default: `false`)
+   */
+  pub adaptive: Option<bool>,
+  /**
+   * Shortest wait between two polls in milliseconds in adaptive mode (default: `50`)
+   */
+  pub min_interval_ms: Option<u32>,
+  /**
+   * Longest wait between two polls in milliseconds in adaptive mode (default: `1000`)
+   */
+  pub max_interval_ms: Option<u32>,
+  /**
+   * Factor applied to the wait after each poll without change in adaptive mode, at least `1` (default: `1.5`)
+   */
+  pub backoff_factor: Option<f64>,
+}
+
+impl PollingOptions {
+  /**
+   * Build the interval between polls, invalid values (e.g. a `0` interval) return an error
+   */
+  pub fn poll_interval(&self) -> Result<PollInterval> {
+    if !self.adaptive.unwrap_or(false) {
+      let interval_ms = self.interval_ms.unwrap_or(100);
+      if interval_ms == 0 {
+        return Err(napi::Error::from_reason(
+          "intervalMs must be greater than 0",
+        ));
+      }
+      return Ok(PollInterval::Fixed(Duration::from_millis(
+        interval_ms as u64,
+      )));
+    }
+    let min_interval_ms = self.min_interval_ms.unwrap_or(50);
+    let max_interval_ms = self.max_interval_ms.unwrap_or(1000.max(min_interval_ms));
+    let backoff_factor = self.backoff_factor.unwrap_or(1.5);
+    if min_interval_ms == 0 || max_interval_ms < min_interval_ms {
+      return Err(napi::Error::from_reason(
+        "minIntervalMs must be greater than 0 and not greater than maxIntervalMs",
+      ));
+    }
+    if !backoff_factor.is_finite() || backoff_factor < 1.0 {
+      return Err(napi::Error::from_reason(
+        "backoffFactor must be a number not less than 1",
+      ));
+    }
+    Ok(PollInterval::Adaptive {
+      min: Duration::from_millis(min_interval_ms as u64),
+      max: Duration::from_millis(max_interval_ms as u64),
+      backoff_factor,
+    })
+  }
+}
@@ -1,9 +1,12 @@
 #![deny(unused_imports)]
 
+use serde::Serialize;
+
 /**
  * Struct to store process information of the window
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[napi(object)]
 #[repr(C)]
 pub struct ProcessInfo {
@@ -11,15 +14,53 @@ pub struct ProcessInfo {
   pub path: String,
   pub name: String,
   pub exec_name: String,
+  /**
+   * Arguments used to launch the process separated by spaces, empty when the process is not readable
+   */
+  pub command_line: String,
+  /**
+   * Name of the user owning the process, empty when the process cannot be inspected
+   */
+  pub user: String,
+  /**
+   * Start time of the process as a Unix timestamp in milliseconds, `0` when unavailable
+   */
+  pub start_time: i64,
+  /**
+   * Bundle identifier of the application (e.g. `com.microsoft.VSCode`), only set on macOS
+   */
+  pub bundle_id: String,
+  /**
+   * Localized display name of the application, only set on macOS
+   */
+  pub app_name: String,
+  /**
+   * Instance name of WM_CLASS (e.g. `Navigator` for Firefox), `name` holds its class name, only set on X11
+   */
+  pub instance_name: String,
 }
 
 impl ProcessInfo {
-  pub fn new(process_id: u32, path: String, name: String, exec_name: String) -> Self {
+  pub fn new(
+    process_id: u32,
+    path: String,
+    name: String,
+    exec_name: String,
+    command_line: String,
+    user: String,
+    start_time: i64,
+  ) -> Self {
     Self {
       process_id,
       path,
       name,
       exec_name,
+      command_line,
+      user,
+      start_time,
+      bundle_id: "".to_owned(),
+      app_name: "".to_owned(),
+      instance_name: "".to_owned(),
     }
   }
 }
@@ -31,6 +72,12 @@ impl From<x_win::ProcessInfo> for ProcessInfo {
       name: value.name,
       path: value.path,
       process_id: value.process_id,
+      command_line: value.command_line,
+      user: value.user,
+      start_time: value.start_time as i64,
+      bundle_id: value.bundle_id,
+      app_name: value.app_name,
+      instance_name: value.instance_name,
     }
   }
 }
@@ -42,6 +89,12 @@ impl From<ProcessInfo> for x_win::ProcessInfo {
       name: value.name,
       path: value.path,
       process_id: value.process_id,
+      command_line: value.command_line,
+      user: value.user,
+      start_time: value.start_time.max(0) as u64,
+      bundle_id: value.bundle_id,
+      app_name: value.app_name,
+      instance_name: value.instance_name,
     }
   }
 }
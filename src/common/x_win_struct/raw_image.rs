@@ -0,0 +1,28 @@
+#![deny(unused_imports)]
+
+use napi::bindgen_prelude::Buffer;
+
+/**
+ * Struct to store the raw pixels of a window captured with the `rgba` format, 8 bits RGBA rows from top to bottom
+ */
+#[napi(object, object_from_js = false)]
+pub struct RawImage {
+  pub width: u32,
+  pub height: u32,
+  /**
+   * Number of bytes of a row in `data`
+   */
+  pub stride: u32,
+  pub data: Buffer,
+}
+
+impl From<x_win::RawImage> for RawImage {
+  fn from(value: x_win::RawImage) -> Self {
+    RawImage {
+      width: value.width,
+      height: value.height,
+      stride: value.stride,
+      data: value.data.into(),
+    }
+  }
+}
@@ -0,0 +1,83 @@
+#![deny(unused_imports)]
+
+use std::time::Duration;
+
+use napi::Result;
+use x_win::{ActiveWindowDebouncer, WatchedFields, WindowFilter};
+
+use crate::to_napi_error;
+
+/**
+ * Struct to store options used by active window subscriptions
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct SubscribeOptions {
+  /**
+   * Only call the callback once the active window has been focused for this duration in milliseconds (default: `0`)
+   */
+  pub debounce_ms: Option<u32>,
+  /**
+   * Only call the callback once the title of the active window has been stable for this duration in milliseconds,
+   * focus changes still use `debounceMs` (default: `debounceMs`)
+   */
+  pub title_change_debounce_ms: Option<u32>,
+  /**
+   * Keep the last titles of each active window, up to this number of entries per window,
+   * and set them in `titleHistory` of the emitted window (default: `0`, disabled)
+   */
+  pub title_history_length: Option<u32>,
+  /**
+   * Only call the callback when the executable or application name of the new active window is one of them
+   * (case-insensitive)
+   */
+  pub process_names: Option<Vec<String>>,
+  /**
+   * Only call the callback when the title of the new active window matches this regular expression,
+   * an invalid expression throws an error at subscription time
+   */
+  pub title_regex: Option<String>,
+  /**
+   * Call the callback with the current active window as a `focus` change on the first poll after subscription,
+   * without waiting for `debounceMs` (default: `true`)
+   */
+  pub emit_initial: Option<bool>,
+  /**
+   * Fields of the active window triggering the callback when they change, among `id`, `process`, `title` and
+   * `position` (default: `["id", "process", "title"]`), an unknown field throws an error at subscription time
+   */
+  pub watched_fields: Option<Vec<String>>,
+}
+
+impl SubscribeOptions {
+  /**
+   * Build the filter of the active windows passed to the callback
+   */
+  pub fn window_filter(&self) -> Result<WindowFilter> {
+    WindowFilter::new(
+      self.process_names.clone().unwrap_or_default(),
+      self.title_regex.as_deref(),
+    )
+    .map_err(to_napi_error)
+  }
+
+  /**
+   * Build the fields compared to detect a change of the active window
+   */
+  pub fn watched_fields(&self) -> Result<WatchedFields> {
+    match &self.watched_fields {
+      Some(names) => WatchedFields::from_names(names).map_err(to_napi_error),
+      None => Ok(WatchedFields::default()),
+    }
+  }
+}
+
+impl From<SubscribeOptions> for ActiveWindowDebouncer {
+  fn from(value: SubscribeOptions) -> Self {
+    let debounce_ms = value.debounce_ms.unwrap_or(0);
+    ActiveWindowDebouncer::new(
+      Duration::from_millis(debounce_ms as u64),
+      Duration::from_millis(value.title_change_debounce_ms.unwrap_or(debounce_ms) as u64),
+    )
+  }
+}
@@ -0,0 +1,24 @@
+#![deny(unused_imports)]
+
+use serde::Serialize;
+
+/**
+ * Struct to store a title taken by a window, `timestamp` is the time in milliseconds since the Unix epoch
+ * when the title has been seen first
+ */
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[napi(object)]
+pub struct TitleEntry {
+  pub timestamp: i64,
+  pub title: String,
+}
+
+impl From<x_win::TitleEntry> for TitleEntry {
+  fn from(value: x_win::TitleEntry) -> Self {
+    TitleEntry {
+      timestamp: value.timestamp as i64,
+      title: value.title,
+    }
+  }
+}
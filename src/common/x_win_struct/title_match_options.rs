@@ -0,0 +1,26 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used by `findWindowsByTitle`
+ */
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct TitleMatchOptions {
+  /**
+   * Compare titles ignoring case (default: `false`)
+   */
+  pub case_insensitive: Option<bool>,
+  /**
+   * Only match titles equal to the query instead of titles containing it (default: `false`)
+   */
+  pub exact: Option<bool>,
+}
+
+impl From<TitleMatchOptions> for x_win::TitleMatchOptions {
+  fn from(value: TitleMatchOptions) -> Self {
+    x_win::TitleMatchOptions {
+      case_insensitive: value.case_insensitive.unwrap_or(false),
+      exact: value.exact.unwrap_or(false),
+    }
+  }
+}
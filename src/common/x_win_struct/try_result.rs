@@ -0,0 +1,59 @@
+#![deny(unused_imports)]
+
+use napi::Result;
+
+use super::window_info::WindowInfo;
+
+/**
+ * Struct to store the outcome of `tryActiveWindow`, `value` is set when `ok` is `true` and `error` otherwise
+ */
+#[napi(object, object_from_js = false)]
+pub struct ActiveWindowResult {
+  pub ok: bool,
+  pub value: Option<WindowInfo>,
+  pub error: Option<String>,
+}
+
+impl From<Result<WindowInfo>> for ActiveWindowResult {
+  fn from(value: Result<WindowInfo>) -> Self {
+    match value {
+      Ok(window_info) => ActiveWindowResult {
+        ok: true,
+        value: Some(window_info),
+        error: None,
+      },
+      Err(error) => ActiveWindowResult {
+        ok: false,
+        value: None,
+        error: Some(error.reason),
+      },
+    }
+  }
+}
+
+/**
+ * Struct to store the outcome of `tryOpenWindows`, `value` is set when `ok` is `true` and `error` otherwise
+ */
+#[napi(object, object_from_js = false)]
+pub struct OpenWindowsResult {
+  pub ok: bool,
+  pub value: Option<Vec<WindowInfo>>,
+  pub error: Option<String>,
+}
+
+impl From<Result<Vec<WindowInfo>>> for OpenWindowsResult {
+  fn from(value: Result<Vec<WindowInfo>>) -> Self {
+    match value {
+      Ok(windows) => OpenWindowsResult {
+        ok: true,
+        value: Some(windows),
+        error: None,
+      },
+      Err(error) => OpenWindowsResult {
+        ok: false,
+        value: None,
+        error: Some(error.reason),
+      },
+    }
+  }
+}
@@ -1,17 +1,25 @@
 #![deny(unused_imports)]
 
+use serde::Serialize;
+
 /**
  * Struct to store usage data of the window
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[napi(object)]
 pub struct UsageInfo {
   pub memory: u32,
+  /**
+   * CPU usage of the process in percent of one core since the previous poll of `subscribeActiveWindow`,
+   * `0` for one-shot calls which can't compute a rate from a single sample
+   */
+  pub cpu: f64,
 }
 
 impl UsageInfo {
   pub fn new(memory: u32) -> Self {
-    Self { memory }
+    Self { memory, cpu: 0.0 }
   }
 }
 
@@ -19,6 +27,7 @@ impl From<x_win::UsageInfo> for UsageInfo {
   fn from(value: x_win::UsageInfo) -> Self {
     UsageInfo {
       memory: value.memory,
+      cpu: value.cpu,
     }
   }
 }
@@ -27,6 +36,7 @@ impl From<UsageInfo> for x_win::UsageInfo {
   fn from(value: UsageInfo) -> Self {
     x_win::UsageInfo {
       memory: value.memory,
+      cpu: value.cpu,
     }
   }
 }
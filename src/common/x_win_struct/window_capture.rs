@@ -0,0 +1,21 @@
+#![deny(unused_imports)]
+
+use napi::bindgen_prelude::Buffer;
+
+/**
+ * Struct to store the PNG thumbnail of a window captured by `captureWindows`
+ */
+#[napi(object, object_from_js = false)]
+pub struct WindowCapture {
+  pub id: u32,
+  pub image: Buffer,
+}
+
+impl From<(u32, Vec<u8>)> for WindowCapture {
+  fn from((id, image): (u32, Vec<u8>)) -> Self {
+    WindowCapture {
+      id,
+      image: image.into(),
+    }
+  }
+}
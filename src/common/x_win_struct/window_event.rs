@@ -0,0 +1,29 @@
+#![deny(unused_imports)]
+
+use super::window_info::WindowInfo;
+
+/**
+ * Struct to store a change of the open windows (`opened` or `closed`)
+ */
+#[derive(Debug, Clone)]
+#[napi(object, object_from_js = false)]
+pub struct WindowEvent {
+  #[napi(ts_type = "'opened' | 'closed'")]
+  pub kind: String,
+  pub window: WindowInfo,
+}
+
+impl From<x_win::WindowEvent> for WindowEvent {
+  fn from(value: x_win::WindowEvent) -> Self {
+    match value {
+      x_win::WindowEvent::Opened(window_info) => WindowEvent {
+        kind: "opened".to_owned(),
+        window: window_info.into(),
+      },
+      x_win::WindowEvent::Closed(window_info) => WindowEvent {
+        kind: "closed".to_owned(),
+        window: window_info.into(),
+      },
+    }
+  }
+}
@@ -1,11 +1,18 @@
 #![deny(unused_imports)]
 
-use super::{process_info::ProcessInfo, usage_info::UsageInfo, window_position::WindowPosition};
+use serde::Serialize;
+
+use super::{
+  focused_element::FocusedElement, monitor_info::MonitorInfo, process_info::ProcessInfo,
+  title_entry::TitleEntry, usage_info::UsageInfo, window_position::WindowPosition,
+  workspace_info::WorkspaceInfo,
+};
 
 /**
  * Struct to store all informations of the window
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[napi(constructor)]
 pub struct WindowInfo {
   pub id: u32,
@@ -15,6 +22,96 @@ pub struct WindowInfo {
   pub info: ProcessInfo,
   pub usage: UsageInfo,
   pub url: String,
+  pub z_order: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub on_external_display: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub workspace: Option<WorkspaceInfo>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub content_language: Option<String>,
+  /**
+   * Monitor containing the center of the window, `undefined` when unknown
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub monitor: Option<MonitorInfo>,
+  /**
+   * `true` for the window owning the input focus (`GetInputFocus` on X11), the active window on other platforms.
+   * It can differ from `isActive` on X11 (e.g. focus-follows-mouse), at most one window of `openWindows` has it set
+   */
+  pub is_focused: bool,
+  /**
+   * `true` for the active window (`_NET_ACTIVE_WINDOW` on X11, foreground window on Windows, key window of the
+   * frontmost application on macOS), at most one window of `openWindows` has it set
+   */
+  pub is_active: bool,
+  /**
+   * Opacity of the window from `0` (transparent) to `1` (opaque), `1` when unknown
+   */
+  pub opacity: f64,
+  /**
+   * Id of the window this one is transient for (`WM_TRANSIENT_FOR` on X11, owner window on Windows),
+   * `undefined` for top-level windows or when unknown
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub parent_id: Option<u32>,
+  /**
+   * `true` when the window is minimized (`_NET_WM_STATE_HIDDEN` or iconic `WM_STATE` on X11),
+   * minimized windows are not listed on macOS
+   */
+  pub is_minimized: bool,
+  /**
+   * `true` when the window is mapped (`Viewable` map state on X11, `IsWindowVisible` on Windows, on screen on macOS),
+   * mapped windows can still be off-screen or covered. Always `true` on Wayland where the state is not exposed
+   */
+  pub is_visible: bool,
+  /**
+   * Role of the window from `WM_WINDOW_ROLE` on X11 (e.g. `browser` or `pop-up`) to match windows across sessions,
+   * empty when not set or on other platforms
+   */
+  pub role: String,
+  /**
+   * Focused UI element of the window, only recovered with `includeFocusedElement` of `activeWindow`,
+   * `undefined` when accessibility is unavailable
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub focused_element: Option<FocusedElement>,
+  /**
+   * Visible bounds of the window including the decorations of the window manager (title bar and borders),
+   * `position` keeps the client bounds. `undefined` when the frame is unknown
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub frame: Option<WindowPosition>,
+  /**
+   * Window pinned above the other windows: `_NET_WM_STATE_ABOVE` on X11, `WS_EX_TOPMOST` on Windows
+   * and a window level above the normal one on macOS
+   */
+  pub always_on_top: bool,
+  /**
+   * Window kept below the other windows with `_NET_WM_STATE_BELOW` on X11, always `false` on other platforms
+   */
+  pub always_on_bottom: bool,
+  /**
+   * Raw platform handle of the window for interop with other native libraries: the XID on X11, the `HWND` value
+   * on Windows and the `CGWindowID` on macOS, `0` when not available (e.g. Wayland). Handles fit in a safe integer
+   * on every platform (`HWND` values only use their lower 32 bits)
+   */
+  pub native_handle: i64,
+  /**
+   * Best-effort identity of the window preserved across title changes, hashed from the window class,
+   * the role and the process id with its start time. Windows of a process sharing the same class and role share it
+   */
+  pub stable_id: String,
+  /**
+   * Time in milliseconds the previous window has been focused, only set by `subscribeActiveWindow` on `focus` and
+   * `process` changes once a previous focus change is known (accurate to the poll interval)
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub previous_focus_duration_ms: Option<i64>,
+  /**
+   * Recent titles of the window, oldest first, only set by `subscribeActiveWindow` when `titleHistoryLength` is set
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub title_history: Option<Vec<TitleEntry>>,
 }
 
 #[napi]
@@ -36,12 +133,33 @@ impl WindowInfo {
       info,
       usage,
       url,
+      on_external_display: None,
+      z_order: 0,
+      workspace: None,
+      content_language: None,
+      monitor: None,
+      is_focused: false,
+      is_active: false,
+      opacity: 1.0,
+      parent_id: None,
+      is_minimized: false,
+      is_visible: false,
+      role: "".to_owned(),
+      focused_element: None,
+      frame: None,
+      always_on_top: false,
+      always_on_bottom: false,
+      native_handle: 0,
+      stable_id: "".to_owned(),
+      previous_focus_duration_ms: None,
+      title_history: None,
     }
   }
 }
 
 impl From<x_win::WindowInfo> for WindowInfo {
   fn from(value: x_win::WindowInfo) -> Self {
+    let stable_id = value.stable_id();
     WindowInfo {
       id: value.id,
       info: value.info.into(),
@@ -50,6 +168,26 @@ impl From<x_win::WindowInfo> for WindowInfo {
       position: value.position.into(),
       url: value.url,
       usage: value.usage.into(),
+      on_external_display: value.on_external_display,
+      z_order: value.z_order,
+      workspace: value.workspace.map(|workspace| workspace.into()),
+      content_language: value.content_language,
+      monitor: value.monitor.map(|monitor| monitor.into()),
+      is_focused: value.is_focused,
+      is_active: value.is_active,
+      opacity: value.opacity,
+      parent_id: value.parent_id,
+      is_minimized: value.is_minimized,
+      is_visible: value.is_visible,
+      role: value.role,
+      focused_element: value.focused_element.map(|element| element.into()),
+      frame: value.frame.map(|frame| frame.into()),
+      always_on_top: value.always_on_top,
+      always_on_bottom: value.always_on_bottom,
+      native_handle: value.native_handle as i64,
+      stable_id,
+      previous_focus_duration_ms: None,
+      title_history: None,
     }
   }
 }
@@ -64,6 +202,23 @@ impl From<WindowInfo> for x_win::WindowInfo {
       position: value.position.into(),
       url: value.url,
       usage: value.usage.into(),
+      on_external_display: value.on_external_display,
+      z_order: value.z_order,
+      workspace: value.workspace.map(|workspace| workspace.into()),
+      content_language: value.content_language,
+      monitor: value.monitor.map(|monitor| monitor.into()),
+      is_focused: value.is_focused,
+      is_active: value.is_active,
+      opacity: value.opacity,
+      parent_id: value.parent_id,
+      is_minimized: value.is_minimized,
+      is_visible: value.is_visible,
+      role: value.role,
+      focused_element: value.focused_element.map(|element| element.into()),
+      frame: value.frame.map(|frame| frame.into()),
+      always_on_top: value.always_on_top,
+      always_on_bottom: value.always_on_bottom,
+      native_handle: value.native_handle as u64,
     }
   }
 }
@@ -1,9 +1,14 @@
 #![deny(unused_imports)]
 
+use serde::Serialize;
+
 /**
- * Struct to store position and size of the window
+ * Struct to store position and size of the window.
+ * Values are physical pixels on Windows and X11, logical points on macOS and stage coordinates of the compositor on Wayland,
+ * use `scaleFactor` of the monitor to convert them.
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[napi(object)]
 pub struct WindowPosition {
   pub x: i32,
@@ -0,0 +1,32 @@
+#![deny(unused_imports)]
+
+use napi::bindgen_prelude::Buffer;
+
+/**
+ * Struct to store the raw value of a window property, only read on X11
+ */
+#[napi(object, object_from_js = false)]
+pub struct WindowProperty {
+  /**
+   * Name of the type atom of the value (e.g. `UTF8_STRING`, `CARDINAL` or `ATOM`)
+   */
+  pub type_name: String,
+  /**
+   * Size of the items of the value in bits, `8`, `16` or `32`
+   */
+  pub format: u32,
+  /**
+   * Bytes of the value, items of 16 and 32 bits are in the byte order of the host
+   */
+  pub data: Buffer,
+}
+
+impl From<x_win::WindowProperty> for WindowProperty {
+  fn from(value: x_win::WindowProperty) -> Self {
+    WindowProperty {
+      type_name: value.type_name,
+      format: value.format as u32,
+      data: value.data.into(),
+    }
+  }
+}
@@ -0,0 +1,22 @@
+#![deny(unused_imports)]
+
+use super::window_info::WindowInfo;
+
+/**
+ * Struct to store the active window and the open windows recovered together
+ */
+#[derive(Debug, Clone)]
+#[napi(object, object_from_js = false)]
+pub struct WindowSnapshot {
+  pub active: WindowInfo,
+  pub windows: Vec<WindowInfo>,
+}
+
+impl From<x_win::WindowSnapshot> for WindowSnapshot {
+  fn from(value: x_win::WindowSnapshot) -> Self {
+    WindowSnapshot {
+      active: value.active.into(),
+      windows: value.windows.into_iter().map(WindowInfo::from).collect(),
+    }
+  }
+}
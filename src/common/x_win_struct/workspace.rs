@@ -0,0 +1,32 @@
+#![deny(unused_imports)]
+
+use serde::Serialize;
+
+/**
+ * Struct to store a workspace (virtual desktop) of the session, `id` matches `WorkspaceInfo.id` of its windows
+ */
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[napi(object)]
+pub struct Workspace {
+  pub id: i32,
+  /**
+   * Label of the workspace, `undefined` when it has not been named
+   */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /**
+   * `true` for the workspace shown on screen
+   */
+  pub is_current: bool,
+}
+
+impl From<x_win::Workspace> for Workspace {
+  fn from(value: x_win::Workspace) -> Self {
+    Workspace {
+      id: value.id,
+      name: value.name,
+      is_current: value.is_current,
+    }
+  }
+}
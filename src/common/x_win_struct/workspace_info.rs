@@ -0,0 +1,40 @@
+#![deny(unused_imports)]
+
+use serde::Serialize;
+
+/**
+ * Struct to store the workspace (virtual desktop) of the window.
+ * `id` is `-1` and `name` is `undefined` for sticky windows shown on all workspaces.
+ */
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[napi(object)]
+pub struct WorkspaceInfo {
+  pub id: i32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+}
+
+impl WorkspaceInfo {
+  pub fn new(id: i32, name: Option<String>) -> Self {
+    Self { id, name }
+  }
+}
+
+impl From<x_win::WorkspaceInfo> for WorkspaceInfo {
+  fn from(value: x_win::WorkspaceInfo) -> Self {
+    WorkspaceInfo {
+      id: value.id,
+      name: value.name,
+    }
+  }
+}
+
+impl From<WorkspaceInfo> for x_win::WorkspaceInfo {
+  fn from(value: WorkspaceInfo) -> Self {
+    x_win::WorkspaceInfo {
+      id: value.id,
+      name: value.name,
+    }
+  }
+}
@@ -5,29 +5,116 @@
 mod common;
 
 use common::{
-  thread::ThreadManager,
-  x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+  json_lines::JsonLinesWriter,
+  thread::{PollInterval, ThreadManager},
+  x_win_struct::{
+    active_window_options::ActiveWindowOptions,
+    app_info::AppInfo,
+    capture_options::CaptureOptions,
+    desktop_entry::DesktopEntry,
+    extension_install_info::ExtensionInstallInfo,
+    extension_install_options::ExtensionInstallOptions,
+    icon_info::IconInfo,
+    monitor_info::MonitorInfo,
+    open_windows_options::OpenWindowsOptions,
+    permission_status::PermissionStatus,
+    polling_options::PollingOptions,
+    raw_image::RawImage,
+    subscribe_options::SubscribeOptions,
+    title_match_options::TitleMatchOptions,
+    try_result::{ActiveWindowResult, OpenWindowsResult},
+    window_capture::WindowCapture,
+    window_info::WindowInfo,
+    window_position::WindowPosition,
+    window_property::WindowProperty,
+    window_snapshot::WindowSnapshot,
+    workspace::Workspace,
+  },
+};
+use napi::{
+  bindgen_prelude::{AsyncTask, Buffer, Either},
+  JsFunction, Result, Task,
 };
-use napi::{bindgen_prelude::AsyncTask, JsFunction, Result, Task};
 use napi_derive::napi;
-use x_win::{empty_entity, get_active_window, get_open_windows, get_window_icon};
+use x_win::{
+  get_active_window, get_active_window_cached, get_open_windows_with_options,
+  get_open_windows_with_progress, get_window_icon, ActiveWindowChange, ActiveWindowDebouncer,
+  ChangeNotifier, CpuSampler, TitleHistory, WatchedFields, WindowFilter,
+};
 
 #[macro_use]
 extern crate napi_derive;
 
 use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
-use std::{thread, time::Duration};
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
 
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
 
-static THREAD_MANAGER: Lazy<Mutex<ThreadManager>> = Lazy::new(|| Mutex::new(ThreadManager::new()));
+/**
+ * Active window subscriptions, the active window is polled by a single thread shared by all of them.
+ * Polls follow the changes notified by the X server on Linux and are done every 100ms otherwise
+ * (see `setActiveWindowPolling`)
+ */
+static THREAD_MANAGER: Lazy<ThreadManager<x_win::WindowInfo, x_win::XWinError>> = Lazy::new(|| {
+  ThreadManager::new(
+    poll_active_window,
+    PollInterval::Fixed(Duration::from_millis(100)),
+    is_active_window_change,
+  )
+  .with_change_notifier(ChangeNotifier::new)
+});
+
+/**
+ * Another window is active or the title, the process or the position of the active window changed,
+ * adaptive polling keeps the shortest interval while a window is moved or resized
+ */
+fn is_active_window_change(previous: &x_win::WindowInfo, current: &x_win::WindowInfo) -> bool {
+  previous.id != current.id
+    || previous.title != current.title
+    || previous.info.process_id != current.info.process_id
+    || previous.position != current.position
+}
+
+/**
+ * Kind of change emitted to subscribers when the active window can't be recovered
+ */
+const ERROR_CHANGE: &str = "error";
+
+/**
+ * Recover the active window for subscriptions, a panic is turned into an error so the polling thread keeps running.
+ * A new connection to the display server is opened by each poll, the next poll reconnects after a restart of the server.
+ */
+fn poll_active_window() -> std::result::Result<x_win::WindowInfo, x_win::XWinError> {
+  // A window cached by `activeWindow` is never older than the last poll
+  x_win::invalidate_active_window_cache();
+  std::panic::catch_unwind(get_active_window).unwrap_or_else(|_| {
+    Err(x_win::XWinError::PlatformError(
+      "active window recovery panicked".to_owned(),
+    ))
+  })
+}
 
-pub struct OpenWindowsTask;
-pub struct ActiveWindowTask;
+pub struct OpenWindowsTask {
+  options: Option<OpenWindowsOptions>,
+  /**
+   * Called from the worker thread with each window as soon as it is gathered
+   */
+  on_window: Option<ThreadsafeFunction<WindowInfo, ErrorStrategy::Fatal>>,
+}
+pub struct ActiveWindowTask {
+  options: Option<ActiveWindowOptions>,
+}
 pub struct GetIconTask {
   data: WindowInfo,
 }
+pub struct CaptureWindowTask {
+  id: u32,
+  max_dimension: Option<u32>,
+  rgba: bool,
+}
 
 impl GetIconTask {
   pub fn new(data: WindowInfo) -> Self {
@@ -41,7 +128,24 @@ impl Task for OpenWindowsTask {
   type JsValue = Vec<WindowInfo>;
 
   fn compute(&mut self) -> Result<Self::Output> {
-    open_windows()
+    match &self.on_window {
+      Some(on_window) => compute_task(|| {
+        let options: x_win::OpenWindowsOptions = self.options.clone().unwrap_or_default().into();
+        Ok(
+          get_open_windows_with_progress(&options, |window_info| {
+            on_window.call(
+              window_info.clone().into(),
+              ThreadsafeFunctionCallMode::Blocking,
+            );
+          })
+          .map_err(to_napi_error)?
+          .into_iter()
+          .map(WindowInfo::from)
+          .collect(),
+        )
+      }),
+      None => compute_task(|| open_windows(self.options.clone())),
+    }
   }
 
   fn resolve(&mut self, _: napi::Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -55,7 +159,7 @@ impl Task for ActiveWindowTask {
   type JsValue = WindowInfo;
 
   fn compute(&mut self) -> Result<Self::Output> {
-    active_window()
+    compute_task(|| active_window(self.options.clone()))
   }
 
   fn resolve(&mut self, _: napi::Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -63,13 +167,27 @@ impl Task for ActiveWindowTask {
   }
 }
 
+#[napi]
+impl Task for CaptureWindowTask {
+  type Output = Either<Vec<u8>, x_win::RawImage>;
+  type JsValue = Either<Buffer, RawImage>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    compute_task(|| capture(self.id, self.max_dimension, self.rgba))
+  }
+
+  fn resolve(&mut self, _: napi::Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(to_capture_value(output))
+  }
+}
+
 #[napi]
 impl Task for GetIconTask {
   type Output = IconInfo;
   type JsValue = IconInfo;
 
   fn compute(&mut self) -> Result<Self::Output> {
-    get_icon(&self.data)
+    compute_task(|| get_icon(&self.data))
   }
 
   fn resolve(&mut self, _: napi::Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -77,9 +195,53 @@ impl Task for GetIconTask {
   }
 }
 
+/**
+ * Convert an error of x-win into a JS error
+ */
+pub(crate) fn to_napi_error(error: x_win::XWinError) -> napi::Error {
+  napi::Error::from_reason(error.to_string())
+}
+
+/**
+ * Run the work of an async task on the libuv pool, a panic rejects the promise instead of aborting the process
+ */
+fn compute_task<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+  std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+    let message = payload
+      .downcast_ref::<&str>()
+      .map(|message| message.to_string())
+      .or_else(|| payload.downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "unknown error".to_owned());
+    Err(napi::Error::from_reason(message))
+  })
+}
+
+/**
+ * Capture the window as PNG bytes or as raw RGBA pixels with `rgba`
+ */
+fn capture(
+  id: u32,
+  max_dimension: Option<u32>,
+  rgba: bool,
+) -> Result<Either<Vec<u8>, x_win::RawImage>> {
+  if rgba {
+    x_win::capture_window_rgba(id, max_dimension).map(Either::B)
+  } else {
+    x_win::capture_window(id, max_dimension).map(Either::A)
+  }
+  .map_err(to_napi_error)
+}
+
+fn to_capture_value(capture: Either<Vec<u8>, x_win::RawImage>) -> Either<Buffer, RawImage> {
+  match capture {
+    Either::A(png_data) => Either::A(png_data.into()),
+    Either::B(image) => Either::B(image.into()),
+  }
+}
+
 fn get_icon(window_info: &WindowInfo) -> Result<IconInfo> {
   let t: x_win::WindowInfo = window_info.clone().into();
-  Ok(get_window_icon(&t).unwrap().into())
+  Ok(get_window_icon(&t).map_err(to_napi_error)?.into())
 }
 
 #[napi]
@@ -100,11 +262,25 @@ impl WindowInfo {
     let data = self;
     AsyncTask::new(GetIconTask { data: data.clone() })
   }
+
+  /**
+   * Resolve the freedesktop `.desktop` entry of the application to get its display name and icon name,
+   * matched with `StartupWMClass`, the desktop file id or the program of `Exec` (Linux only).
+   * Return empty names on other platforms and when no entry matches.
+   */
+  #[napi]
+  pub fn get_desktop_entry(&self) -> DesktopEntry {
+    let window_info: x_win::WindowInfo = self.clone().into();
+    x_win::get_desktop_entry(&window_info).into()
+  }
 }
 
 /**
  * Retrieve information the about currently active window.
  * Returns an object of `WindowInfo`.
+ * Use `options.includeUrl` to recover the url of supported browsers.
+ * Repeated calls with the same options within 16ms return the same window (see `setActiveWindowCacheTtl`),
+ * use `options.forceRefresh` to recover it again.
  *
  * # Example
  *
@@ -131,13 +307,60 @@ impl WindowInfo {
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
  */
 #[napi]
-pub fn active_window() -> Result<WindowInfo> {
-  Ok(get_active_window().unwrap().into())
+pub fn active_window(options: Option<ActiveWindowOptions>) -> Result<WindowInfo> {
+  let options = options.unwrap_or_default();
+  let force_refresh = options.force_refresh.unwrap_or(false);
+  let options: x_win::ActiveWindowOptions = options.into();
+  Ok(
+    get_active_window_cached(&options, force_refresh)
+      .map_err(to_napi_error)?
+      .into(),
+  )
+}
+
+/**
+ * Same as `activeWindow` but never throws, the failure (missing permission, no display...) is returned
+ * as `{ ok: false, error }` and the window as `{ ok: true, value }`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { tryActiveWindow } from '@miniben90/x-win';
+ *
+ * const result = tryActiveWindow();
+ * if (result.ok) {
+ *   console.log(result.value.title);
+ * } else {
+ *   console.error(result.error);
+ * }
+ * ```
+ */
+#[napi(ts_return_type = "{ ok: true, value: WindowInfo } | { ok: false, error: string }")]
+pub fn try_active_window(options: Option<ActiveWindowOptions>) -> ActiveWindowResult {
+  active_window(options).into()
+}
+
+/**
+ * Change how long `activeWindow` returns the same window to repeated calls, in milliseconds (default: `16`).
+ * `0` disables the cache, use `forceRefresh` of `activeWindow` to bypass it for one call.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { setActiveWindowCacheTtl } from '@miniben90/x-win';
+ *
+ * setActiveWindowCacheTtl(50);
+ * ```
+ */
+#[napi]
+pub fn set_active_window_cache_ttl(ttl_ms: u32) {
+  x_win::set_active_window_cache_ttl(Duration::from_millis(ttl_ms as u64));
 }
 
 /**
  * Retrieve information about the currently active window as a promise.
  * Returns an object of `WindowInfo`.
+ * Use `options.includeUrl` to recover the url of supported browsers.
  *
  * # Example
  *
@@ -166,13 +389,203 @@ pub fn active_window() -> Result<WindowInfo> {
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
  */
 #[napi]
-pub fn active_window_async() -> AsyncTask<ActiveWindowTask> {
-  AsyncTask::new(ActiveWindowTask {})
+pub fn active_window_async(options: Option<ActiveWindowOptions>) -> AsyncTask<ActiveWindowTask> {
+  AsyncTask::new(ActiveWindowTask { options })
+}
+
+/**
+ * Retrieve only the id of the active window, `0` when no window is focused.
+ * Lighter than `activeWindow` to detect focus changes in a tight polling loop,
+ * title, process and memory are not recovered on X11, Windows and macOS (the whole active window is on Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getActiveWindowId } from '@miniben90/x-win';
+ *
+ * let currentId = getActiveWindowId();
+ * setInterval(() => {
+ *   const id = getActiveWindowId();
+ *   if (id !== currentId) {
+ *     currentId = id;
+ *     console.log(activeWindow());
+ *   }
+ * }, 100);
+ * ```
+ */
+#[napi]
+pub fn get_active_window_id() -> Result<u32> {
+  x_win::get_active_window_id().map_err(to_napi_error)
+}
+
+/**
+ * Check if the active window is full screen, e.g. to pause notifications during a presentation.
+ * Reads `_NET_WM_STATE_FULLSCREEN` of the active window on X11, checks that the foreground window covers
+ * its whole monitor without caption on Windows and compares the frontmost window with the screen on macOS.
+ * Return `false` when no window is focused.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { isFullscreenActive } from '@miniben90/x-win';
+ *
+ * if (isFullscreenActive()) {
+ *   console.log('Do not disturb');
+ * }
+ * ```
+ */
+#[napi]
+pub fn is_fullscreen_active() -> Result<bool> {
+  x_win::is_fullscreen_active().map_err(to_napi_error)
+}
+
+/**
+ * Retrieve the active window and compare it with `previous` the same way as `subscribeActiveWindow` without debounce.
+ * Return the new active window when the focus, the process or the title changed, `null` otherwise.
+ * Allows to detect changes from an existing timer or event loop without a dedicated thread.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, activeWindowChanged } from '@miniben90/x-win';
+ *
+ * let current = activeWindow();
+ * setInterval(() => {
+ *   const changed = activeWindowChanged(current);
+ *   if (changed) {
+ *     current = changed;
+ *     console.log(current);
+ *   }
+ * }, 100);
+ * ```
+ */
+#[napi]
+pub fn active_window_changed(previous: &WindowInfo) -> Result<Option<WindowInfo>> {
+  let previous: x_win::WindowInfo = previous.clone().into();
+  Ok(
+    x_win::active_window_changed(&previous)
+      .map_err(to_napi_error)?
+      .map(WindowInfo::from),
+  )
+}
+
+/**
+ * Retrieve the window focused before the current active window, e.g. to switch back to the last window.
+ * Focus changes are recorded by every recovery of the active window in this process (`activeWindow`,
+ * `subscribeActiveWindow` polls, ...), the most recent window distinct from the current one is returned.
+ * Return `null` when no other window has been recovered, the window can have been closed since.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getPreviousActiveWindow, subscribeActiveWindow } from '@miniben90/x-win';
+ *
+ * subscribeActiveWindow(() => {});
+ * const previous = getPreviousActiveWindow();
+ * console.log(previous?.title);
+ * ```
+ */
+#[napi]
+pub fn get_previous_active_window() -> Result<Option<WindowInfo>> {
+  Ok(
+    x_win::get_previous_active_window()
+      .map_err(to_napi_error)?
+      .map(WindowInfo::from),
+  )
+}
+
+/**
+ * Retrieve the frontmost window intersecting the monitor at `monitorIndex`, following the order of the monitors list.
+ * Return `null` when no window overlaps this monitor or when the monitor does not exist (always the case on Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindowForDisplay } from '@miniben90/x-win';
+ *
+ * const windowOnSecondMonitor = activeWindowForDisplay(1);
+ * console.log(windowOnSecondMonitor);
+ * ```
+ */
+#[napi]
+pub fn active_window_for_display(monitor_index: u32) -> Result<Option<WindowInfo>> {
+  Ok(
+    x_win::get_active_window_for_display(monitor_index)
+      .map_err(to_napi_error)?
+      .map(WindowInfo::from),
+  )
+}
+
+/**
+ * Retrieve the connected monitors with their position, size and `scaleFactor`.
+ * It will use RandR on X11, `EnumDisplayMonitors` on Windows and the CoreGraphics displays on macOS,
+ * an empty array is returned on Wayland.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getMonitors } from '@miniben90/x-win';
+ *
+ * const primary = getMonitors().find((monitor) => monitor.isPrimary);
+ * ```
+ */
+#[napi]
+pub fn get_monitors() -> Result<Vec<MonitorInfo>> {
+  Ok(
+    x_win::get_monitors()
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(MonitorInfo::from)
+      .collect(),
+  )
+}
+
+/**
+ * Retrieve the workspaces (virtual desktops) of the session in their display order, the current one has `isCurrent` set.
+ * It will use `_NET_NUMBER_OF_DESKTOPS`, `_NET_DESKTOP_NAMES` and `_NET_CURRENT_DESKTOP` on X11 and the virtual desktops
+ * of explorer on Windows, an empty array is returned on macOS and Wayland.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getWorkspaces } from '@miniben90/x-win';
+ *
+ * const current = getWorkspaces().find((workspace) => workspace.isCurrent);
+ * ```
+ */
+#[napi]
+pub fn get_workspaces() -> Result<Vec<Workspace>> {
+  Ok(
+    x_win::get_workspaces()
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(Workspace::from)
+      .collect(),
+  )
+}
+
+/**
+ * Retrieve the bounds of the virtual desktop, the combined extent of all monitors.
+ * `x` and `y` are negative when a monitor is placed left of or above the primary monitor.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getDesktopBounds } from '@miniben90/x-win';
+ *
+ * const { x, y, width, height } = getDesktopBounds();
+ * console.log(x, y, width, height);
+ * ```
+ */
+#[napi]
+pub fn get_desktop_bounds() -> Result<WindowPosition> {
+  Ok(x_win::get_desktop_bounds().map_err(to_napi_error)?.into())
 }
 
 /**
  * Retrieve information about the currently open windows.
  * Returns an array of `WindowInfo`, each containing details about a specific open window.
+ * Use `options.includeUrl` to recover the url of supported browsers.
  *
  * # Example
  *
@@ -203,19 +616,41 @@ pub fn active_window_async() -> AsyncTask<ActiveWindowTask> {
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
  */
 #[napi]
-pub fn open_windows() -> Result<Vec<WindowInfo>> {
+pub fn open_windows(options: Option<OpenWindowsOptions>) -> Result<Vec<WindowInfo>> {
+  let options: x_win::OpenWindowsOptions = options.unwrap_or_default().into();
   Ok(
-    get_open_windows()
-      .unwrap()
+    get_open_windows_with_options(&options)
+      .map_err(to_napi_error)?
       .into_iter()
       .map(WindowInfo::from)
       .collect(),
   )
 }
 
+/**
+ * Same as `openWindows` but never throws, the failure (missing permission, no display...) is returned
+ * as `{ ok: false, error }` and the windows as `{ ok: true, value }`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { tryOpenWindows } from '@miniben90/x-win';
+ *
+ * const result = tryOpenWindows();
+ * const windows = result.ok ? result.value : [];
+ * ```
+ */
+#[napi(ts_return_type = "{ ok: true, value: Array<WindowInfo> } | { ok: false, error: string }")]
+pub fn try_open_windows(options: Option<OpenWindowsOptions>) -> OpenWindowsResult {
+  open_windows(options).into()
+}
+
 /**
  * Retrieve information about the currently open windows as a promise.
  * Returns an array of `WindowInfo`, each containing details about a specific open window.
+ * Use `options.includeUrl` to recover the url of supported browsers.
+ * `onWindow` is called with each window as soon as it is gathered (during the recovery on X11 and Windows) to render
+ * long lists incrementally, the promise still resolves with the whole list.
  *
  * # Example
  *
@@ -249,13 +684,444 @@ pub fn open_windows() -> Result<Vec<WindowInfo>> {
  *
  * It is recommended to use this function within a worker to mitigate potential recovery issues on MacOS.
  */
+#[napi(
+  ts_args_type = "options?: OpenWindowsOptions, onWindow?: (info: WindowInfo) => void",
+  ts_return_type = "Promise<Array<WindowInfo>>"
+)]
+pub fn open_windows_async(
+  options: Option<OpenWindowsOptions>,
+  on_window: Option<JsFunction>,
+) -> Result<AsyncTask<OpenWindowsTask>> {
+  let on_window = on_window
+    .map(|on_window| {
+      on_window.create_threadsafe_function(
+        0,
+        |ctx: napi::threadsafe_function::ThreadSafeCallContext<WindowInfo>| Ok(vec![ctx.value]),
+      )
+    })
+    .transpose()?;
+  Ok(AsyncTask::new(OpenWindowsTask { options, on_window }))
+}
+
+/**
+ * Retrieve the active window and the open windows in one call, the windows are recovered once
+ * and the active one is marked with `isActive` in the list.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { snapshot } from '@miniben90/x-win';
+ *
+ * const { active, windows } = snapshot();
+ * console.log(active.title, windows.length);
+ * ```
+ */
+#[napi]
+pub fn snapshot() -> Result<WindowSnapshot> {
+  Ok(x_win::get_snapshot().map_err(to_napi_error)?.into())
+}
+
+/**
+ * Retrieve the open windows grouped by their owning process, applications follow the order of their
+ * frontmost window in `openWindows`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { openApplications } from '@miniben90/x-win';
+ *
+ * for (const { info, frontmostWindow, windows } of openApplications()) {
+ *   console.log(info.name, frontmostWindow.title, windows.length);
+ * }
+ * ```
+ */
+#[napi]
+pub fn open_applications() -> Result<Vec<AppInfo>> {
+  Ok(
+    x_win::get_open_applications()
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(AppInfo::from)
+      .collect(),
+  )
+}
+
+/**
+ * Retrieve information about the currently open windows owned by a process.
+ * Returns an array of `WindowInfo` in the same order as `openWindows`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { getWindowsByPid } from '@miniben90/x-win';
+ *
+ * const windows = getWindowsByPid(1234);
+ * for (let i = 0; i < windows.length; i++) {
+ *   console.log(i, windows[i]);
+ * }
+ * ```
+ */
+#[napi]
+pub fn get_windows_by_pid(pid: i32) -> Result<Vec<WindowInfo>> {
+  if pid < 0 {
+    return Ok(Vec::new());
+  }
+  Ok(
+    x_win::get_windows_by_pid(pid as u32)
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(WindowInfo::from)
+      .collect(),
+  )
+}
+
+/**
+ * Retrieve the environment variables of a process (e.g. `VIRTUAL_ENV`, `TERM_PROGRAM`).
+ * Only processes of the same user are readable, only the current process is supported on Windows.
+ * An empty object is returned when the environment is not accessible.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getProcessEnv } from '@miniben90/x-win';
+ *
+ * const env = getProcessEnv(activeWindow().info.processId);
+ * console.log(env.VIRTUAL_ENV);
+ * ```
+ */
 #[napi]
-pub fn open_windows_async() -> AsyncTask<OpenWindowsTask> {
-  AsyncTask::new(OpenWindowsTask {})
+pub fn get_process_env(pid: i32) -> HashMap<String, String> {
+  if pid < 0 {
+    return HashMap::new();
+  }
+  x_win::get_process_env(pid as u32)
 }
 
 /**
- * Subscribe an observer thread to monitor changes in the active window.
+ * Retrieve information about the open windows with a title containing `query`, the titles are matched
+ * in Rust so only matching windows are returned to JavaScript.
+ * Use `options.caseInsensitive` to ignore case and `options.exact` to only match titles equal to `query`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { findWindowsByTitle } from '@miniben90/x-win';
+ *
+ * const windows = findWindowsByTitle('visual studio code', { caseInsensitive: true });
+ * ```
+ */
+#[napi]
+pub fn find_windows_by_title(
+  query: String,
+  options: Option<TitleMatchOptions>,
+) -> Result<Vec<WindowInfo>> {
+  let options: x_win::TitleMatchOptions = options.unwrap_or_default().into();
+  Ok(
+    x_win::find_windows_by_title(&query, &options)
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(WindowInfo::from)
+      .collect(),
+  )
+}
+
+/**
+ * Retrieve information about the open windows matching an accessibility identifier.
+ * It will use the AX identifier on macOS (accessibility permission required) and the UIAutomation `AutomationId` on Windows.
+ * Returns an empty array on Linux.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { findWindowsByAxIdentifier } from '@miniben90/x-win';
+ *
+ * const windows = findWindowsByAxIdentifier('MainWindow');
+ * ```
+ */
+#[napi]
+pub fn find_windows_by_ax_identifier(identifier: String) -> Result<Vec<WindowInfo>> {
+  Ok(
+    x_win::find_windows_by_ax_identifier(&identifier)
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(WindowInfo::from)
+      .collect(),
+  )
+}
+
+/**
+ * Capture a thumbnail of the window as PNG bytes, or as raw RGBA pixels with their `width`, `height` and `stride`
+ * when the `format` option is `'rgba'` (no encoding, the buffer can be uploaded to a texture or given to OpenCV).
+ * The image will be downscaled to fit in `maxDimension` (in pixels) when given.
+ * Throws an error when the window is minimized, occluded (X11) or cannot be captured (e.g. Wayland),
+ * screen recording permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, captureWindow } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const { id } = activeWindow();
+ * writeFileSync('thumbnail.png', captureWindow(id, 256));
+ *
+ * const { width, height, stride, data } = captureWindow(id, undefined, { format: 'rgba' });
+ * ```
+ */
+#[napi(
+  ts_generic_types = "F extends 'png' | 'rgba' = 'png'",
+  ts_args_type = "id: number, maxDimension?: number | undefined | null, options?: CaptureOptions & { format?: F }",
+  ts_return_type = "F extends 'rgba' ? RawImage : Buffer"
+)]
+pub fn capture_window(
+  id: u32,
+  max_dimension: Option<u32>,
+  options: Option<CaptureOptions>,
+) -> Result<Either<Buffer, RawImage>> {
+  let rgba = options.unwrap_or_default().is_rgba()?;
+  capture(id, max_dimension, rgba).map(to_capture_value)
+}
+
+/**
+ * Capture a thumbnail of the window without blocking the main thread,
+ * the capture and the PNG encoding run on the thread pool of libuv.
+ * Same as `captureWindow` but returns a promise.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, captureWindowAsync } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const { id } = activeWindow();
+ * captureWindowAsync(id, 256).then(thumbnail => writeFileSync('thumbnail.png', thumbnail));
+ * ```
+ */
+#[napi(
+  ts_generic_types = "F extends 'png' | 'rgba' = 'png'",
+  ts_args_type = "id: number, maxDimension?: number | undefined | null, options?: CaptureOptions & { format?: F }",
+  ts_return_type = "Promise<F extends 'rgba' ? RawImage : Buffer>"
+)]
+pub fn capture_window_async(
+  id: u32,
+  max_dimension: Option<u32>,
+  options: Option<CaptureOptions>,
+) -> Result<AsyncTask<CaptureWindowTask>> {
+  let rgba = options.unwrap_or_default().is_rgba()?;
+  Ok(AsyncTask::new(CaptureWindowTask {
+    id,
+    max_dimension,
+    rgba,
+  }))
+}
+
+/**
+ * Capture thumbnails of many windows as PNG bytes with a single session, faster than calling `captureWindow` in a loop.
+ * The images will be downscaled to fit in `maxDimension` (in pixels) when given.
+ * Windows that cannot be captured (minimized, occluded on X11...) are skipped, screen recording permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { captureWindows, openWindows } from '@miniben90/x-win';
+ * import { writeFileSync } from 'fs';
+ *
+ * const captures = captureWindows(openWindows().map(({ id }) => id), 256);
+ * for (const { id, image } of captures) {
+ *   writeFileSync(`thumbnail-${id}.png`, image);
+ * }
+ * ```
+ */
+#[napi]
+pub fn capture_windows(ids: Vec<u32>, max_dimension: Option<u32>) -> Result<Vec<WindowCapture>> {
+  Ok(
+    x_win::capture_windows(&ids, max_dimension)
+      .map_err(to_napi_error)?
+      .into_iter()
+      .map(WindowCapture::from)
+      .collect(),
+  )
+}
+
+/**
+ * Ask the window to close like the user would do, the application can still prompt to save changes and the process is never killed.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, closeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * closeWindow(id);
+ * ```
+ */
+#[napi]
+pub fn close_window(id: u32) -> Result<bool> {
+  x_win::close_window(id).map_err(to_napi_error)
+}
+
+/**
+ * Move and resize the window, fullscreen is toggled when `isFullScreen` differs from the current state.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, setWindowPosition } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * setWindowPosition(id, { x: 0, y: 0, width: 800, height: 600, isFullScreen: false });
+ * ```
+ */
+#[napi]
+pub fn set_window_position(id: u32, position: WindowPosition) -> Result<bool> {
+  x_win::set_window_position(id, &position.into()).map_err(to_napi_error)
+}
+
+/**
+ * Retrieve the position of the window only, cheaper than `openWindows` to follow a window frequently
+ * (e.g. to reposition an overlay). Return `null` when the window no longer exists.
+ * Throws an error when it is not supported (e.g. Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getWindowGeometry } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * const position = getWindowGeometry(id);
+ * ```
+ */
+#[napi]
+pub fn get_window_geometry(id: u32) -> Result<Option<WindowPosition>> {
+  Ok(
+    x_win::get_window_geometry(id)
+      .map_err(to_napi_error)?
+      .map(WindowPosition::from),
+  )
+}
+
+/**
+ * Read the raw value of a window property not modeled by `WindowInfo` (e.g. `_GTK_APPLICATION_ID`, `_STEAM_GAME`)
+ * to decode it in JavaScript. Return the bytes with the name of the type atom and the format, `null` when the property
+ * is not set or the window no longer exists. Only supported on X11, `null` is returned on Wayland and other platforms.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, getWindowProperty } from '@miniben90/x-win';
+ *
+ * const property = getWindowProperty(activeWindow().id, '_GTK_APPLICATION_ID');
+ * if (property?.typeName === 'UTF8_STRING') {
+ *   console.log(property.data.toString('utf8'));
+ * }
+ * ```
+ */
+#[napi]
+pub fn get_window_property(id: u32, name: String) -> Result<Option<WindowProperty>> {
+  Ok(
+    x_win::get_window_property(id, &name)
+      .map_err(to_napi_error)?
+      .map(WindowProperty::from),
+  )
+}
+
+/**
+ * Minimize the window.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, minimizeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * minimizeWindow(id);
+ * ```
+ */
+#[napi]
+pub fn minimize_window(id: u32) -> Result<bool> {
+  x_win::minimize_window(id).map_err(to_napi_error)
+}
+
+/**
+ * Maximize the window.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, maximizeWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * maximizeWindow(id);
+ * ```
+ */
+#[napi]
+pub fn maximize_window(id: u32) -> Result<bool> {
+  x_win::maximize_window(id).map_err(to_napi_error)
+}
+
+/**
+ * Restore the minimized or maximized window to its normal state.
+ * Return `false` when the window cannot receive the request.
+ * Throws an error when it is not supported (e.g. Wayland), accessibility permission is required on macOS.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindow, restoreWindow } from '@miniben90/x-win';
+ *
+ * const { id } = activeWindow();
+ * restoreWindow(id);
+ * ```
+ */
+#[napi]
+pub fn restore_window(id: u32) -> Result<bool> {
+  x_win::restore_window(id).map_err(to_napi_error)
+}
+
+/**
+ * Retrieve the topmost window under the mouse cursor.
+ * Return `null` when the cursor is over the desktop, throws an error when it is not supported (e.g. Wayland).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { windowUnderCursor } from '@miniben90/x-win';
+ *
+ * const hoveredWindow = windowUnderCursor();
+ * console.log(hoveredWindow);
+ * ```
+ */
+#[napi]
+pub fn window_under_cursor() -> Result<Option<WindowInfo>> {
+  Ok(
+    x_win::get_window_under_cursor()
+      .map_err(to_napi_error)?
+      .map(WindowInfo::from),
+  )
+}
+
+/**
+ * Subscribe an observer to monitor changes in the active window, the active window is polled by a single thread
+ * shared by all observers after each change notified by the X server on Linux and every 100ms otherwise
+ * (see `setActiveWindowPolling`).
+ * The callback receives the new active window and the kind of change (`focus`, `title`, `process` or `position`
+ * when it is watched).
+ * The first successfully polled window is emitted as a `focus` change without debounce unless `emitInitial` is `false`.
+ * When the active window can't be recovered (e.g. lost connection to the X server) the callback is called once
+ * with an empty window, the `error` change and the error message, polls are retried with a backoff until
+ * the active window is recovered and emitted again as a `focus` change.
+ * Use `debounceMs` and `titleChangeDebounceMs` options to only be notified once the active window
+ * or its title has been stable for the duration. Set `titleHistoryLength` to receive the recent titles
+ * of the window in `titleHistory`. Use `processNames` and `titleRegex` to only be notified when the new
+ * active window matches, an invalid `titleRegex` throws an error.
  *
  * # Example
  *
@@ -273,6 +1139,9 @@ pub fn open_windows_async() -> AsyncTask<OpenWindowsTask> {
  * const c = subscribeActiveWindow((info) => {
  *   t.log(c, info);
  * });
+ * const d = subscribeActiveWindow((info) => {
+ *   t.log(d, info);
+ * }, { debounceMs: 300, titleChangeDebounceMs: 1000 });
  *
  * unsubscribeAllActiveWindow();
  * ```
@@ -291,57 +1160,194 @@ pub fn open_windows_async() -> AsyncTask<OpenWindowsTask> {
  * const c = subscribeActiveWindow((info) => {
  *   t.log(c, info);
  * });
+ * const d = subscribeActiveWindow((info) => {
+ *   t.log(d, info);
+ * }, { debounceMs: 300, titleChangeDebounceMs: 1000 });
  *
  * unsubscribeAllActiveWindow();
  * ```
  *
  */
-#[napi(ts_args_type = "callback: (info: WindowInfo) => void")]
-pub fn subscribe_active_window(callback: JsFunction) -> Result<u32> {
-  let tsfn: ThreadsafeFunction<WindowInfo, ErrorStrategy::Fatal> = callback
-    .create_threadsafe_function(
+#[napi(
+  ts_args_type = "callback: (info: WindowInfo, change: 'focus' | 'title' | 'process' | 'position' | 'error', error?: string) => void, options?: SubscribeOptions"
+)]
+pub fn subscribe_active_window(
+  callback: JsFunction,
+  options: Option<SubscribeOptions>,
+) -> Result<u32> {
+  let options = options.unwrap_or_default();
+  let window_filter = options.window_filter()?;
+  let watched_fields = options.watched_fields()?;
+
+  let tsfn: ThreadsafeFunction<(WindowInfo, String, Option<String>), ErrorStrategy::Fatal> =
+    callback.create_threadsafe_function(
       0,
-      |ctx: napi::threadsafe_function::ThreadSafeCallContext<WindowInfo>| Ok(vec![ctx.value]),
+      |ctx: napi::threadsafe_function::ThreadSafeCallContext<(
+        WindowInfo,
+        String,
+        Option<String>,
+      )>| {
+        let (window_info, change, error) = ctx.value;
+        let mut args = vec![Either::A(window_info), Either::B(change)];
+        args.extend(error.map(Either::B));
+        Ok(args)
+      },
     )?;
 
-  let tsfn_clone: ThreadsafeFunction<WindowInfo, ErrorStrategy::Fatal> = tsfn.clone();
+  let tsfn_clone: ThreadsafeFunction<(WindowInfo, String, Option<String>), ErrorStrategy::Fatal> =
+    tsfn.clone();
 
-  let thread_manager = THREAD_MANAGER.lock().unwrap();
+  Ok(subscribe_changes(
+    options,
+    window_filter,
+    watched_fields,
+    move |window_info, change, error| {
+      tsfn_clone.call(
+        (window_info, change.to_owned(), error),
+        ThreadsafeFunctionCallMode::Blocking,
+      );
+    },
+  ))
+}
 
-  let id = thread_manager.start_thread(move |receiver| {
-    let mut current_window: WindowInfo = empty_entity().into();
-    loop {
-      match receiver.try_recv() {
-        Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-          break;
-        }
-        _ => {
-          let new_current_window = get_active_window().unwrap();
-          if new_current_window.id.ne(&current_window.id)
-            || new_current_window.title.ne(&current_window.title)
-            || new_current_window
-              .info
-              .process_id
-              .ne(&current_window.info.process_id)
-            || new_current_window.id.eq(&0)
-          {
-            current_window = new_current_window.clone().into();
-            tsfn_clone.call(
-              new_current_window.into(),
-              ThreadsafeFunctionCallMode::Blocking,
-            );
-          }
-          thread::sleep(Duration::from_millis(100));
+/**
+ * Subscribe an observer appending each change of the active window to the file at `path` as a line of JSON
+ * (newline-delimited JSON), written from the polling thread without calling JavaScript.
+ * Each line holds the `timestamp` in milliseconds since the Unix epoch, the kind of `change` and the `window`
+ * with the same fields as `WindowInfo` (`undefined` fields left out), `error` changes hold the `error` message too.
+ * The file is created when it does not exist and is never truncated or rotated, use `/dev/stdout` or `/dev/fd/<fd>`
+ * to write to a file descriptor on Linux and macOS.
+ * Options are the same as `subscribeActiveWindow`, an error is thrown when the file can't be opened.
+ * Return the id of the observer to use with `unsubscribeActiveWindow`.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { subscribeActiveWindowToFile, unsubscribeActiveWindow } from '@miniben90/x-win';
+ *
+ * const id = subscribeActiveWindowToFile('/tmp/active-windows.jsonl', { debounceMs: 300 });
+ *
+ * unsubscribeActiveWindow(id);
+ * ```
+ */
+#[napi]
+pub fn subscribe_active_window_to_file(
+  path: String,
+  options: Option<SubscribeOptions>,
+) -> Result<u32> {
+  let options = options.unwrap_or_default();
+  let window_filter = options.window_filter()?;
+  let watched_fields = options.watched_fields()?;
+  let mut writer = JsonLinesWriter::open(&path)
+    .map_err(|error| napi::Error::from_reason(format!("Unable to open {}: {}", path, error)))?;
+  Ok(subscribe_changes(
+    options,
+    window_filter,
+    watched_fields,
+    move |window_info, change, error| {
+      // Write errors (e.g. full disk) don't stop the subscription, next changes are still written
+      let _ = writer.write(&window_info, change, error.as_deref());
+    },
+  ))
+}
+
+/**
+ * Subscribe to the shared polling thread and call `emit` with the changes of the `watched_fields` of the active
+ * window kept by the debounce of `options` and `window_filter`.
+ * When the active window can't be recovered (e.g. lost connection to the display server) `emit` is called once
+ * with an empty window, the `error` change and the error message while polls are retried, the active window
+ * is emitted again as a `focus` change once it is recovered.
+ */
+fn subscribe_changes<F>(
+  options: SubscribeOptions,
+  window_filter: WindowFilter,
+  watched_fields: WatchedFields,
+  mut emit: F,
+) -> u32
+where
+  F: FnMut(WindowInfo, &str, Option<String>) + Send + 'static,
+{
+  let title_history_length = options.title_history_length.unwrap_or(0);
+  let emit_initial = options.emit_initial.unwrap_or(true);
+  let mut debouncer: ActiveWindowDebouncer = options.into();
+  debouncer.set_watched_fields(watched_fields);
+  let mut title_history = TitleHistory::new(title_history_length as usize);
+  let mut cpu_sampler = CpuSampler::new();
+  let mut disrupted = false;
+  let mut is_initial = true;
+  // Time of the last focus change, including the ones left out by the filter
+  let mut focused_at: Option<Instant> = None;
+
+  THREAD_MANAGER.subscribe(move |current_window| {
+    let current_window = match current_window {
+      Ok(current_window) => current_window,
+      Err(error) => {
+        // Failed retries of the same disruption are not emitted again
+        if !disrupted {
+          disrupted = true;
+          debouncer.reset();
+          // The window focused before the disruption is unknown
+          focused_at = None;
+          emit(
+            x_win::empty_entity().into(),
+            ERROR_CHANGE,
+            Some(error.to_string()),
+          );
         }
+        return false;
       }
+    };
+    disrupted = false;
+    let mut new_current_window = current_window.clone();
+    // CPU usage is computed between two polls
+    cpu_sampler.sample(&mut new_current_window);
+    // Every polled title is recorded, including the ones skipped by the debounce
+    title_history.record(&new_current_window);
+    let (change, is_emitted) = if is_initial {
+      // The current window is known from the first successful poll, even when it never changes
+      is_initial = false;
+      debouncer.set_emitted(&new_current_window);
+      (
+        Some((new_current_window.clone(), ActiveWindowChange::Focus)),
+        emit_initial,
+      )
+    } else {
+      (debouncer.update(&new_current_window), true)
+    };
+    let Some((window_info, change)) = change else {
+      // A debounced change needs later polls to be emitted
+      return debouncer.has_pending_change();
+    };
+    let previous_focus_duration = match change {
+      ActiveWindowChange::Focus | ActiveWindowChange::Process => {
+        let now = Instant::now();
+        focused_at.replace(now).map(|at| now.duration_since(at))
+      }
+      _ => None,
+    };
+    if is_emitted && window_filter.matches(&window_info) {
+      let window_id = window_info.id;
+      let mut window_info: WindowInfo = window_info.into();
+      window_info.previous_focus_duration_ms =
+        previous_focus_duration.map(|duration| duration.as_millis().min(i64::MAX as u128) as i64);
+      if title_history_length > 0 {
+        window_info.title_history = Some(
+          title_history
+            .get(window_id)
+            .into_iter()
+            .map(|entry| entry.into())
+            .collect(),
+        );
+      }
+      emit(window_info, change.as_str(), None);
     }
-  });
-
-  Ok(id.unwrap())
+    debouncer.has_pending_change()
+  })
 }
 
 /**
  * Terminate and unsubscribe a specific observer using their ID.
+ * Return `false` when no observer is subscribed with this ID (e.g. already unsubscribed).
  *
  * # Example
  *
@@ -386,17 +1392,12 @@ pub fn subscribe_active_window(callback: JsFunction) -> Result<u32> {
  * ```
  */
 #[napi]
-pub fn unsubscribe_active_window(thread_id: u32) -> Result<()> {
-  THREAD_MANAGER
-    .lock()
-    .unwrap()
-    .stop_thread(thread_id)
-    .unwrap();
-  Ok(())
+pub fn unsubscribe_active_window(thread_id: u32) -> Result<bool> {
+  Ok(THREAD_MANAGER.unsubscribe(thread_id))
 }
 
 /**
- * Terminate and unsubscribe all observer threads monitoring changes in the active window.
+ * Unsubscribe all observers monitoring changes in the active window.
  *
  * # Example
  *
@@ -438,18 +1439,116 @@ pub fn unsubscribe_active_window(thread_id: u32) -> Result<()> {
  */
 #[napi]
 pub fn unsubscribe_all_active_window() -> Result<()> {
-  THREAD_MANAGER.lock().unwrap().stop_all_threads().unwrap();
+  THREAD_MANAGER.unsubscribe_all();
   Ok(())
 }
 
+/**
+ * Unsubscribe all observers and wait until the polling thread is finished, so no callback is called once it returns
+ * (e.g. before an Electron renderer reloads or the module is required again).
+ * New subscriptions can be started afterwards and it is safe to call it multiple times.
+ * Return `false` when the polling thread did not finish within a second.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { shutdown } from '@miniben90/x-win';
+ *
+ * window.addEventListener('beforeunload', () => shutdown());
+ * ```
+ */
+#[napi]
+pub fn shutdown() -> bool {
+  THREAD_MANAGER.shutdown(Duration::from_secs(1))
+}
+
+/**
+ * Get the number of observers subscribed with `subscribeActiveWindow` and not unsubscribed yet.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { activeWindowSubscriptionCount, unsubscribeAllActiveWindow } from '@miniben90/x-win';
+ *
+ * unsubscribeAllActiveWindow();
+ * console.log(activeWindowSubscriptionCount()); // 0
+ * ```
+ */
+#[napi]
+pub fn active_window_subscription_count() -> u32 {
+  THREAD_MANAGER.subscription_count()
+}
+
+/**
+ * Change how often the thread shared by active window subscriptions polls the active window (default: every `100ms`).
+ * With `adaptive`, polls are done every `minIntervalMs` right after a change of the active window, its title or its
+ * position (the user is likely switching windows or dragging one), then the wait is multiplied by `backoffFactor` after each poll without change up to
+ * `maxIntervalMs`, reducing wakeups while the focus is stable.
+ * Pending `debounceMs` changes are checked every `minIntervalMs`. The running thread uses the new options after its
+ * current wait, they also apply to the polls done when the events of the X server are not available on Linux.
+ * An error is thrown for invalid options (e.g. `minIntervalMs` greater than `maxIntervalMs`).
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { setActiveWindowPolling } from '@miniben90/x-win';
+ *
+ * setActiveWindowPolling({ adaptive: true, minIntervalMs: 50, maxIntervalMs: 2000, backoffFactor: 2 });
+ * ```
+ */
+#[napi]
+pub fn set_active_window_polling(options: PollingOptions) -> Result<()> {
+  THREAD_MANAGER.set_interval(options.poll_interval()?);
+  Ok(())
+}
+
+/**
+ * Return the states of the permissions required to recover titles, urls and captures without prompting the user.
+ * On macOS titles and captures are silently empty without Screen Recording permission, this allows to tell
+ * a permission wall from a genuine empty result. Permissions are always granted on other platforms.
+ *
+ * # Example
+ *
+ * ```typescript
+ * import { checkPermissions } from '@miniben90/x-win';
+ *
+ * const { screenRecording, accessibility } = checkPermissions();
+ * ```
+ */
+#[napi]
+pub fn check_permissions() -> Result<PermissionStatus> {
+  x_win::check_permissions()
+    .map(PermissionStatus::from)
+    .map_err(to_napi_error)
+}
+
+/**
+ * Show the system prompts of the macOS permissions not granted yet and return their current states,
+ * the application has to be restarted once a permission is granted. Nothing is prompted on other platforms.
+ */
+#[napi]
+pub fn request_permissions() -> Result<PermissionStatus> {
+  x_win::request_permissions()
+    .map(PermissionStatus::from)
+    .map_err(to_napi_error)
+}
+
 /**
  * Install "@mininben90/x-win" Gnome extension required for Linux using Gnome > 41.
  * This function will write extension files needed to correctly detect working windows with Wayland desktop environment.
- * **Restart session will be require to install the gnome extension.**
+ * Return `ExtensionInstallInfo` with the extension folder, `installed` is `false` on X11 sessions which don't need the extension.
+ * An error describing the detected version is thrown when the running Gnome shell can't load the extension.
+ * **Restart session will be require to install the gnome extension** when `needsReload` is `true`.
+ * With `options.dryRun` nothing is written, the planned `files` are returned with `applicable` telling if the
+ * session needs the extension and `needsReload` if files would be written. A dry run never throws where the
+ * extension is not applicable (other platforms, Wayland without Gnome shell).
  */
 #[napi]
-pub fn install_extension() -> Result<bool> {
-  Ok(x_win::install_extension().unwrap())
+pub fn install_extension(options: Option<ExtensionInstallOptions>) -> Result<ExtensionInstallInfo> {
+  let options: x_win::ExtensionInstallOptions = options.unwrap_or_default().into();
+  x_win::install_extension_with_options(&options)
+    .map(ExtensionInstallInfo::from)
+    .map_err(to_napi_error)
 }
 
 /**
@@ -459,7 +1558,7 @@ pub fn install_extension() -> Result<bool> {
  */
 #[napi]
 pub fn uninstall_extension() -> Result<bool> {
-  Ok(x_win::uninstall_extension().unwrap())
+  x_win::uninstall_extension().map_err(to_napi_error)
 }
 
 /**
@@ -468,7 +1567,7 @@ pub fn uninstall_extension() -> Result<bool> {
  */
 #[napi]
 pub fn enable_extension() -> Result<bool> {
-  Ok(x_win::enable_extension().unwrap())
+  x_win::enable_extension().map_err(to_napi_error)
 }
 
 /**
@@ -477,5 +1576,37 @@ pub fn enable_extension() -> Result<bool> {
  */
 #[napi]
 pub fn disable_extension() -> Result<bool> {
-  Ok(x_win::disable_extension().unwrap())
+  x_win::disable_extension().map_err(to_napi_error)
+}
+
+/**
+ * Return a JSON dump of the environment (os, session type, window manager, permissions, monitors and windows) to join with bug reports.
+ * Use `redactTitles` to replace window titles by `<redacted>`.
+ *
+ * # Example
+ * ```javascript
+ * import { diagnostics } from '@miniben90/x-win';
+ *
+ * console.log(diagnostics(true));
+ * ```
+ */
+#[napi]
+pub fn diagnostics(redact_titles: Option<bool>) -> Result<String> {
+  x_win::diagnostics(redact_titles.unwrap_or(false)).map_err(to_napi_error)
+}
+
+/**
+ * Return the name of the running window manager (e.g. `Mutter`, `KWin`, `i3`), `""` when it can't be recovered.
+ * Read from `_NET_WM_NAME` of the `_NET_SUPPORTING_WM_CHECK` window on Linux.
+ *
+ * # Example
+ * ```javascript
+ * import { getWindowManagerName } from '@miniben90/x-win';
+ *
+ * console.log(getWindowManagerName());
+ * ```
+ */
+#[napi]
+pub fn get_window_manager_name() -> String {
+  x_win::get_window_manager_name()
 }
@@ -1,12 +1,12 @@
-use x_win::{get_active_window, XWinError};
+use x_win::get_active_window;
 
 fn main() {
   match get_active_window() {
     Ok(active_window) => {
       println!("active window: {:#?}", active_window);
     }
-    Err(XWinError) => {
-      println!("error occurred while getting the active window");
+    Err(error) => {
+      println!("error occurred while getting the active window: {}", error);
     }
   }
 }
@@ -1,12 +1,12 @@
-use x_win::{get_open_windows, XWinError};
+use x_win::get_open_windows;
 
 fn main() {
   match get_open_windows() {
     Ok(open_windows) => {
       println!("open windows: {:#?}", open_windows);
     }
-    Err(XWinError) => {
-      println!("error occurred while getting open windows");
+    Err(error) => {
+      println!("error occurred while getting open windows: {}", error);
     }
   }
 }
@@ -0,0 +1,167 @@
+#![deny(unused_imports)]
+
+use std::time::{Duration, Instant};
+
+use super::x_win_struct::{active_window_options::ActiveWindowOptions, window_info::WindowInfo};
+
+/**
+ * Default time to live of the cached active window, about one frame at 60 fps
+ */
+pub const DEFAULT_ACTIVE_WINDOW_CACHE_TTL: Duration = Duration::from_millis(16);
+
+struct CacheEntry {
+  /**
+   * Time when the recovery of the window started, the age of the entry is measured from it
+   */
+  recovered_at: Instant,
+  options: ActiveWindowOptions,
+  window_info: WindowInfo,
+}
+
+/**
+ * Memoize the last active window recovered with the same options for a short time to live,
+ * a zero time to live disables the cache. Errors are never cached.
+ */
+pub struct ActiveWindowCache {
+  ttl: Duration,
+  entry: Option<CacheEntry>,
+  /**
+   * Increased by each invalidation, a window recovered before an invalidation is never stored
+   */
+  generation: u64,
+}
+
+impl ActiveWindowCache {
+  pub fn new(ttl: Duration) -> Self {
+    Self {
+      ttl,
+      entry: None,
+      generation: 0,
+    }
+  }
+
+  pub fn set_ttl(&mut self, ttl: Duration) {
+    self.ttl = ttl;
+    self.invalidate();
+  }
+
+  /**
+   * Current generation to pass to `insert` with the window recovered after a cache miss
+   */
+  pub fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  /**
+   * Return the cached window when it was recovered with the same options and is younger than the time to live
+   */
+  pub fn get(&self, options: &ActiveWindowOptions) -> Option<WindowInfo> {
+    self.get_at(options, Instant::now())
+  }
+
+  /**
+   * Store a window which recovery started at `recovered_at`, ignored when the cache has been invalidated since `generation`
+   */
+  pub fn insert(
+    &mut self,
+    generation: u64,
+    recovered_at: Instant,
+    options: &ActiveWindowOptions,
+    window_info: &WindowInfo,
+  ) {
+    if generation != self.generation || self.ttl.is_zero() {
+      return;
+    }
+    self.entry = Some(CacheEntry {
+      recovered_at,
+      options: options.clone(),
+      window_info: window_info.clone(),
+    });
+  }
+
+  /**
+   * Forget the cached window, the next `get` is a miss
+   */
+  pub fn invalidate(&mut self) {
+    self.entry = None;
+    self.generation = self.generation.wrapping_add(1);
+  }
+
+  fn get_at(&self, options: &ActiveWindowOptions, now: Instant) -> Option<WindowInfo> {
+    self
+      .entry
+      .as_ref()
+      .filter(|entry| entry.options.eq(options))
+      .filter(|entry| now.saturating_duration_since(entry.recovered_at) < self.ttl)
+      .map(|entry| entry.window_info.clone())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::test_window;
+
+  fn cached_id_at(
+    cache: &ActiveWindowCache,
+    options: &ActiveWindowOptions,
+    now: Instant,
+  ) -> Option<u32> {
+    cache.get_at(options, now).map(|window_info| window_info.id)
+  }
+
+  #[test]
+  fn test_ttl() {
+    let mut cache = ActiveWindowCache::new(Duration::from_millis(16));
+    let options = ActiveWindowOptions::default();
+    let start = Instant::now();
+    assert_eq!(cached_id_at(&cache, &options, start), None);
+    cache.insert(cache.generation(), start, &options, &test_window(1, ""));
+    assert_eq!(cached_id_at(&cache, &options, start), Some(1));
+    assert_eq!(
+      cached_id_at(&cache, &options, start + Duration::from_millis(15)),
+      Some(1)
+    );
+    assert_eq!(
+      cached_id_at(&cache, &options, start + Duration::from_millis(16)),
+      None
+    );
+  }
+
+  #[test]
+  fn test_options_mismatch() {
+    let mut cache = ActiveWindowCache::new(Duration::from_millis(16));
+    let start = Instant::now();
+    cache.insert(
+      cache.generation(),
+      start,
+      &ActiveWindowOptions::default(),
+      &test_window(1, ""),
+    );
+    let options = ActiveWindowOptions {
+      include_url: true,
+      ..Default::default()
+    };
+    assert_eq!(cached_id_at(&cache, &options, start), None);
+  }
+
+  #[test]
+  fn test_invalidate() {
+    let mut cache = ActiveWindowCache::new(Duration::from_millis(16));
+    let options = ActiveWindowOptions::default();
+    let start = Instant::now();
+    cache.insert(cache.generation(), start, &options, &test_window(1, ""));
+    cache.invalidate();
+    assert_eq!(cached_id_at(&cache, &options, start), None);
+
+    // A window recovered before an invalidation is stale
+    let generation = cache.generation();
+    cache.invalidate();
+    cache.insert(generation, start, &options, &test_window(2, ""));
+    assert_eq!(cached_id_at(&cache, &options, start), None);
+
+    cache.set_ttl(Duration::ZERO);
+    cache.insert(cache.generation(), start, &options, &test_window(3, ""));
+    assert_eq!(cached_id_at(&cache, &options, start), None);
+  }
+}
@@ -0,0 +1,458 @@
+#![deny(unused_imports)]
+
+use std::time::{Duration, Instant};
+
+use super::x_win_struct::{
+  active_window_change::ActiveWindowChange, watched_fields::WatchedFields, window_info::WindowInfo,
+};
+
+/**
+ * Filter successive active windows and only return a change once it has been stable long enough.
+ * Focus changes (window id or process id) wait for `debounce`, title changes of the same window wait for
+ * `title_change_debounce`, a pending title change never delays a focus change.
+ * Only the watched fields (id, process and title by default) are compared to detect a change.
+ */
+pub struct ActiveWindowDebouncer {
+  debounce: Duration,
+  title_change_debounce: Duration,
+  watched_fields: WatchedFields,
+  /**
+   * Last emitted window, `None` until a window is emitted or after a reset
+   */
+  emitted: Option<WindowInfo>,
+  last: Option<WindowInfo>,
+  focus_since: Instant,
+  title_since: Instant,
+}
+
+impl ActiveWindowDebouncer {
+  pub fn new(debounce: Duration, title_change_debounce: Duration) -> Self {
+    let now = Instant::now();
+    Self {
+      debounce,
+      title_change_debounce,
+      watched_fields: WatchedFields::default(),
+      emitted: None,
+      last: None,
+      focus_since: now,
+      title_since: now,
+    }
+  }
+
+  /**
+   * Set the fields compared to detect a change of the active window
+   */
+  pub fn set_watched_fields(&mut self, watched_fields: WatchedFields) {
+    self.watched_fields = watched_fields;
+  }
+
+  /**
+   * Update the debouncer with the current active window and return it with the kind of change
+   * when the change has to be emitted
+   */
+  pub fn update(&mut self, window_info: &WindowInfo) -> Option<(WindowInfo, ActiveWindowChange)> {
+    self.update_at(window_info, Instant::now())
+  }
+
+  /**
+   * Consider the window as emitted without waiting for the debounce, e.g. for the initial window of a subscription.
+   * Following updates only report changes from it
+   */
+  pub fn set_emitted(&mut self, window_info: &WindowInfo) {
+    self.set_emitted_at(window_info, Instant::now());
+  }
+
+  fn set_emitted_at(&mut self, window_info: &WindowInfo, now: Instant) {
+    self.emitted = Some(window_info.clone());
+    self.last = Some(window_info.clone());
+    self.focus_since = now;
+    self.title_since = now;
+  }
+
+  /**
+   * To know if the last window differs from the emitted one and waits for the debounce,
+   * it is emitted by a later update once stable
+   */
+  pub fn has_pending_change(&self) -> bool {
+    match (&self.emitted, &self.last) {
+      (Some(emitted), Some(last)) => get_change(emitted, last, &self.watched_fields).is_some(),
+      (None, last) => last.is_some(),
+      _ => false,
+    }
+  }
+
+  /**
+   * Forget the emitted window, the next active window is emitted as a focus change even when
+   * it is the same window (e.g. after the connection to the display server has been lost)
+   */
+  pub fn reset(&mut self) {
+    self.emitted = None;
+    self.last = None;
+  }
+
+  fn update_at(
+    &mut self,
+    window_info: &WindowInfo,
+    now: Instant,
+  ) -> Option<(WindowInfo, ActiveWindowChange)> {
+    match &self.last {
+      Some(last) if is_same_focus(last, window_info) => {
+        if last.title.ne(&window_info.title) {
+          self.title_since = now;
+        }
+      }
+      _ => {
+        self.focus_since = now;
+        self.title_since = now;
+      }
+    }
+    self.last = Some(window_info.clone());
+
+    // The first window is always a focus change, even when no window is focused
+    let change = match &self.emitted {
+      Some(emitted) => get_change(emitted, window_info, &self.watched_fields)?,
+      None => ActiveWindowChange::Focus,
+    };
+    let is_ready = match change {
+      ActiveWindowChange::Title => {
+        now.duration_since(self.title_since) >= self.title_change_debounce
+      }
+      _ => now.duration_since(self.focus_since) >= self.debounce,
+    };
+
+    if is_ready {
+      self.emitted = Some(window_info.clone());
+      Some((window_info.clone(), change))
+    } else {
+      None
+    }
+  }
+}
+
+/**
+ * Get the kind of change of the watched fields between two active windows, `None` when nothing changed.
+ * Successive empty windows (nothing focused) are not a change
+ */
+pub(crate) fn get_change(
+  current: &WindowInfo,
+  new: &WindowInfo,
+  watched_fields: &WatchedFields,
+) -> Option<ActiveWindowChange> {
+  if watched_fields.id && new.id.ne(&current.id) {
+    Some(ActiveWindowChange::Focus)
+  } else if watched_fields.process && new.info.process_id.ne(&current.info.process_id) {
+    Some(ActiveWindowChange::Process)
+  } else if watched_fields.title && new.title.ne(&current.title) {
+    Some(ActiveWindowChange::Title)
+  } else if watched_fields.position && new.position.ne(&current.position) {
+    Some(ActiveWindowChange::Position)
+  } else {
+    None
+  }
+}
+
+fn is_same_focus(a: &WindowInfo, b: &WindowInfo) -> bool {
+  a.id.eq(&b.id) && a.info.process_id.eq(&b.info.process_id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::{empty_entity, test_window};
+
+  fn get_change_default(current: &WindowInfo, new: &WindowInfo) -> Option<ActiveWindowChange> {
+    get_change(current, new, &WatchedFields::default())
+  }
+
+  fn emitted_at(
+    debouncer: &mut ActiveWindowDebouncer,
+    window_info: &WindowInfo,
+    now: Instant,
+  ) -> Option<(u32, String)> {
+    debouncer
+      .update_at(window_info, now)
+      .map(|(window_info, _)| (window_info.id, window_info.title))
+  }
+
+  #[test]
+  fn test_get_change() {
+    let current = test_window(1, "a");
+    assert_eq!(get_change_default(&current, &test_window(1, "a")), None);
+    assert_eq!(
+      get_change_default(&current, &test_window(1, "b")),
+      Some(ActiveWindowChange::Title)
+    );
+    assert_eq!(
+      get_change_default(&current, &test_window(2, "a")),
+      Some(ActiveWindowChange::Focus)
+    );
+    let mut new = test_window(1, "b");
+    new.info.process_id = 2;
+    assert_eq!(
+      get_change_default(&current, &new),
+      Some(ActiveWindowChange::Process)
+    );
+  }
+
+  #[test]
+  fn test_watched_fields() {
+    let current = test_window(1, "a");
+    let mut moved = test_window(1, "a");
+    moved.position.x = 10;
+    assert_eq!(get_change_default(&current, &moved), None);
+    let fields = WatchedFields::from_names(&["position"]).unwrap();
+    assert_eq!(
+      get_change(&current, &moved, &fields),
+      Some(ActiveWindowChange::Position)
+    );
+    assert_eq!(get_change(&current, &test_window(1, "b"), &fields), None);
+
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::ZERO, Duration::ZERO);
+    debouncer.set_watched_fields(WatchedFields::from_names(&["id", "position"]).unwrap());
+    let start = Instant::now();
+    debouncer.set_emitted_at(&current, start);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "b"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(&mut debouncer, &moved, start),
+      Some((1, "a".to_owned()))
+    );
+  }
+
+  #[test]
+  fn test_nothing_focused() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::ZERO, Duration::ZERO);
+    let start = Instant::now();
+    assert_eq!(
+      emitted_at(&mut debouncer, &empty_entity(), start),
+      Some((0, "".to_owned()))
+    );
+    assert_eq!(emitted_at(&mut debouncer, &empty_entity(), start), None);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      Some((1, "a".to_owned()))
+    );
+    assert_eq!(
+      emitted_at(&mut debouncer, &empty_entity(), start),
+      Some((0, "".to_owned()))
+    );
+    assert_eq!(emitted_at(&mut debouncer, &empty_entity(), start), None);
+    assert_eq!(get_change_default(&empty_entity(), &empty_entity()), None);
+  }
+
+  #[test]
+  fn test_without_debounce() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::ZERO, Duration::ZERO);
+    let start = Instant::now();
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      Some((1, "a".to_owned()))
+    );
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "b"), start),
+      Some((1, "b".to_owned()))
+    );
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(2, "b"), start),
+      Some((2, "b".to_owned()))
+    );
+  }
+
+  #[test]
+  fn test_reset() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::from_millis(100), Duration::ZERO);
+    let start = Instant::now();
+    emitted_at(&mut debouncer, &test_window(1, "a"), start);
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "a"),
+        start + Duration::from_millis(100)
+      ),
+      Some((1, "a".to_owned()))
+    );
+
+    debouncer.reset();
+    let start = start + Duration::from_millis(200);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      None
+    );
+    let (window_info, change) = debouncer
+      .update_at(&test_window(1, "a"), start + Duration::from_millis(100))
+      .unwrap();
+    assert_eq!(window_info.id, 1);
+    assert_eq!(change, ActiveWindowChange::Focus);
+  }
+
+  #[test]
+  fn test_set_emitted() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::from_millis(100), Duration::ZERO);
+    let start = Instant::now();
+    debouncer.set_emitted_at(&test_window(1, "a"), start);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "b"),
+        start + Duration::from_millis(10)
+      ),
+      Some((1, "b".to_owned()))
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(2, "c"),
+        start + Duration::from_millis(20)
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn test_has_pending_change() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::from_millis(300), Duration::ZERO);
+    let start = Instant::now();
+    assert!(!debouncer.has_pending_change());
+    emitted_at(&mut debouncer, &test_window(1, "a"), start);
+    assert!(debouncer.has_pending_change());
+    emitted_at(
+      &mut debouncer,
+      &test_window(1, "a"),
+      start + Duration::from_millis(300),
+    );
+    assert!(!debouncer.has_pending_change());
+    // Back to the emitted window before the end of the debounce
+    emitted_at(
+      &mut debouncer,
+      &test_window(2, "b"),
+      start + Duration::from_millis(400),
+    );
+    assert!(debouncer.has_pending_change());
+    emitted_at(
+      &mut debouncer,
+      &test_window(1, "a"),
+      start + Duration::from_millis(500),
+    );
+    assert!(!debouncer.has_pending_change());
+  }
+
+  #[test]
+  fn test_focus_debounce() {
+    let mut debouncer = ActiveWindowDebouncer::new(Duration::from_millis(300), Duration::ZERO);
+    let start = Instant::now();
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "a"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "a"),
+        start + Duration::from_millis(300)
+      ),
+      Some((1, "a".to_owned()))
+    );
+
+    // Window 2 is only active for a short time
+    let start = start + Duration::from_millis(400);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(2, "b"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(3, "c"),
+        start + Duration::from_millis(200)
+      ),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(3, "c"),
+        start + Duration::from_millis(400)
+      ),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(3, "c"),
+        start + Duration::from_millis(500)
+      ),
+      Some((3, "c".to_owned()))
+    );
+  }
+
+  #[test]
+  fn test_title_change_debounce() {
+    let mut debouncer =
+      ActiveWindowDebouncer::new(Duration::from_millis(100), Duration::from_millis(1000));
+    let start = Instant::now();
+    emitted_at(&mut debouncer, &test_window(1, "a"), start);
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "a"),
+        start + Duration::from_millis(100)
+      ),
+      Some((1, "a".to_owned()))
+    );
+
+    let start = start + Duration::from_millis(200);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "b"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "c"),
+        start + Duration::from_millis(800)
+      ),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(1, "c"),
+        start + Duration::from_millis(1800)
+      ),
+      Some((1, "c".to_owned()))
+    );
+
+    // A focus change is not delayed by a pending title change
+    let start = start + Duration::from_millis(2000);
+    assert_eq!(
+      emitted_at(&mut debouncer, &test_window(1, "d"), start),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(2, "e"),
+        start + Duration::from_millis(50)
+      ),
+      None
+    );
+    assert_eq!(
+      emitted_at(
+        &mut debouncer,
+        &test_window(2, "e"),
+        start + Duration::from_millis(150)
+      ),
+      Some((2, "e".to_owned()))
+    );
+  }
+}
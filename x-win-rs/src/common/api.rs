@@ -1,25 +1,259 @@
 #![deny(unused_imports)]
 
+use crate::XWinError;
+
+use super::change_notifier::ChangeSource;
+
 use super::x_win_struct::{
-  icon_info::IconInfo, process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
-  window_position::WindowPosition,
+  active_window_options::ActiveWindowOptions, app_info::AppInfo, icon_info::IconInfo,
+  monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions, process_info::ProcessInfo,
+  raw_image::RawImage, usage_info::UsageInfo, window_info::WindowInfo,
+  window_position::WindowPosition, window_property::WindowProperty, workspace::Workspace,
 };
 
 pub trait Api {
   /**
-   * Return information of current active Window
+   * Return information of current active Window, an empty entity when no window is focused
+   */
+  fn get_active_window(&self, options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError>;
+
+  /**
+   * Return the id of the active window only, `0` when no window is focused.
+   * Recovered from `get_active_window` by default (Wayland), X11, Windows and macOS only read the id.
    */
-  fn get_active_window(&self) -> WindowInfo;
+  fn get_active_window_id(&self) -> Result<u32, XWinError> {
+    Ok(self.get_active_window(&ActiveWindowOptions::default())?.id)
+  }
+
+  /**
+   * Return `true` when the active window is full screen, `false` when no window is focused.
+   * Recovered from `is_full_screen` of `get_active_window` by default.
+   */
+  fn is_fullscreen_active(&self) -> Result<bool, XWinError> {
+    Ok(
+      self
+        .get_active_window(&ActiveWindowOptions::default())?
+        .position
+        .is_full_screen,
+    )
+  }
 
   /**
    * Return Array of open windows information
    */
-  fn get_open_windows(&self) -> Vec<WindowInfo>;
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError>;
+
+  /**
+   * Return Array of open windows information accepted by the filter. The filter is called during the enumeration
+   * on X11, Windows and macOS before the details that need another read are recovered (the process details on X11
+   * and macOS, the memory usage on X11 and Windows, the url). It is called once the windows are listed on Wayland
+   */
+  fn get_open_windows_filtered(
+    &self,
+    options: &OpenWindowsOptions,
+    filter: &mut dyn FnMut(&WindowInfo) -> bool,
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut windows = self.get_open_windows(options)?;
+    windows.retain(|window_info| filter(window_info));
+    Ok(windows)
+  }
+
+  /**
+   * Same as `get_open_windows` and call `on_window` with each window as soon as it is gathered (in enumeration order),
+   * changes made by `on_window` are kept in the returned Array. By default windows are passed once they are all recovered
+   */
+  fn get_open_windows_with_progress(
+    &self,
+    options: &OpenWindowsOptions,
+    on_window: &mut dyn FnMut(&mut WindowInfo),
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut windows = self.get_open_windows(options)?;
+    windows.iter_mut().for_each(on_window);
+    Ok(windows)
+  }
+
+  /**
+   * Return Array of open windows information owned by the process id, in the same order as `get_open_windows`
+   */
+  fn get_windows_by_pid(&self, process_id: u32) -> Result<Vec<WindowInfo>, XWinError> {
+    Ok(
+      self
+        .get_open_windows(&OpenWindowsOptions::default())?
+        .into_iter()
+        .filter(|window_info| window_info.info.process_id == process_id)
+        .collect(),
+    )
+  }
+
+  /**
+   * Return Array of open windows information matching the accessibility identifier
+   * (AX identifier on macOS, UIAutomation AutomationId on Windows).
+   * Not supported by default and will return an empty Array.
+   */
+  fn find_windows_by_ax_identifier(&self, _identifier: &str) -> Result<Vec<WindowInfo>, XWinError> {
+    Ok(Vec::new())
+  }
+
+  /**
+   * Return the RGBA pixels of the window downscaled to fit in `max_dimension`.
+   * Minimized or occluded windows return an error instead of a blank image.
+   * Not supported by default.
+   */
+  fn capture_window(
+    &self,
+    _window_id: u32,
+    _max_dimension: Option<u32>,
+  ) -> Result<RawImage, XWinError> {
+    Err(XWinError::PlatformError(
+      "window capture is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Return the RGBA pixels of the windows with their id, windows that cannot be captured are skipped.
+   * Captures are done one by one with `capture_window` by default.
+   */
+  fn capture_windows(
+    &self,
+    window_ids: &[u32],
+    max_dimension: Option<u32>,
+  ) -> Result<Vec<(u32, RawImage)>, XWinError> {
+    Ok(
+      window_ids
+        .iter()
+        .filter_map(|window_id| {
+          self
+            .capture_window(*window_id, max_dimension)
+            .ok()
+            .map(|image| (*window_id, image))
+        })
+        .collect(),
+    )
+  }
+
+  /**
+   * Ask the window to close like the user would do (the application can prompt to save changes),
+   * the process is never killed. Return `false` when the request cannot be sent to the window.
+   * Not supported by default.
+   */
+  fn close_window(&self, _window_id: u32) -> Result<bool, XWinError> {
+    Err(XWinError::PlatformError(
+      "window close is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Move and resize the window, fullscreen is toggled when `is_full_screen` differs from the current state.
+   * Return `false` when the request cannot be sent to the window.
+   * Not supported by default.
+   */
+  fn set_window_position(
+    &self,
+    _window_id: u32,
+    _position: &WindowPosition,
+  ) -> Result<bool, XWinError> {
+    Err(XWinError::PlatformError(
+      "window move is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Return the position of the window only, without recovering its other information.
+   * `None` when the window no longer exists. Not supported by default.
+   */
+  fn get_window_geometry(&self, _window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+    Err(XWinError::PlatformError(
+      "window geometry is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Minimize the window. Return `false` when the request cannot be sent to the window.
+   * Not supported by default.
+   */
+  fn minimize_window(&self, _window_id: u32) -> Result<bool, XWinError> {
+    Err(XWinError::PlatformError(
+      "window minimize is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Maximize the window. Return `false` when the request cannot be sent to the window.
+   * Not supported by default.
+   */
+  fn maximize_window(&self, _window_id: u32) -> Result<bool, XWinError> {
+    Err(XWinError::PlatformError(
+      "window maximize is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Restore the minimized or maximized window to its normal state.
+   * Return `false` when the request cannot be sent to the window.
+   * Not supported by default.
+   */
+  fn restore_window(&self, _window_id: u32) -> Result<bool, XWinError> {
+    Err(XWinError::PlatformError(
+      "window restore is not supported".to_owned(),
+    ))
+  }
+
+  /**
+   * Return the topmost window under the mouse cursor, `None` when the cursor is over the desktop.
+   * Not supported by default.
+   */
+  fn get_window_under_cursor(&self) -> Result<Option<WindowInfo>, XWinError> {
+    Err(XWinError::PlatformError(
+      "window under cursor is not supported".to_owned(),
+    ))
+  }
 
   /**
    * Return a base64 icon from window_info.info.path
    */
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo;
+
+  /**
+   * Return Array of connected monitors
+   */
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError>;
+
+  /**
+   * Return a source of events signaling changes of the windows, to wait for them without polling.
+   * Not supported by default and will return `None`.
+   */
+  fn change_source(&self) -> Option<Box<dyn ChangeSource>> {
+    None
+  }
+
+  /**
+   * Return Array of workspaces (virtual desktops) of the session in their display order.
+   * Not supported by default and will return an empty Array.
+   */
+  fn get_workspaces(&self) -> Result<Vec<Workspace>, XWinError> {
+    Ok(Vec::new())
+  }
+
+  /**
+   * Return the raw value of the property `name` of the window, `None` when the property is not set,
+   * the window no longer exists or properties can't be read (default).
+   */
+  fn get_window_property(
+    &self,
+    _window_id: u32,
+    _name: &str,
+  ) -> Result<Option<WindowProperty>, XWinError> {
+    Ok(None)
+  }
+
+  /**
+   * Return the bounds of the virtual desktop, the union of all monitors (origin can be negative
+   * when a monitor is left of or above the primary one)
+   */
+  fn get_desktop_bounds(&self) -> Result<WindowPosition, XWinError> {
+    get_monitors_bounds(&self.get_monitors()?)
+      .ok_or_else(|| XWinError::PlatformError("no monitor found".to_owned()))
+  }
 }
 
 /**
@@ -59,8 +293,385 @@ pub fn empty_entity() -> WindowInfo {
       path: "".to_string(),
       name: "".to_string(),
       exec_name: "".to_string(),
+      command_line: "".to_string(),
+      user: "".to_string(),
+      start_time: 0,
+      bundle_id: "".to_string(),
+      app_name: "".to_string(),
+      instance_name: "".to_string(),
     },
-    usage: UsageInfo { memory: 0 },
+    usage: UsageInfo::new(0),
     url: "".to_string(),
+    on_external_display: None,
+    z_order: 0,
+    workspace: None,
+    content_language: None,
+    monitor: None,
+    is_focused: false,
+    is_active: false,
+    opacity: 1.0,
+    parent_id: None,
+    is_minimized: false,
+    is_visible: false,
+    role: "".to_owned(),
+    focused_element: None,
+    frame: None,
+    always_on_top: false,
+    always_on_bottom: false,
+    native_handle: 0,
+  }
+}
+
+/**
+ * Window of tests with `id` used as window id and process id
+ */
+#[cfg(test)]
+pub(crate) fn test_window(id: u32, title: &str) -> WindowInfo {
+  let mut window_info = empty_entity();
+  window_info.id = id;
+  window_info.info.process_id = id;
+  window_info.title = title.to_owned();
+  window_info
+}
+
+/**
+ * Get the monitor containing the center of the window
+ */
+#[cfg(not(target_os = "windows"))]
+pub fn get_window_monitor(
+  position: &WindowPosition,
+  monitors: &[MonitorInfo],
+) -> Option<MonitorInfo> {
+  let center_x = position.x + position.width / 2;
+  let center_y = position.y + position.height / 2;
+  monitors
+    .iter()
+    .find(|monitor| {
+      center_x >= monitor.x
+        && center_x < monitor.x + monitor.width
+        && center_y >= monitor.y
+        && center_y < monitor.y + monitor.height
+    })
+    .cloned()
+}
+
+/**
+ * Convert the position of the window to logical pixels with the scale factor of its monitor.
+ * Edges are rounded so adjacent windows stay adjacent, macOS positions are already in logical points.
+ */
+pub fn to_logical_position(window_info: &mut WindowInfo) {
+  if cfg!(target_os = "macos") {
+    return;
+  }
+  let Some(scale_factor) = window_info
+    .monitor
+    .as_ref()
+    .map(|monitor| monitor.scale_factor)
+    .filter(|scale_factor| *scale_factor > 0.0 && *scale_factor != 1.0)
+  else {
+    return;
+  };
+  position_to_logical(&mut window_info.position, scale_factor);
+  if let Some(frame) = window_info.frame.as_mut() {
+    position_to_logical(frame, scale_factor);
+  }
+}
+
+fn position_to_logical(position: &mut WindowPosition, scale_factor: f64) {
+  let to_logical = |value: i32| (value as f64 / scale_factor).round() as i32;
+  let (right, bottom) = (position.x + position.width, position.y + position.height);
+  position.x = to_logical(position.x);
+  position.y = to_logical(position.y);
+  position.width = to_logical(right) - position.x;
+  position.height = to_logical(bottom) - position.y;
+}
+
+/**
+ * Get the frontmost window (highest `z_order`) intersecting the monitor
+ */
+pub fn get_frontmost_window_on_monitor(
+  windows: Vec<WindowInfo>,
+  monitor: &MonitorInfo,
+) -> Option<WindowInfo> {
+  get_frontmost_window(windows, |position| intersects_monitor(position, monitor))
+}
+
+/**
+ * Get the frontmost window (highest `z_order`) containing the point
+ */
+#[cfg(not(target_os = "windows"))]
+pub fn get_frontmost_window_at(windows: Vec<WindowInfo>, x: i32, y: i32) -> Option<WindowInfo> {
+  get_frontmost_window(windows, |position| {
+    x >= position.x
+      && x < position.x + position.width
+      && y >= position.y
+      && y < position.y + position.height
+  })
+}
+
+fn get_frontmost_window<F: Fn(&WindowPosition) -> bool>(
+  windows: Vec<WindowInfo>,
+  filter: F,
+) -> Option<WindowInfo> {
+  windows
+    .into_iter()
+    .filter(|window_info| filter(&window_info.position))
+    // max_by_key keeps the last maximum, first window of the list is kept when z_order is not available
+    .rev()
+    .max_by_key(|window_info| window_info.z_order)
+}
+
+/**
+ * Sort windows from front to back (highest `z_order` first), windows without a stacking order keep their order
+ */
+pub fn sort_front_to_back(windows: &mut [WindowInfo]) {
+  windows.sort_by_key(|window_info| std::cmp::Reverse(window_info.z_order));
+}
+
+/**
+ * Group windows by process id in the order of their first window, the frontmost window
+ * (highest `z_order`, the first one when it is not available) of each process is kept
+ */
+pub fn group_windows_by_process(windows: Vec<WindowInfo>) -> Vec<AppInfo> {
+  let mut groups: Vec<Vec<WindowInfo>> = Vec::new();
+  for window_info in windows {
+    match groups
+      .iter_mut()
+      .find(|group| group[0].info.process_id == window_info.info.process_id)
+    {
+      Some(group) => group.push(window_info),
+      None => groups.push(vec![window_info]),
+    }
+  }
+  groups
+    .into_iter()
+    .map(|windows| {
+      let frontmost_window = windows
+        .iter()
+        .rev()
+        .max_by_key(|window_info| window_info.z_order)
+        .cloned()
+        .unwrap_or_else(empty_entity);
+      AppInfo::new(frontmost_window.info.clone(), frontmost_window, windows)
+    })
+    .collect()
+}
+
+/**
+ * Parse a block of `KEY=VALUE` entries separated by NUL bytes (`/proc/<pid>/environ` format),
+ * entries without `=` are ignored and the first definition of a variable is kept
+ */
+#[cfg(not(target_os = "windows"))]
+pub fn parse_environ(data: &[u8]) -> std::collections::HashMap<String, String> {
+  let mut env = std::collections::HashMap::new();
+  for entry in data.split(|byte| *byte == 0) {
+    let entry = String::from_utf8_lossy(entry);
+    if let Some((key, value)) = entry.split_once('=') {
+      if !key.is_empty() {
+        env
+          .entry(key.to_owned())
+          .or_insert_with(|| value.to_owned());
+      }
+    }
+  }
+  env
+}
+
+/**
+ * Get the smallest position containing all monitors, `None` without monitor
+ */
+pub fn get_monitors_bounds(monitors: &[MonitorInfo]) -> Option<WindowPosition> {
+  let left = monitors.iter().map(|monitor| monitor.x).min()?;
+  let top = monitors.iter().map(|monitor| monitor.y).min()?;
+  let right = monitors
+    .iter()
+    .map(|monitor| monitor.x + monitor.width)
+    .max()?;
+  let bottom = monitors
+    .iter()
+    .map(|monitor| monitor.y + monitor.height)
+    .max()?;
+  Some(WindowPosition::new(
+    left,
+    top,
+    right - left,
+    bottom - top,
+    false,
+  ))
+}
+
+fn intersects_monitor(position: &WindowPosition, monitor: &MonitorInfo) -> bool {
+  position.width > 0
+    && position.height > 0
+    && position.x < monitor.x + monitor.width
+    && monitor.x < position.x + position.width
+    && position.y < monitor.y + monitor.height
+    && monitor.y < position.y + position.height
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn window(id: u32, x: i32, y: i32, z_order: u32) -> WindowInfo {
+    let mut window_info = empty_entity();
+    window_info.id = id;
+    window_info.position = WindowPosition {
+      x,
+      y,
+      width: 800,
+      height: 600,
+      is_full_screen: false,
+    };
+    window_info.z_order = z_order;
+    window_info
+  }
+
+  fn monitor(x: i32) -> MonitorInfo {
+    MonitorInfo {
+      id: 0,
+      name: "".to_owned(),
+      x,
+      y: 0,
+      width: 1920,
+      height: 1080,
+      is_primary: x == 0,
+      scale_factor: 1.0,
+    }
+  }
+
+  #[test]
+  fn test_get_frontmost_window_on_monitor() {
+    let windows = vec![
+      window(1, 100, 100, 1),
+      window(2, 1800, 100, 3),
+      window(3, 2000, 100, 2),
+    ];
+    let frontmost = get_frontmost_window_on_monitor(windows.clone(), &monitor(0));
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(2));
+    let frontmost = get_frontmost_window_on_monitor(windows.clone(), &monitor(1920));
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(2));
+    let frontmost = get_frontmost_window_on_monitor(windows, &monitor(5000));
+    assert!(frontmost.is_none());
+
+    // Without stacking order the first window of the list is kept
+    let windows = vec![window(1, 100, 100, 0), window(2, 200, 100, 0)];
+    let frontmost = get_frontmost_window_on_monitor(windows, &monitor(0));
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(1));
+  }
+
+  #[test]
+  fn test_sort_front_to_back() {
+    let mut windows = vec![
+      window(1, 0, 0, 0),
+      window(2, 0, 0, 2),
+      window(3, 0, 0, 1),
+      window(4, 0, 0, 0),
+    ];
+    sort_front_to_back(&mut windows);
+    let ids: Vec<u32> = windows.iter().map(|window_info| window_info.id).collect();
+    assert_eq!(ids, vec![2, 3, 1, 4]);
+  }
+
+  #[test]
+  fn test_group_windows_by_process() {
+    let mut windows = vec![
+      window(1, 0, 0, 4),
+      window(2, 0, 0, 3),
+      window(3, 0, 0, 2),
+      window(4, 0, 0, 5),
+    ];
+    windows[0].info.process_id = 10;
+    windows[1].info.process_id = 20;
+    windows[2].info.process_id = 10;
+    windows[3].info.process_id = 20;
+    let applications = group_windows_by_process(windows);
+    assert_eq!(applications.len(), 2);
+    assert_eq!(applications[0].info.process_id, 10);
+    assert_eq!(applications[0].frontmost_window.id, 1);
+    let ids: Vec<u32> = applications[0]
+      .windows
+      .iter()
+      .map(|window_info| window_info.id)
+      .collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(applications[1].info.process_id, 20);
+    assert_eq!(applications[1].frontmost_window.id, 4);
+    assert!(group_windows_by_process(Vec::new()).is_empty());
+  }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn test_parse_environ() {
+    let env = parse_environ(b"TERM_PROGRAM=vscode\0VIRTUAL_ENV=/home/a/.venv\0EQ=a=b\0invalid\0=x\0TERM_PROGRAM=other\0\0");
+    assert_eq!(env.len(), 3);
+    assert_eq!(env.get("TERM_PROGRAM").map(String::as_str), Some("vscode"));
+    assert_eq!(
+      env.get("VIRTUAL_ENV").map(String::as_str),
+      Some("/home/a/.venv")
+    );
+    assert_eq!(env.get("EQ").map(String::as_str), Some("a=b"));
+    assert!(parse_environ(b"").is_empty());
+  }
+
+  #[test]
+  fn test_get_monitors_bounds() {
+    assert!(get_monitors_bounds(&[]).is_none());
+    let mut above = monitor(-1280);
+    above.y = -1024;
+    above.height = 1024;
+    let bounds = get_monitors_bounds(&[monitor(0), monitor(1920), above]).unwrap();
+    assert_eq!(
+      (bounds.x, bounds.y, bounds.width, bounds.height),
+      (-1280, -1024, 5120, 2104)
+    );
+  }
+
+  #[test]
+  #[cfg(not(target_os = "macos"))]
+  fn test_to_logical_position() {
+    let mut window_info = window(1, 1921, -9, 0);
+    window_info.position.width = 1001;
+    to_logical_position(&mut window_info);
+    assert_eq!(window_info.position.x, 1921);
+
+    let mut screen = monitor(0);
+    screen.scale_factor = 1.5;
+    window_info.monitor = Some(screen.clone());
+    to_logical_position(&mut window_info);
+    let position = window_info.position;
+    assert_eq!((position.x, position.y), (1281, -6));
+    // Right edge 2922 / 1.5 = 1948
+    assert_eq!((position.width, position.height), (667, 400));
+
+    let mut window_info = window(1, 0, 0, 0);
+    window_info.frame = Some(WindowPosition {
+      x: -3,
+      y: -30,
+      width: 606,
+      height: 633,
+      is_full_screen: false,
+    });
+    screen.scale_factor = 2.0;
+    window_info.monitor = Some(screen);
+    to_logical_position(&mut window_info);
+    let frame = window_info.frame.unwrap();
+    assert_eq!(
+      (frame.x, frame.y, frame.width, frame.height),
+      (-2, -15, 304, 317)
+    );
+  }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn test_get_frontmost_window_at() {
+    let windows = vec![window(1, 0, 0, 1), window(2, 400, 300, 2)];
+    let frontmost = get_frontmost_window_at(windows.clone(), 500, 400);
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(2));
+    let frontmost = get_frontmost_window_at(windows.clone(), 100, 100);
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(1));
+    let frontmost = get_frontmost_window_at(windows.clone(), 800, 300);
+    assert_eq!(frontmost.map(|window_info| window_info.id), Some(2));
+    assert!(get_frontmost_window_at(windows, 1300, 100).is_none());
   }
 }
@@ -0,0 +1,124 @@
+#![deny(unused_imports)]
+
+use crate::{RawImage, XWinError};
+
+/**
+ * Check the size of a captured RGBA buffer and downscale it to fit in `max_dimension` when given
+ */
+pub fn to_raw_image(
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+  max_dimension: Option<u32>,
+) -> Result<RawImage, XWinError> {
+  if width == 0 || height == 0 || rgba.len() != (width * height * 4) as usize {
+    return Err(XWinError::PlatformError(
+      "captured image is empty or has an unexpected size".to_owned(),
+    ));
+  }
+  let (width, height, rgba) = scale_to_fit(width, height, rgba, max_dimension);
+  Ok(RawImage::new(width, height, rgba))
+}
+
+/**
+ * Encode a RGBA image as PNG
+ */
+pub fn encode_png(image: &RawImage) -> Result<Vec<u8>, XWinError> {
+  let mut png_data: Vec<u8> = Vec::new();
+  {
+    let mut encoder = png::Encoder::new(
+      std::io::Cursor::new(&mut png_data),
+      image.width,
+      image.height,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+      .write_header()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    writer
+      .write_image_data(&image.data)
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+  }
+  Ok(png_data)
+}
+
+/**
+ * Downscale a RGBA buffer by averaging source pixels so the largest side is at most `max_dimension`
+ */
+fn scale_to_fit(
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+  max_dimension: Option<u32>,
+) -> (u32, u32, Vec<u8>) {
+  let max_dimension = match max_dimension {
+    Some(max_dimension) if max_dimension > 0 && max_dimension < width.max(height) => max_dimension,
+    _ => return (width, height, rgba),
+  };
+  let scale = max_dimension as f64 / width.max(height) as f64;
+  let dst_width = ((width as f64 * scale).round() as u32).max(1);
+  let dst_height = ((height as f64 * scale).round() as u32).max(1);
+
+  let mut scaled: Vec<u8> = vec![0u8; (dst_width * dst_height * 4) as usize];
+  for dst_y in 0..dst_height {
+    let src_y0 = dst_y * height / dst_height;
+    let src_y1 = ((dst_y + 1) * height / dst_height).max(src_y0 + 1);
+    for dst_x in 0..dst_width {
+      let src_x0 = dst_x * width / dst_width;
+      let src_x1 = ((dst_x + 1) * width / dst_width).max(src_x0 + 1);
+      let mut sum = [0u32; 4];
+      for src_y in src_y0..src_y1 {
+        for src_x in src_x0..src_x1 {
+          let index = ((src_y * width + src_x) * 4) as usize;
+          for (channel, value) in sum.iter_mut().enumerate() {
+            *value += rgba[index + channel] as u32;
+          }
+        }
+      }
+      let count = (src_y1 - src_y0) * (src_x1 - src_x0);
+      let index = ((dst_y * dst_width + dst_x) * 4) as usize;
+      for (channel, value) in sum.iter().enumerate() {
+        scaled[index + channel] = (value / count) as u8;
+      }
+    }
+  }
+  (dst_width, dst_height, scaled)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_scale_to_fit() {
+    let rgba: Vec<u8> = [[0u8, 0, 0, 255], [255, 255, 255, 255]]
+      .iter()
+      .cycle()
+      .take(4 * 2)
+      .flatten()
+      .copied()
+      .collect();
+    let (width, height, scaled) = scale_to_fit(4, 2, rgba.clone(), Some(2));
+    assert_eq!((width, height), (2, 1));
+    assert_eq!(scaled, vec![127, 127, 127, 255, 127, 127, 127, 255]);
+
+    let (width, height, unchanged) = scale_to_fit(4, 2, rgba.clone(), Some(8));
+    assert_eq!((width, height), (4, 2));
+    assert_eq!(unchanged, rgba);
+  }
+
+  #[test]
+  fn test_to_raw_image() {
+    let image = to_raw_image(4, 2, vec![255u8; 32], Some(2)).unwrap();
+    assert_eq!((image.width, image.height, image.stride), (2, 1, 8));
+    assert_eq!(image.data.len(), 8);
+    assert!(to_raw_image(2, 2, vec![255u8; 4], None).is_err());
+  }
+
+  #[test]
+  fn test_encode_png() {
+    let png_data = encode_png(&RawImage::new(2, 2, vec![255u8; 16])).unwrap();
+    assert_eq!(&png_data[..8], b"\x89PNG\r\n\x1a\n");
+  }
+}
@@ -0,0 +1,91 @@
+#![deny(unused_imports)]
+
+use std::time::{Duration, Instant};
+
+use crate::XWinError;
+
+use super::api::Api;
+
+/**
+ * Shortest time between two notified changes, bursts of events (e.g. a window dragged) are coalesced
+ */
+const MIN_NOTIFY_INTERVAL: Duration = Duration::from_millis(20);
+
+/**
+ * Events of the display server signaling that the active window or the open windows may have changed
+ */
+pub trait ChangeSource: Send {
+  /**
+   * Wait at most `timeout` for a change, return `false` when no change has been received in time
+   */
+  fn wait(&mut self, timeout: Duration) -> Result<bool, XWinError>;
+}
+
+/**
+ * Wait for changes of the windows (focus, title, opened, closed or moved windows) reported by the display server
+ * instead of polling. Only available on Linux with X11 where `SubstructureNotify` and `PropertyChange` events are
+ * selected on the root windows, callers have to keep polling when `new` returns `None`.
+ */
+pub struct ChangeNotifier {
+  source: Box<dyn ChangeSource>,
+  notified_at: Option<Instant>,
+}
+
+impl ChangeNotifier {
+  /**
+   * Select the events of the display server, `None` when it is not supported or the selection failed
+   */
+  pub fn new() -> Option<Self> {
+    crate::init_platform_api()
+      .change_source()
+      .map(Self::from_source)
+  }
+
+  pub(crate) fn from_source(source: Box<dyn ChangeSource>) -> Self {
+    Self {
+      source,
+      notified_at: None,
+    }
+  }
+
+  /**
+   * Wait at most `timeout` for a change, return `Ok(false)` when nothing changed in time.
+   * Changes are notified at most every `MIN_NOTIFY_INTERVAL`, an error means the connection is lost
+   */
+  pub fn wait(&mut self, timeout: Duration) -> Result<bool, XWinError> {
+    let changed = self.source.wait(timeout)?;
+    if changed {
+      if let Some(notified_at) = self.notified_at {
+        let elapsed = notified_at.elapsed();
+        if elapsed < MIN_NOTIFY_INTERVAL {
+          std::thread::sleep(MIN_NOTIFY_INTERVAL - elapsed);
+        }
+      }
+      self.notified_at = Some(Instant::now());
+    }
+    Ok(changed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct EveryWait;
+
+  impl ChangeSource for EveryWait {
+    fn wait(&mut self, _timeout: Duration) -> Result<bool, XWinError> {
+      Ok(true)
+    }
+  }
+
+  #[test]
+  fn test_min_notify_interval() {
+    let mut notifier = ChangeNotifier::from_source(Box::new(EveryWait));
+    let start = Instant::now();
+    assert!(notifier.wait(Duration::ZERO).unwrap());
+    assert!(notifier.wait(Duration::ZERO).unwrap());
+    assert!(notifier.wait(Duration::ZERO).unwrap());
+    assert!(start.elapsed() >= MIN_NOTIFY_INTERVAL * 2);
+  }
+}
@@ -0,0 +1,175 @@
+#![deny(unused_imports)]
+
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+
+#[cfg(target_os = "linux")]
+use crate::linux::get_process_cpu_time;
+
+#[cfg(target_os = "macos")]
+use crate::macos::get_process_cpu_time;
+
+#[cfg(target_os = "windows")]
+use crate::win32::get_process_cpu_time;
+
+use super::x_win_struct::window_info::WindowInfo;
+
+/**
+ * Limit of processes with a previous sample, the process sampled least recently is forgotten first
+ */
+const CPU_SAMPLER_MAX_PROCESSES: usize = 100;
+
+/**
+ * Compute the CPU usage of window processes between two samples, a rate can't be computed from a single sample
+ * so the first sample of a process gives `0`
+ */
+#[derive(Default)]
+pub struct CpuSampler {
+  samples: HashMap<u32, (Instant, Duration)>,
+}
+
+impl CpuSampler {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /**
+   * Set `usage.cpu` of the window with the CPU usage of its process since the previous sample
+   */
+  pub fn sample(&mut self, window_info: &mut WindowInfo) {
+    self.sample_with(window_info, get_process_cpu_time, Instant::now());
+  }
+
+  /**
+   * Same as `sample` with the CPU time of processes read by `get_cpu_time` at `now`
+   */
+  fn sample_with<F: Fn(u32) -> Option<Duration>>(
+    &mut self,
+    window_info: &mut WindowInfo,
+    get_cpu_time: F,
+    now: Instant,
+  ) {
+    let process_id = window_info.info.process_id;
+    if process_id == 0 {
+      return;
+    }
+    if let Some(cpu_time) = get_cpu_time(process_id) {
+      window_info.usage.cpu = self.sample_at(process_id, cpu_time, now);
+    }
+  }
+
+  fn sample_at(&mut self, process_id: u32, cpu_time: Duration, now: Instant) -> f64 {
+    if !self.samples.contains_key(&process_id) && self.samples.len() >= CPU_SAMPLER_MAX_PROCESSES {
+      self.forget_least_recent_process();
+    }
+    match self.samples.insert(process_id, (now, cpu_time)) {
+      Some((previous_instant, previous_cpu_time)) => cpu_usage(
+        previous_cpu_time,
+        cpu_time,
+        now.duration_since(previous_instant),
+      ),
+      None => 0.0,
+    }
+  }
+
+  fn forget_least_recent_process(&mut self) {
+    let least_recent = self
+      .samples
+      .iter()
+      .min_by_key(|(_, (instant, _))| *instant)
+      .map(|(process_id, _)| *process_id);
+    if let Some(process_id) = least_recent {
+      self.samples.remove(&process_id);
+    }
+  }
+}
+
+/**
+ * Percentage of one core used during `elapsed`, above `100` when several cores are used
+ */
+fn cpu_usage(previous_cpu_time: Duration, cpu_time: Duration, elapsed: Duration) -> f64 {
+  if elapsed.is_zero() {
+    return 0.0;
+  }
+  // The process id may have been reused by a process with a lower CPU time
+  let used = cpu_time.saturating_sub(previous_cpu_time);
+  used.as_secs_f64() * 100.0 / elapsed.as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_cpu_usage() {
+    let second = Duration::from_secs(1);
+    assert_eq!(cpu_usage(second, Duration::from_millis(1500), second), 50.0);
+    assert_eq!(cpu_usage(second, 3 * second, second), 200.0);
+    assert_eq!(cpu_usage(3 * second, second, second), 0.0);
+    assert_eq!(cpu_usage(second, 2 * second, Duration::ZERO), 0.0);
+  }
+
+  #[test]
+  fn test_sample_at() {
+    let mut sampler = CpuSampler::new();
+    let start = Instant::now();
+    assert_eq!(sampler.sample_at(1, Duration::from_secs(1), start), 0.0);
+    assert_eq!(
+      sampler.sample_at(
+        1,
+        Duration::from_millis(1250),
+        start + Duration::from_secs(1)
+      ),
+      25.0
+    );
+    assert_eq!(
+      sampler.sample_at(2, Duration::from_secs(1), start + Duration::from_secs(1)),
+      0.0
+    );
+  }
+
+  #[test]
+  fn test_forget_least_recent_process() {
+    let mut sampler = CpuSampler::new();
+    let start = Instant::now();
+    for process_id in 0..CPU_SAMPLER_MAX_PROCESSES as u32 {
+      sampler.sample_at(
+        process_id + 1,
+        Duration::ZERO,
+        start + Duration::from_millis(process_id as u64),
+      );
+    }
+    sampler.sample_at(1000, Duration::ZERO, start + Duration::from_secs(1));
+    assert_eq!(sampler.samples.len(), CPU_SAMPLER_MAX_PROCESSES);
+    assert!(!sampler.samples.contains_key(&1));
+    assert!(sampler.samples.contains_key(&2));
+  }
+
+  #[test]
+  fn test_sample_with() {
+    let mut sampler = CpuSampler::new();
+    let mut window_info = crate::common::api::test_window(1, "");
+    let start = Instant::now();
+    sampler.sample_with(&mut window_info, |_| Some(Duration::from_secs(2)), start);
+    assert_eq!(window_info.usage.cpu, 0.0);
+    sampler.sample_with(
+      &mut window_info,
+      |_| Some(Duration::from_millis(2100)),
+      start + Duration::from_millis(200),
+    );
+    assert_eq!(window_info.usage.cpu, 50.0);
+    // Unreadable processes keep their previous usage
+    sampler.sample_with(&mut window_info, |_| None, start + Duration::from_secs(1));
+    assert_eq!(window_info.usage.cpu, 50.0);
+    let mut no_process = crate::empty_entity();
+    sampler.sample_with(
+      &mut no_process,
+      |_| Some(Duration::from_secs(1)),
+      start + Duration::from_secs(1),
+    );
+    assert_eq!(no_process.usage.cpu, 0.0);
+    assert!(!sampler.samples.contains_key(&0));
+  }
+}
@@ -0,0 +1,121 @@
+#![deny(unused_imports)]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use serde_json::{json, Map, Value};
+
+#[cfg(target_os = "linux")]
+use crate::linux::{get_permissions, os_version, session_type, window_manager_name};
+
+#[cfg(target_os = "macos")]
+use crate::macos::{get_permissions, os_version, session_type, window_manager_name};
+
+#[cfg(target_os = "windows")]
+use crate::win32::{get_permissions, os_version, session_type, window_manager_name};
+
+use crate::XWinError;
+
+use super::{
+  api::{os_name, Api},
+  x_win_struct::{
+    active_window_options::ActiveWindowOptions, open_windows_options::OpenWindowsOptions,
+    window_info::WindowInfo,
+  },
+};
+
+const REDACTED_TITLE: &str = "<redacted>";
+
+/**
+ * Build a JSON snapshot of the environment to join with bug reports.
+ * Platform calls that fail are reported with an `error` entry instead of aborting the dump.
+ */
+pub fn get_diagnostics(api: &impl Api, redact_titles: bool) -> Value {
+  let permissions: Map<String, Value> = get_permissions()
+    .into_iter()
+    .map(|(name, granted)| (name, Value::Bool(granted)))
+    .collect();
+
+  let monitors = match capture_result(|| api.get_monitors()) {
+    Ok(monitors) => Value::Array(
+      monitors
+        .iter()
+        .map(|monitor| {
+          json!({
+            "id": monitor.id,
+            "name": monitor.name,
+            "x": monitor.x,
+            "y": monitor.y,
+            "width": monitor.width,
+            "height": monitor.height,
+            "is_primary": monitor.is_primary,
+            "scale_factor": monitor.scale_factor,
+          })
+        })
+        .collect(),
+    ),
+    Err(error) => json!({ "error": error }),
+  };
+
+  let active_window =
+    match capture_result(|| api.get_active_window(&ActiveWindowOptions::default())) {
+      Ok(window_info) => window_summary(&window_info, redact_titles),
+      Err(error) => json!({ "error": error }),
+    };
+
+  let open_windows = match capture_result(|| api.get_open_windows(&OpenWindowsOptions::default())) {
+    Ok(windows) => json!({
+      "count": windows.len(),
+      "windows": windows
+        .iter()
+        .map(|window_info| window_summary(window_info, redact_titles))
+        .collect::<Vec<Value>>(),
+    }),
+    Err(error) => json!({ "error": error }),
+  };
+
+  json!({
+    "os": os_name(),
+    "os_version": os_version(),
+    "session_type": session_type(),
+    "window_manager": window_manager_name(),
+    "permissions": permissions,
+    "monitors": monitors,
+    "active_window": active_window,
+    "open_windows": open_windows,
+  })
+}
+
+fn window_summary(window_info: &WindowInfo, redact_titles: bool) -> Value {
+  let title = if redact_titles && !window_info.title.is_empty() {
+    REDACTED_TITLE
+  } else {
+    window_info.title.as_str()
+  };
+  json!({
+    "id": window_info.id,
+    "title": title,
+    "exec_name": window_info.info.exec_name,
+  })
+}
+
+/**
+ * Run a fallible platform call and turn its error or a panic into a message
+ */
+fn capture_result<T>(f: impl FnOnce() -> Result<T, XWinError>) -> Result<T, String> {
+  capture(f)?.map_err(|error| error.to_string())
+}
+
+/**
+ * Run a platform call and turn a panic into its message
+ */
+fn capture<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+  catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+      message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+      message.to_owned()
+    } else {
+      "unknown error".to_owned()
+    }
+  })
+}
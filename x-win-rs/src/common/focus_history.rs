@@ -0,0 +1,90 @@
+#![deny(unused_imports)]
+
+use std::collections::VecDeque;
+
+use super::x_win_struct::window_info::WindowInfo;
+
+/**
+ * Number of distinct windows remembered by the focus history
+ */
+pub const FOCUS_HISTORY_LENGTH: usize = 16;
+
+/**
+ * Most recently focused windows, each window is kept once with its last recovered information.
+ * The most recent window is first, the least recent one is dropped once `capacity` windows are kept
+ */
+pub struct FocusHistory {
+  capacity: usize,
+  windows: VecDeque<WindowInfo>,
+}
+
+impl FocusHistory {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      windows: VecDeque::new(),
+    }
+  }
+
+  /**
+   * Record the active window as the most recent focus, empty entities (no focused window) are ignored
+   */
+  pub fn record(&mut self, window_info: &WindowInfo) {
+    if self.capacity == 0 || window_info.id == 0 {
+      return;
+    }
+    self.windows.retain(|window| window.id != window_info.id);
+    self.windows.push_front(window_info.clone());
+    self.windows.truncate(self.capacity);
+  }
+
+  /**
+   * Most recent window focused before `current_id`, `None` when no other window has been recorded
+   */
+  pub fn previous(&self, current_id: u32) -> Option<WindowInfo> {
+    self
+      .windows
+      .iter()
+      .find(|window| window.id != current_id)
+      .cloned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::{empty_entity, test_window};
+
+  #[test]
+  fn test_previous() {
+    let mut history = FocusHistory::new(FOCUS_HISTORY_LENGTH);
+    assert!(history.previous(0).is_none());
+    history.record(&test_window(1, "a"));
+    assert!(history.previous(1).is_none());
+    assert_eq!(history.previous(0).map(|window| window.id), Some(1));
+    history.record(&test_window(2, "b"));
+    // Repeated polls of the same window keep the prior focus
+    history.record(&test_window(2, "b"));
+    history.record(&empty_entity());
+    assert_eq!(history.previous(2).map(|window| window.id), Some(1));
+    history.record(&test_window(1, "c"));
+    let previous = history.previous(1).unwrap();
+    assert_eq!(previous.id, 2);
+    history.record(&test_window(2, "d"));
+    assert_eq!(
+      history.previous(2).map(|window| window.title),
+      Some("c".to_owned())
+    );
+  }
+
+  #[test]
+  fn test_capacity() {
+    let mut history = FocusHistory::new(2);
+    history.record(&test_window(1, "a"));
+    history.record(&test_window(2, "b"));
+    history.record(&test_window(3, "c"));
+    assert_eq!(history.previous(3).map(|window| window.id), Some(2));
+    assert_eq!(history.previous(2).map(|window| window.id), Some(3));
+    assert!(history.windows.iter().all(|window| window.id != 1));
+  }
+}
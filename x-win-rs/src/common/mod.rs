@@ -1,4 +1,14 @@
 #![deny(unused_imports)]
 
+pub mod active_window_cache;
+pub mod active_window_debouncer;
 pub mod api;
+pub mod capture;
+pub mod change_notifier;
+pub mod cpu_sampler;
+pub mod diagnostics;
+pub mod focus_history;
+pub mod title_history;
+pub mod window_filter;
+pub mod window_tracker;
 pub mod x_win_struct;
@@ -0,0 +1,143 @@
+#![deny(unused_imports)]
+
+use std::{
+  collections::{HashMap, VecDeque},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::x_win_struct::{title_entry::TitleEntry, window_info::WindowInfo};
+
+/**
+ * Limit of windows with a history, the window updated least recently is forgotten first
+ */
+const TITLE_HISTORY_MAX_WINDOWS: usize = 100;
+
+/**
+ * Keep the recent titles of each active window in a ring buffer of `capacity` entries,
+ * the oldest title is dropped once the buffer is full
+ */
+pub struct TitleHistory {
+  capacity: usize,
+  windows: HashMap<u32, VecDeque<TitleEntry>>,
+}
+
+impl TitleHistory {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      windows: HashMap::new(),
+    }
+  }
+
+  /**
+   * Record the title of the active window when it differs from the last one of this window
+   */
+  pub fn record(&mut self, window_info: &WindowInfo) {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_millis() as u64)
+      .unwrap_or(0);
+    self.record_at(window_info, timestamp)
+  }
+
+  fn record_at(&mut self, window_info: &WindowInfo, timestamp: u64) {
+    if self.capacity == 0 || window_info.id == 0 {
+      return;
+    }
+    if !self.windows.contains_key(&window_info.id)
+      && self.windows.len() >= TITLE_HISTORY_MAX_WINDOWS
+    {
+      self.forget_least_recent_window();
+    }
+    let entries = self.windows.entry(window_info.id).or_default();
+    if entries
+      .back()
+      .is_some_and(|entry| entry.title.eq(&window_info.title))
+    {
+      return;
+    }
+    if entries.len() >= self.capacity {
+      entries.pop_front();
+    }
+    entries.push_back(TitleEntry::new(timestamp, window_info.title.clone()));
+  }
+
+  /**
+   * Get the recorded titles of a window, oldest first
+   */
+  pub fn get(&self, window_id: u32) -> Vec<TitleEntry> {
+    self
+      .windows
+      .get(&window_id)
+      .map(|entries| entries.iter().cloned().collect())
+      .unwrap_or_default()
+  }
+
+  fn forget_least_recent_window(&mut self) {
+    let least_recent = self
+      .windows
+      .iter()
+      .min_by_key(|(_, entries)| entries.back().map(|entry| entry.timestamp).unwrap_or(0))
+      .map(|(id, _)| *id);
+    if let Some(id) = least_recent {
+      self.windows.remove(&id);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::test_window;
+
+  fn titles(history: &TitleHistory, window_id: u32) -> Vec<(u64, String)> {
+    history
+      .get(window_id)
+      .into_iter()
+      .map(|entry| (entry.timestamp, entry.title))
+      .collect()
+  }
+
+  #[test]
+  fn test_record_title_changes() {
+    let mut history = TitleHistory::new(3);
+    history.record_at(&test_window(1, "a"), 1);
+    history.record_at(&test_window(1, "a"), 2);
+    history.record_at(&test_window(2, "x"), 3);
+    history.record_at(&test_window(1, "b"), 4);
+    history.record_at(&test_window(0, ""), 5);
+    assert_eq!(
+      titles(&history, 1),
+      vec![(1, "a".to_owned()), (4, "b".to_owned())]
+    );
+    assert_eq!(titles(&history, 2), vec![(3, "x".to_owned())]);
+    assert_eq!(titles(&history, 0), vec![]);
+  }
+
+  #[test]
+  fn test_capacity() {
+    let mut history = TitleHistory::new(2);
+    for (timestamp, title) in ["a", "b", "c"].iter().enumerate() {
+      history.record_at(&test_window(1, title), timestamp as u64);
+    }
+    assert_eq!(
+      titles(&history, 1),
+      vec![(1, "b".to_owned()), (2, "c".to_owned())]
+    );
+
+    let mut history = TitleHistory::new(0);
+    history.record_at(&test_window(1, "a"), 0);
+    assert_eq!(titles(&history, 1), vec![]);
+  }
+
+  #[test]
+  fn test_max_windows() {
+    let mut history = TitleHistory::new(1);
+    for id in 1..=TITLE_HISTORY_MAX_WINDOWS as u32 + 1 {
+      history.record_at(&test_window(id, "a"), id as u64);
+    }
+    assert_eq!(titles(&history, 1), vec![]);
+    assert_eq!(titles(&history, 2), vec![(2, "a".to_owned())]);
+    assert_eq!(history.windows.len(), TITLE_HISTORY_MAX_WINDOWS);
+  }
+}
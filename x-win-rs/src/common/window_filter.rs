@@ -0,0 +1,100 @@
+#![deny(unused_imports)]
+
+use regex::Regex;
+
+use crate::XWinError;
+
+use super::x_win_struct::window_info::WindowInfo;
+
+/**
+ * Filter windows by process name and title pattern, a window is accepted when it matches both criteria.
+ * Process names are compared case-insensitively with the executable name and the application name,
+ * every window is accepted when no criteria is set.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct WindowFilter {
+  process_names: Vec<String>,
+  title_regex: Option<Regex>,
+}
+
+impl WindowFilter {
+  /**
+   * Create a filter, an invalid `title_regex` returns an error
+   */
+  pub fn new(process_names: Vec<String>, title_regex: Option<&str>) -> Result<Self, XWinError> {
+    let title_regex = title_regex
+      .map(Regex::new)
+      .transpose()
+      .map_err(|error| XWinError::PlatformError(format!("invalid title regex: {}", error)))?;
+    Ok(Self {
+      process_names: process_names
+        .into_iter()
+        .map(|name| name.to_lowercase())
+        .collect(),
+      title_regex,
+    })
+  }
+
+  /**
+   * To know if the window is accepted by the filter
+   */
+  pub fn matches(&self, window_info: &WindowInfo) -> bool {
+    let is_process_matching = self.process_names.is_empty()
+      || self.process_names.iter().any(|name| {
+        name.eq(&window_info.info.exec_name.to_lowercase())
+          || name.eq(&window_info.info.name.to_lowercase())
+      });
+    is_process_matching
+      && self
+        .title_regex
+        .as_ref()
+        .is_none_or(|title_regex| title_regex.is_match(&window_info.title))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::empty_entity;
+
+  fn window(exec_name: &str, name: &str, title: &str) -> WindowInfo {
+    let mut window_info = empty_entity();
+    window_info.id = 1;
+    window_info.info.exec_name = exec_name.to_owned();
+    window_info.info.name = name.to_owned();
+    window_info.title = title.to_owned();
+    window_info
+  }
+
+  #[test]
+  fn test_matches() {
+    let code = window("code", "Visual Studio Code", "main.rs - x-win");
+    let firefox = window("firefox", "Firefox", "GitHub — Mozilla Firefox");
+
+    assert!(WindowFilter::default().matches(&code));
+
+    let filter = WindowFilter::new(vec!["Code".to_owned(), "firefox".to_owned()], None).unwrap();
+    assert!(filter.matches(&code));
+    assert!(filter.matches(&firefox));
+    assert!(!filter.matches(&window("gedit", "Text Editor", "main.rs")));
+
+    let filter = WindowFilter::new(vec!["visual studio code".to_owned()], None).unwrap();
+    assert!(filter.matches(&code));
+
+    let filter = WindowFilter::new(vec![], Some(r"\.rs\b")).unwrap();
+    assert!(filter.matches(&code));
+    assert!(!filter.matches(&firefox));
+
+    let filter = WindowFilter::new(vec!["firefox".to_owned()], Some(r"\.rs\b")).unwrap();
+    assert!(!filter.matches(&code));
+    assert!(!filter.matches(&firefox));
+  }
+
+  #[test]
+  fn test_invalid_title_regex() {
+    assert!(matches!(
+      WindowFilter::new(vec![], Some("(")),
+      Err(XWinError::PlatformError(_))
+    ));
+  }
+}
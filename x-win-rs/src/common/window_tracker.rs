@@ -0,0 +1,154 @@
+#![deny(unused_imports)]
+
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+
+use super::x_win_struct::{window_event::WindowEvent, window_info::WindowInfo};
+
+/**
+ * Compare successive snapshots of open windows and emit `Opened`/`Closed` events.
+ * A window must be absent for the whole `close_grace_period` before `Closed` is emitted,
+ * the pending close is cancelled if the window reappears meanwhile.
+ */
+pub struct WindowTracker {
+  close_grace_period: Duration,
+  windows: HashMap<u32, WindowInfo>,
+  missing_since: HashMap<u32, Instant>,
+}
+
+impl WindowTracker {
+  pub fn new(close_grace_period: Duration) -> Self {
+    Self {
+      close_grace_period,
+      windows: HashMap::new(),
+      missing_since: HashMap::new(),
+    }
+  }
+
+  /**
+   * Update the tracker with the current open windows and return the events since the last update
+   */
+  pub fn update(&mut self, windows: &[WindowInfo]) -> Vec<WindowEvent> {
+    self.update_at(windows, Instant::now())
+  }
+
+  fn update_at(&mut self, windows: &[WindowInfo], now: Instant) -> Vec<WindowEvent> {
+    let mut events: Vec<WindowEvent> = Vec::new();
+
+    for window_info in windows {
+      self.missing_since.remove(&window_info.id);
+      if self
+        .windows
+        .insert(window_info.id, window_info.clone())
+        .is_none()
+      {
+        events.push(WindowEvent::Opened(window_info.clone()));
+      }
+    }
+
+    let mut missing_ids: Vec<u32> = self
+      .windows
+      .keys()
+      .filter(|id| !windows.iter().any(|window_info| window_info.id == **id))
+      .copied()
+      .collect();
+    missing_ids.sort_unstable();
+
+    for id in missing_ids {
+      let since = *self.missing_since.entry(id).or_insert(now);
+      if now.duration_since(since) >= self.close_grace_period {
+        self.missing_since.remove(&id);
+        if let Some(window_info) = self.windows.remove(&id) {
+          events.push(WindowEvent::Closed(window_info));
+        }
+      }
+    }
+
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::api::test_window;
+
+  fn closed_ids(events: &[WindowEvent]) -> Vec<u32> {
+    events
+      .iter()
+      .filter_map(|event| match event {
+        WindowEvent::Closed(window_info) => Some(window_info.id),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn opened_ids(events: &[WindowEvent]) -> Vec<u32> {
+    events
+      .iter()
+      .filter_map(|event| match event {
+        WindowEvent::Opened(window_info) => Some(window_info.id),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_window_reappearing_within_grace_period() {
+    let mut tracker = WindowTracker::new(Duration::from_millis(500));
+    let start = Instant::now();
+
+    let events = tracker.update_at(&[test_window(1, ""), test_window(2, "")], start);
+    assert_eq!(opened_ids(&events), vec![1, 2]);
+
+    let events = tracker.update_at(&[test_window(1, "")], start + Duration::from_millis(100));
+    assert!(events.is_empty());
+
+    let events = tracker.update_at(
+      &[test_window(1, ""), test_window(2, "")],
+      start + Duration::from_millis(300),
+    );
+    assert!(events.is_empty());
+
+    let events = tracker.update_at(
+      &[test_window(1, ""), test_window(2, "")],
+      start + Duration::from_millis(900),
+    );
+    assert!(events.is_empty());
+  }
+
+  #[test]
+  fn test_window_missing_beyond_grace_period() {
+    let mut tracker = WindowTracker::new(Duration::from_millis(500));
+    let start = Instant::now();
+
+    tracker.update_at(&[test_window(1, ""), test_window(2, "")], start);
+
+    let events = tracker.update_at(&[test_window(1, "")], start + Duration::from_millis(100));
+    assert!(events.is_empty());
+
+    let events = tracker.update_at(&[test_window(1, "")], start + Duration::from_millis(400));
+    assert!(events.is_empty());
+
+    let events = tracker.update_at(&[test_window(1, "")], start + Duration::from_millis(600));
+    assert_eq!(closed_ids(&events), vec![2]);
+
+    let events = tracker.update_at(
+      &[test_window(1, ""), test_window(2, "")],
+      start + Duration::from_millis(700),
+    );
+    assert_eq!(opened_ids(&events), vec![2]);
+  }
+
+  #[test]
+  fn test_window_closed_without_grace_period() {
+    let mut tracker = WindowTracker::new(Duration::ZERO);
+    let start = Instant::now();
+
+    tracker.update_at(&[test_window(1, ""), test_window(2, "")], start);
+    let events = tracker.update_at(&[test_window(2, "")], start);
+    assert_eq!(closed_ids(&events), vec![1]);
+  }
+}
@@ -0,0 +1,35 @@
+#![deny(unused_imports)]
+
+/**
+ * Kind of change between two active windows
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveWindowChange {
+  /**
+   * Another window is active
+   */
+  Focus,
+  /**
+   * Same window id but owned by another process
+   */
+  Process,
+  /**
+   * Same window with a new title
+   */
+  Title,
+  /**
+   * Same window moved or resized, only reported when the position is watched
+   */
+  Position,
+}
+
+impl ActiveWindowChange {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ActiveWindowChange::Focus => "focus",
+      ActiveWindowChange::Process => "process",
+      ActiveWindowChange::Title => "title",
+      ActiveWindowChange::Position => "position",
+    }
+  }
+}
@@ -0,0 +1,36 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to recover the active window
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActiveWindowOptions {
+  /**
+   * Recover the url of the active tab when the window belongs to a supported browser
+   */
+  pub include_url: bool,
+  /**
+   * Recover the language of the focused document (requires accessibility permission on macOS)
+   */
+  pub include_content_language: bool,
+  /**
+   * Recover the focused UI element of the window with AT-SPI on Linux, UI Automation on Windows
+   * and the AX API on macOS (requires accessibility permission)
+   */
+  pub include_focused_element: bool,
+  /**
+   * Return positions in logical pixels, physical geometry is divided by the scale factor of the window monitor
+   * (positions are already logical on macOS). Native coordinates are returned by default
+   */
+  pub logical_pixels: bool,
+  /**
+   * Maximum time in milliseconds to wait for the X server on Linux, `XWinError::Timeout` is returned when it is exceeded.
+   * Replies are awaited without limit when not set
+   */
+  pub timeout_ms: Option<u32>,
+  /**
+   * X display to connect to on Linux (e.g. `:1`), windows are then always recovered through X11.
+   * `$DISPLAY` is used when not set
+   */
+  pub display: Option<String>,
+}
@@ -0,0 +1,32 @@
+#![deny(unused_imports)]
+
+use super::{process_info::ProcessInfo, window_info::WindowInfo};
+
+/**
+ * Struct to store the open windows of an application (owning process)
+ */
+#[derive(Debug, Clone)]
+pub struct AppInfo {
+  /**
+   * Process owning the windows
+   */
+  pub info: ProcessInfo,
+  /**
+   * Frontmost window of the application (highest `z_order`)
+   */
+  pub frontmost_window: WindowInfo,
+  /**
+   * Open windows of the application, in the same order as `get_open_windows`
+   */
+  pub windows: Vec<WindowInfo>,
+}
+
+impl AppInfo {
+  pub fn new(info: ProcessInfo, frontmost_window: WindowInfo, windows: Vec<WindowInfo>) -> Self {
+    Self {
+      info,
+      frontmost_window,
+      windows,
+    }
+  }
+}
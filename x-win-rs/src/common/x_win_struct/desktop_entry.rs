@@ -0,0 +1,25 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the metadata of the freedesktop `.desktop` entry of an application, only resolved on Linux
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DesktopEntry {
+  /**
+   * Localized `Name` of the entry (e.g. `Visual Studio Code`), empty when no entry matches
+   */
+  pub display_name: String,
+  /**
+   * `Icon` of the entry, a theme icon name (e.g. `org.gnome.Nautilus`) or an absolute path, empty when no entry matches
+   */
+  pub icon_name: String,
+}
+
+impl DesktopEntry {
+  pub fn new(display_name: String, icon_name: String) -> Self {
+    Self {
+      display_name,
+      icon_name,
+    }
+  }
+}
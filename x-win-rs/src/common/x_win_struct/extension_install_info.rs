@@ -0,0 +1,47 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the result of the Gnome extension installation
+ */
+#[derive(Debug, Clone)]
+pub struct ExtensionInstallInfo {
+  /**
+   * `true` when extension files are present in `path` after the call (already present with `dry_run`)
+   */
+  pub installed: bool,
+  /**
+   * Folder where extension files are written
+   */
+  pub path: String,
+  /**
+   * `true` when extension files have been written or updated (would be with `dry_run`), the user session has to be
+   * restarted to load them
+   */
+  pub needs_reload: bool,
+  /**
+   * `true` when the session is a Gnome Wayland desktop where the extension is needed to recover windows
+   */
+  pub applicable: bool,
+  /**
+   * Paths of the extension files written (or which would be written) by the installation
+   */
+  pub files: Vec<String>,
+}
+
+impl ExtensionInstallInfo {
+  pub fn new(
+    installed: bool,
+    path: String,
+    needs_reload: bool,
+    applicable: bool,
+    files: Vec<String>,
+  ) -> Self {
+    Self {
+      installed,
+      path,
+      needs_reload,
+      applicable,
+      files,
+    }
+  }
+}
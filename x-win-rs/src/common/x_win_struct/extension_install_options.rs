@@ -0,0 +1,12 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to install the Gnome extension
+ */
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionInstallOptions {
+  /**
+   * Only report the files which would be written, without touching the filesystem
+   */
+  pub dry_run: bool,
+}
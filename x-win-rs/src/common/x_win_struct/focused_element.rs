@@ -0,0 +1,28 @@
+#![deny(unused_imports)]
+
+use super::window_position::WindowPosition;
+
+/**
+ * Struct to store the focused UI element of the active window recovered with the accessibility API
+ */
+#[derive(Debug, Clone)]
+pub struct FocusedElement {
+  /**
+   * Role of the element, the AT-SPI role name on Linux and Windows (e.g. `push button`) and the AXRole on macOS (e.g. `AXButton`)
+   */
+  pub role: String,
+  /**
+   * Accessible name of the element, empty when not set
+   */
+  pub name: String,
+  /**
+   * Position and size of the element on screen
+   */
+  pub bounds: WindowPosition,
+}
+
+impl FocusedElement {
+  pub fn new(role: String, name: String, bounds: WindowPosition) -> Self {
+    Self { role, name, bounds }
+  }
+}
@@ -1,7 +1,26 @@
 #![deny(unused_imports)]
 
+pub mod active_window_change;
+pub mod active_window_options;
+pub mod app_info;
+pub mod desktop_entry;
+pub mod extension_install_info;
+pub mod extension_install_options;
+pub mod focused_element;
 pub mod icon_info;
+pub mod monitor_info;
+pub mod open_windows_options;
+pub mod permission_status;
 pub mod process_info;
+pub mod raw_image;
+pub mod title_entry;
+pub mod title_match_options;
 pub mod usage_info;
+pub mod watched_fields;
+pub mod window_event;
 pub mod window_info;
 pub mod window_position;
+pub mod window_property;
+pub mod window_snapshot;
+pub mod workspace;
+pub mod workspace_info;
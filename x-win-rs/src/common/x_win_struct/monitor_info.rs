@@ -0,0 +1,19 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store position, size and name of a monitor
+ */
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+  pub id: u32,
+  pub name: String,
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+  pub is_primary: bool,
+  /**
+   * Ratio between physical and logical pixels of the monitor (e.g. `2.0` on Retina displays), `1.0` when unknown
+   */
+  pub scale_factor: f64,
+}
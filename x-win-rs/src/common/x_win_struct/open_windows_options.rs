@@ -0,0 +1,92 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to recover open windows
+ */
+#[derive(Debug, Clone)]
+pub struct OpenWindowsOptions {
+  /**
+   * Recover the url of the active tab when the window belongs to a supported browser
+   */
+  pub include_url: bool,
+  /**
+   * Keep windows without `_NET_WM_WINDOW_TYPE` on Linux (only typed windows are kept by default)
+   */
+  pub include_untyped: bool,
+  /**
+   * Exclude desktop shell windows (taskbar, dock, desktop, menu bar...) on Windows and macOS,
+   * they are always excluded on Linux
+   */
+  pub exclude_desktop_shell: bool,
+  /**
+   * Return positions in logical pixels, physical geometry is divided by the scale factor of the window monitor
+   * (positions are already logical on macOS). Native coordinates are returned by default
+   */
+  pub logical_pixels: bool,
+  /**
+   * Accepted `_NET_WM_WINDOW_TYPE` on Linux (e.g. `dialog` for `_NET_WM_WINDOW_TYPE_DIALOG`),
+   * normal, dialog, utility and toolbar windows are kept when not set
+   */
+  pub window_types: Option<Vec<String>>,
+  /**
+   * Recover the memory usage of windows, `usage.memory` is left at `0` and its reading is skipped when `false`
+   */
+  pub include_usage: bool,
+  /**
+   * Only keep windows of the current workspace (virtual desktop) on Linux X11 and Windows,
+   * sticky windows shown on all workspaces are kept. macOS only lists windows of the active Space
+   */
+  pub only_current_workspace: bool,
+  /**
+   * Keep transient windows (dialogs, tool palettes...) declaring a parent with `WM_TRANSIENT_FOR` on X11
+   * or owned by another window on Windows, they are excluded by default. Their parent is set in `parent_id`
+   */
+  pub include_transient: bool,
+  /**
+   * Keep override-redirect windows (menus, tooltips, drag icons...) on X11 even when they have no
+   * `_NET_WM_WINDOW_TYPE`, and tool windows or popups without caption on Windows. They are excluded by default,
+   * except the foreground window on Windows
+   */
+  pub include_override_redirect: bool,
+  /**
+   * Also list client windows missing from `_NET_CLIENT_LIST_STACKING` on X11: windows of `_NET_CLIENT_LIST`
+   * (minimized windows are omitted from the stacking list by some minimal WMs) and, when the WM maintains
+   * neither list, client windows found with `QueryTree`. Minimized windows have `is_minimized` set
+   */
+  pub include_hidden: bool,
+  /**
+   * Keep the enumeration order of the platform (back to front stacking list on X11) instead of sorting windows
+   * from front to back with `z_order`
+   */
+  pub raw_order: bool,
+  /**
+   * Maximum time in milliseconds to wait for the X server on Linux, `XWinError::Timeout` is returned when it is exceeded.
+   * Replies are awaited without limit when not set
+   */
+  pub timeout_ms: Option<u32>,
+  /**
+   * X display to connect to on Linux (e.g. `:1`), windows are then always recovered through X11.
+   * `$DISPLAY` is used when not set
+   */
+  pub display: Option<String>,
+}
+
+impl Default for OpenWindowsOptions {
+  fn default() -> Self {
+    Self {
+      include_url: false,
+      include_untyped: false,
+      exclude_desktop_shell: false,
+      logical_pixels: false,
+      window_types: None,
+      include_usage: true,
+      only_current_workspace: false,
+      include_transient: false,
+      include_override_redirect: false,
+      include_hidden: false,
+      raw_order: false,
+      timeout_ms: None,
+      display: None,
+    }
+  }
+}
@@ -0,0 +1,26 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the states of the macOS permissions required to recover windows information,
+ * permissions are always granted on other platforms
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionStatus {
+  /**
+   * Screen Recording permission, required for titles of other applications and captures
+   */
+  pub screen_recording: bool,
+  /**
+   * Accessibility permission, required for content language and accessibility identifiers
+   */
+  pub accessibility: bool,
+}
+
+impl PermissionStatus {
+  pub fn new(screen_recording: bool, accessibility: bool) -> Self {
+    Self {
+      screen_recording,
+      accessibility,
+    }
+  }
+}
@@ -0,0 +1,29 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the uncompressed pixels of a captured window, 8 bits RGBA rows from top to bottom
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawImage {
+  pub width: u32,
+  pub height: u32,
+  /**
+   * Number of bytes of a row in `data`
+   */
+  pub stride: u32,
+  pub data: Vec<u8>,
+}
+
+impl RawImage {
+  /**
+   * Image of tightly packed rows, `stride` is `width * 4`
+   */
+  pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+    Self {
+      width,
+      height,
+      stride: width * 4,
+      data,
+    }
+  }
+}
@@ -0,0 +1,17 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store a title taken by a window, `timestamp` is the time in milliseconds since the Unix epoch
+ * when the title has been seen first
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleEntry {
+  pub timestamp: u64,
+  pub title: String,
+}
+
+impl TitleEntry {
+  pub fn new(timestamp: u64, title: String) -> Self {
+    Self { timestamp, title }
+  }
+}
@@ -0,0 +1,73 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store options used to find windows by title
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TitleMatchOptions {
+  /**
+   * Compare titles ignoring case (Unicode lowercase)
+   */
+  pub case_insensitive: bool,
+  /**
+   * Only match titles equal to the query instead of titles containing it
+   */
+  pub exact: bool,
+}
+
+impl TitleMatchOptions {
+  /**
+   * To know if the title matches the query
+   */
+  pub fn matches(&self, title: &str, query: &str) -> bool {
+    if self.case_insensitive {
+      let (title, query) = (title.to_lowercase(), query.to_lowercase());
+      if self.exact {
+        title.eq(&query)
+      } else {
+        title.contains(&query)
+      }
+    } else if self.exact {
+      title.eq(query)
+    } else {
+      title.contains(query)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn options(case_insensitive: bool, exact: bool) -> TitleMatchOptions {
+    TitleMatchOptions {
+      case_insensitive,
+      exact,
+    }
+  }
+
+  #[test]
+  fn test_substring() {
+    let options = options(false, false);
+    assert!(options.matches("main.rs - Visual Studio Code", "Visual Studio"));
+    assert!(!options.matches("main.rs - Visual Studio Code", "visual studio"));
+    assert!(options.matches("Café ✓ テスト", "✓ テ"));
+    assert!(options.matches("any title", ""));
+  }
+
+  #[test]
+  fn test_case_insensitive() {
+    let options = options(true, false);
+    assert!(options.matches("main.rs - Visual Studio Code", "visual STUDIO"));
+    assert!(options.matches("ÉCOLE", "école"));
+    assert!(!options.matches("Firefox", "chrome"));
+  }
+
+  #[test]
+  fn test_exact() {
+    assert!(options(false, true).matches("Firefox", "Firefox"));
+    assert!(!options(false, true).matches("Firefox", "Fire"));
+    assert!(!options(false, true).matches("Firefox", "firefox"));
+    assert!(options(true, true).matches("Firefox", "firefox"));
+  }
+}
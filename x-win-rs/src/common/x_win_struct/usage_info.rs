@@ -6,10 +6,15 @@
 #[derive(Debug, Clone)]
 pub struct UsageInfo {
   pub memory: u32,
+  /**
+   * CPU usage of the process in percent of one core since the previous sample of a `CpuSampler`,
+   * `0` for one-shot calls which can't compute a rate from a single sample
+   */
+  pub cpu: f64,
 }
 
 impl UsageInfo {
   pub fn new(memory: u32) -> Self {
-    Self { memory }
+    Self { memory, cpu: 0.0 }
   }
 }
@@ -0,0 +1,82 @@
+#![deny(unused_imports)]
+
+use crate::XWinError;
+
+/**
+ * Fields of the active window compared to detect a change, the default watches the id, the process and the title
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchedFields {
+  /**
+   * Another window is active
+   */
+  pub id: bool,
+  /**
+   * The process owning the window changed
+   */
+  pub process: bool,
+  /**
+   * The title of the window changed
+   */
+  pub title: bool,
+  /**
+   * The window moved or has been resized
+   */
+  pub position: bool,
+}
+
+impl Default for WatchedFields {
+  fn default() -> Self {
+    Self {
+      id: true,
+      process: true,
+      title: true,
+      position: false,
+    }
+  }
+}
+
+impl WatchedFields {
+  /**
+   * Build the watched fields from their names (`id`, `process`, `title` or `position`), an unknown name returns an error
+   */
+  pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self, XWinError> {
+    let mut fields = Self {
+      id: false,
+      process: false,
+      title: false,
+      position: false,
+    };
+    for name in names {
+      match name.as_ref() {
+        "id" => fields.id = true,
+        "process" => fields.process = true,
+        "title" => fields.title = true,
+        "position" => fields.position = true,
+        name => {
+          return Err(XWinError::PlatformError(format!(
+            "unknown watched field: {}",
+            name
+          )))
+        }
+      }
+    }
+    Ok(fields)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_names() {
+    assert_eq!(
+      WatchedFields::from_names(&["id", "title", "process"]).unwrap(),
+      WatchedFields::default()
+    );
+    let fields = WatchedFields::from_names(&["position"]).unwrap();
+    assert!(fields.position && !fields.id && !fields.process && !fields.title);
+    assert!(WatchedFields::from_names(&["size"]).is_err());
+  }
+}
@@ -0,0 +1,12 @@
+#![deny(unused_imports)]
+
+use super::window_info::WindowInfo;
+
+/**
+ * Event emitted when a window appears or disappears between two snapshots of open windows
+ */
+#[derive(Debug, Clone)]
+pub enum WindowEvent {
+  Opened(WindowInfo),
+  Closed(WindowInfo),
+}
@@ -1,6 +1,15 @@
 #![deny(unused_imports)]
 
-use super::{process_info::ProcessInfo, usage_info::UsageInfo, window_position::WindowPosition};
+use super::{
+  focused_element::FocusedElement, monitor_info::MonitorInfo, process_info::ProcessInfo,
+  usage_info::UsageInfo, window_position::WindowPosition, workspace_info::WorkspaceInfo,
+};
+
+/**
+ * FNV-1a parameters, the hash has to stay the same across versions and runs to be persisted
+ */
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
 
 /**
  * Struct to store all informations of the window
@@ -14,6 +23,85 @@ pub struct WindowInfo {
   pub info: ProcessInfo,
   pub usage: UsageInfo,
   pub url: String,
+  /**
+   * `Some(true)` when the window is displayed on an external monitor, `Some(false)` on the built-in panel and `None` when unknown
+   */
+  pub on_external_display: Option<bool>,
+  /**
+   * Stacking index of the window, higher means closer to the front (`0` when unknown)
+   */
+  pub z_order: u32,
+  /**
+   * Workspace (virtual desktop) of the window, `None` when unknown
+   */
+  pub workspace: Option<WorkspaceInfo>,
+  /**
+   * BCP 47 language tag of the focused document (e.g. `en-US`), only recovered for the active window when requested
+   */
+  pub content_language: Option<String>,
+  /**
+   * Monitor containing the center of the window, `None` when unknown
+   */
+  pub monitor: Option<MonitorInfo>,
+  /**
+   * `true` for the window owning the input focus (`GetInputFocus` on X11), the active window on other platforms.
+   * It can differ from `is_active` on X11 (e.g. focus-follows-mouse), at most one window of `get_open_windows` has it set
+   */
+  pub is_focused: bool,
+  /**
+   * `true` for the active window (`_NET_ACTIVE_WINDOW` on X11, foreground window on Windows, key window of the
+   * frontmost application on macOS), at most one window of `get_open_windows` has it set
+   */
+  pub is_active: bool,
+  /**
+   * Opacity of the window from `0.0` (transparent) to `1.0` (opaque), `1.0` when unknown
+   */
+  pub opacity: f64,
+  /**
+   * Id of the window this one is transient for (`WM_TRANSIENT_FOR` on X11, owner window on Windows),
+   * `None` for top-level windows or when unknown
+   */
+  pub parent_id: Option<u32>,
+  /**
+   * `true` when the window is minimized (`_NET_WM_STATE_HIDDEN` or iconic `WM_STATE` on X11),
+   * minimized windows are not listed on macOS
+   */
+  pub is_minimized: bool,
+  /**
+   * `true` when the window is mapped (`Viewable` map state on X11, `IsWindowVisible` on Windows, on screen on macOS),
+   * mapped windows can still be off-screen or covered. Always `true` on Wayland where the state is not exposed
+   */
+  pub is_visible: bool,
+  /**
+   * Role of the window from `WM_WINDOW_ROLE` on X11 (e.g. `browser` or `pop-up`) to match windows across sessions,
+   * empty when not set or on other platforms
+   */
+  pub role: String,
+  /**
+   * Focused UI element of the window, only recovered with `include_focused_element` of `ActiveWindowOptions`,
+   * `None` when accessibility is unavailable
+   */
+  pub focused_element: Option<FocusedElement>,
+  /**
+   * Visible bounds of the window with its decorations (title bar and borders): `position` extended with
+   * `_NET_FRAME_EXTENTS` on X11, `DWMWA_EXTENDED_FRAME_BOUNDS` on Windows where `position` includes the invisible
+   * resize borders, same as `position` on macOS. `None` when unknown (e.g. Wayland or a window manager without extents)
+   */
+  pub frame: Option<WindowPosition>,
+  /**
+   * Window pinned above the other windows: `_NET_WM_STATE_ABOVE` on X11, `WS_EX_TOPMOST` on Windows
+   * and a window level above the normal one on macOS
+   */
+  pub always_on_top: bool,
+  /**
+   * Window kept below the other windows with `_NET_WM_STATE_BELOW` on X11, always `false` on other platforms
+   */
+  pub always_on_bottom: bool,
+  /**
+   * Raw platform handle of the window for interop with other native libraries: the XID on X11, the `HWND` value
+   * on Windows and the `CGWindowID` on macOS. `0` when not available (e.g. Wayland)
+   */
+  pub native_handle: u64,
 }
 
 impl WindowInfo {
@@ -34,6 +122,81 @@ impl WindowInfo {
       info,
       usage,
       url,
+      on_external_display: None,
+      z_order: 0,
+      workspace: None,
+      content_language: None,
+      monitor: None,
+      is_focused: false,
+      is_active: false,
+      opacity: 1.0,
+      parent_id: None,
+      is_minimized: false,
+      is_visible: false,
+      role: "".to_owned(),
+      focused_element: None,
+      frame: None,
+      always_on_top: false,
+      always_on_bottom: false,
+      native_handle: 0,
+    }
+  }
+
+  /**
+   * Best-effort identity of the window preserved across title changes, hashed from the window class
+   * (`WM_CLASS` on X11, application name elsewhere), the role and the process id with its start time.
+   * Unlike `id` it is not reused by a window of another process, but windows of a process sharing
+   * the same class and role share it. Empty for an empty entity.
+   */
+  pub fn stable_id(&self) -> String {
+    if self.id == 0 {
+      return "".to_owned();
+    }
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in [
+      self.info.name.as_bytes(),
+      self.role.as_bytes(),
+      &self.info.process_id.to_le_bytes(),
+      &self.info.start_time.to_le_bytes(),
+    ] {
+      // Parts are separated to avoid collisions between ("ab", "c") and ("a", "bc")
+      for byte in part.iter().chain(&[0xff]) {
+        hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+      }
     }
+    format!("{:016x}", hash)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::common::api::empty_entity;
+
+  #[test]
+  fn test_stable_id() {
+    let mut window_info = empty_entity();
+    assert_eq!(window_info.stable_id(), "");
+
+    window_info.id = 42;
+    window_info.title = "a".to_owned();
+    window_info.info.name = "Firefox".to_owned();
+    window_info.role = "browser".to_owned();
+    window_info.info.process_id = 1234;
+    window_info.info.start_time = 1700000000000;
+    let stable_id = window_info.stable_id();
+    assert_eq!(stable_id.len(), 16);
+    assert_eq!(stable_id, "55c80d92c79a58f2");
+
+    // Title and recycled window ids are ignored
+    window_info.title = "b".to_owned();
+    window_info.id = 43;
+    assert_eq!(window_info.stable_id(), stable_id);
+
+    window_info.info.start_time += 1;
+    assert_ne!(window_info.stable_id(), stable_id);
+    window_info.info.start_time -= 1;
+    window_info.role = "".to_owned();
+    window_info.info.name = "Firefoxbrowser".to_owned();
+    assert_ne!(window_info.stable_id(), stable_id);
   }
 }
@@ -1,9 +1,11 @@
 #![deny(unused_imports)]
 
 /**
- * Struct to store position and size of the window
+ * Struct to store position and size of the window.
+ * Values are physical pixels on Windows (for DPI aware processes like Node.js) and X11, logical points on macOS
+ * and stage coordinates of the compositor on Wayland, use `scale_factor` of the monitor to convert them.
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowPosition {
   pub x: i32,
   pub y: i32,
@@ -0,0 +1,30 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the raw value of a window property, only read on X11
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowProperty {
+  /**
+   * Name of the type atom of the value (e.g. `UTF8_STRING`, `CARDINAL` or `ATOM`)
+   */
+  pub type_name: String,
+  /**
+   * Size of the items of the value in bits, `8`, `16` or `32`
+   */
+  pub format: u8,
+  /**
+   * Bytes of the value, items of 16 and 32 bits are in the byte order of the host
+   */
+  pub data: Vec<u8>,
+}
+
+impl WindowProperty {
+  pub fn new(type_name: String, format: u8, data: Vec<u8>) -> Self {
+    Self {
+      type_name,
+      format,
+      data,
+    }
+  }
+}
@@ -0,0 +1,24 @@
+#![deny(unused_imports)]
+
+use super::window_info::WindowInfo;
+
+/**
+ * Struct to store the active window and the open windows recovered together
+ */
+#[derive(Debug, Clone)]
+pub struct WindowSnapshot {
+  /**
+   * Active window, an empty entity (`id` equal to `0`) means that no window is focused
+   */
+  pub active: WindowInfo,
+  /**
+   * Open windows, the active one has `is_active` set
+   */
+  pub windows: Vec<WindowInfo>,
+}
+
+impl WindowSnapshot {
+  pub fn new(active: WindowInfo, windows: Vec<WindowInfo>) -> Self {
+    Self { active, windows }
+  }
+}
@@ -0,0 +1,27 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store a workspace (virtual desktop) of the session, `id` matches `WorkspaceInfo.id` of its windows
+ */
+#[derive(Debug, Clone)]
+pub struct Workspace {
+  pub id: i32,
+  /**
+   * Label of the workspace, `None` when it has not been named
+   */
+  pub name: Option<String>,
+  /**
+   * `true` for the workspace shown on screen
+   */
+  pub is_current: bool,
+}
+
+impl Workspace {
+  pub fn new(id: i32, name: Option<String>, is_current: bool) -> Self {
+    Self {
+      id,
+      name,
+      is_current,
+    }
+  }
+}
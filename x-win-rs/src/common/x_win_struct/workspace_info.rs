@@ -0,0 +1,17 @@
+#![deny(unused_imports)]
+
+/**
+ * Struct to store the workspace (virtual desktop) of the window.
+ * `id` is `-1` and `name` is `None` for sticky windows shown on all workspaces.
+ */
+#[derive(Debug, Clone)]
+pub struct WorkspaceInfo {
+  pub id: i32,
+  pub name: Option<String>,
+}
+
+impl WorkspaceInfo {
+  pub fn new(id: i32, name: Option<String>) -> Self {
+    Self { id, name }
+  }
+}
@@ -25,29 +25,124 @@ mod macos;
 use win32::init_platform_api;
 
 #[cfg(target_os = "linux")]
-use linux::init_platform_api;
+use linux::{init_platform_api, init_platform_api_with_display};
 
 #[cfg(target_os = "macos")]
 use macos::init_platform_api;
 
 pub use common::{
+  active_window_cache::DEFAULT_ACTIVE_WINDOW_CACHE_TTL,
+  active_window_debouncer::ActiveWindowDebouncer,
   api::{empty_entity, os_name},
+  change_notifier::ChangeNotifier,
+  cpu_sampler::CpuSampler,
+  title_history::TitleHistory,
+  window_filter::WindowFilter,
+  window_tracker::WindowTracker,
   x_win_struct::{
-    icon_info::IconInfo, process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
-    window_position::WindowPosition,
+    active_window_change::ActiveWindowChange, active_window_options::ActiveWindowOptions,
+    app_info::AppInfo, desktop_entry::DesktopEntry, extension_install_info::ExtensionInstallInfo,
+    extension_install_options::ExtensionInstallOptions, focused_element::FocusedElement,
+    icon_info::IconInfo, monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions,
+    permission_status::PermissionStatus, process_info::ProcessInfo, raw_image::RawImage,
+    title_entry::TitleEntry, title_match_options::TitleMatchOptions, usage_info::UsageInfo,
+    watched_fields::WatchedFields, window_event::WindowEvent, window_info::WindowInfo,
+    window_position::WindowPosition, window_property::WindowProperty,
+    window_snapshot::WindowSnapshot, workspace::Workspace, workspace_info::WorkspaceInfo,
   },
 };
 
-use crate::common::api::Api;
+use crate::common::{
+  active_window_cache::ActiveWindowCache,
+  active_window_debouncer::get_change,
+  api::{get_frontmost_window_on_monitor, group_windows_by_process, to_logical_position, Api},
+  capture::encode_png,
+  focus_history::{FocusHistory, FOCUS_HISTORY_LENGTH},
+};
+
+use std::{
+  collections::HashMap,
+  fmt,
+  sync::{Mutex, MutexGuard},
+  time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+/**
+ * Active window memoized by `get_active_window_cached`
+ */
+static ACTIVE_WINDOW_CACHE: Lazy<Mutex<ActiveWindowCache>> =
+  Lazy::new(|| Mutex::new(ActiveWindowCache::new(DEFAULT_ACTIVE_WINDOW_CACHE_TTL)));
+
+/**
+ * Lock a state which is always left consistent, a panic while it is locked can't corrupt it
+ * so the lock is taken even when it is poisoned
+ */
+pub fn lock_unpoisoned<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+  mutex
+    .lock()
+    .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
 
-use std::fmt;
+fn active_window_cache() -> MutexGuard<'static, ActiveWindowCache> {
+  lock_unpoisoned(&ACTIVE_WINDOW_CACHE)
+}
 
-#[derive(Debug)]
-pub struct XWinError;
+/**
+ * Windows focused most recently, recorded by each recovery of the active window of the default display
+ */
+static FOCUS_HISTORY: Lazy<Mutex<FocusHistory>> =
+  Lazy::new(|| Mutex::new(FocusHistory::new(FOCUS_HISTORY_LENGTH)));
+
+fn focus_history() -> MutexGuard<'static, FocusHistory> {
+  lock_unpoisoned(&FOCUS_HISTORY)
+}
+
+/**
+ * Only X11 can target another display, the default API is used on other platforms
+ */
+#[cfg(not(target_os = "linux"))]
+fn init_platform_api_with_display(_display: Option<String>) -> impl Api {
+  init_platform_api()
+}
+
+/**
+ * Errors returned when windows information cannot be recovered
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XWinError {
+  /**
+   * Connection to the display server (or the desktop session) failed
+   */
+  NoDisplay,
+  /**
+   * The display server is reachable but has no screen (e.g. headless or misconfigured X server)
+   */
+  NoScreen,
+  /**
+   * A permission required by the call has not been granted (e.g. accessibility on macOS)
+   */
+  PermissionDenied,
+  /**
+   * Platform API failed, with the reason
+   */
+  PlatformError(String),
+  /**
+   * The display server did not answer before the requested timeout
+   */
+  Timeout,
+}
 
 impl fmt::Display for XWinError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "Oops something got wrong with x-win")
+    match self {
+      XWinError::NoDisplay => write!(f, "Unable to connect to the display server"),
+      XWinError::NoScreen => write!(f, "No screen available on the display server"),
+      XWinError::PermissionDenied => write!(f, "Permission denied to recover windows information"),
+      XWinError::PlatformError(reason) => write!(f, "Platform error: {}", reason),
+      XWinError::Timeout => write!(f, "Timed out waiting for the display server"),
+    }
   }
 }
 
@@ -64,11 +159,175 @@ pub fn get_window_icon(window_info: &WindowInfo) -> Result<IconInfo, XWinError>
 
 /**
  * Retrieve information the about currently active window.
- * Return `WindowInfo` containing details about a specific active window.
+ * Return `WindowInfo` containing details about a specific active window,
+ * an empty entity (`id` equal to `0`) means that no window is focused.
  */
 pub fn get_active_window() -> Result<WindowInfo, XWinError> {
+  get_active_window_with_options(&ActiveWindowOptions::default())
+}
+
+/**
+ * Retrieve information the about currently active window using options.
+ * Return `WindowInfo` containing details about a specific active window.
+ */
+pub fn get_active_window_with_options(
+  options: &ActiveWindowOptions,
+) -> Result<WindowInfo, XWinError> {
+  let api = init_platform_api_with_display(options.display.clone());
+  let mut window_info = api.get_active_window(options)?;
+  if options.logical_pixels {
+    to_logical_position(&mut window_info);
+  }
+  // Ids of windows of other displays can't be compared with the ones of the default display
+  if options.display.is_none() {
+    focus_history().record(&window_info);
+  }
+  Ok(window_info)
+}
+
+/**
+ * Retrieve the window focused before the current active window, e.g. to switch back to the last window.
+ * Focus changes are only known from the active windows recovered by this process (every call of
+ * `get_active_window` and its variants, e.g. from a polling loop), the most recent window distinct from the
+ * current one is returned, `None` when no other window has been recovered.
+ * The window information is the one recovered when it was last active, the window can have been closed since.
+ */
+pub fn get_previous_active_window() -> Result<Option<WindowInfo>, XWinError> {
+  let current_window = get_active_window()?;
+  Ok(focus_history().previous(current_window.id))
+}
+
+/**
+ * Retrieve the active window like `get_active_window_with_options` but return the window memoized by a previous call
+ * with the same options when it is younger than the cache time to live (`DEFAULT_ACTIVE_WINDOW_CACHE_TTL` by default).
+ * Use `force_refresh` to bypass the cache, the recovered window is cached again.
+ */
+pub fn get_active_window_cached(
+  options: &ActiveWindowOptions,
+  force_refresh: bool,
+) -> Result<WindowInfo, XWinError> {
+  let generation = {
+    let cache = active_window_cache();
+    if !force_refresh {
+      if let Some(window_info) = cache.get(options) {
+        return Ok(window_info);
+      }
+    }
+    cache.generation()
+  };
+  // The cache is not locked during the recovery, concurrent misses recover the window each
+  let recovered_at = Instant::now();
+  let window_info = get_active_window_with_options(options)?;
+  active_window_cache().insert(generation, recovered_at, options, &window_info);
+  Ok(window_info)
+}
+
+/**
+ * Change the time to live of the cache of `get_active_window_cached`, `Duration::ZERO` disables it
+ */
+pub fn set_active_window_cache_ttl(ttl: Duration) {
+  active_window_cache().set_ttl(ttl);
+}
+
+/**
+ * Forget the window cached by `get_active_window_cached`, e.g. when a newer active window has been observed
+ */
+pub fn invalidate_active_window_cache() {
+  active_window_cache().invalidate();
+}
+
+/**
+ * Retrieve only the id of the active window, `0` when no window is focused.
+ * Lighter than `get_active_window` to detect focus changes in a tight polling loop
+ * (only `_NET_ACTIVE_WINDOW` is read on X11, the foreground window on Windows and the front window of the
+ * frontmost application on macOS). On Wayland the whole active window is recovered.
+ */
+pub fn get_active_window_id() -> Result<u32, XWinError> {
+  let api = init_platform_api();
+  api.get_active_window_id()
+}
+
+/**
+ * Check if the active window is full screen, e.g. to pause notifications during a presentation.
+ * Reads `_NET_WM_STATE_FULLSCREEN` of the active window on X11, checks that the foreground window covers
+ * its whole monitor without caption on Windows and compares the frontmost window with the screen on macOS.
+ * Return `false` when no window is focused.
+ */
+pub fn is_fullscreen_active() -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.is_fullscreen_active()
+}
+
+/**
+ * Retrieve the name of the running window manager (e.g. `Mutter`, `KWin`, `i3`) to tell environments apart.
+ * Read from `_NET_WM_NAME` of the `_NET_SUPPORTING_WM_CHECK` window on Linux, `""` when it can't be recovered.
+ */
+pub fn get_window_manager_name() -> String {
+  #[cfg(target_os = "linux")]
+  {
+    linux::window_manager_name()
+  }
+  #[cfg(target_os = "macos")]
+  {
+    macos::window_manager_name()
+  }
+  #[cfg(target_os = "windows")]
+  {
+    win32::window_manager_name()
+  }
+}
+
+/**
+ * Retrieve the active window and compare it with `previous` the same way as `ActiveWindowDebouncer` without debounce.
+ * Return `Some` with the new active window when the focus, the process or the title changed, `None` otherwise.
+ * Allows to detect changes from an existing scheduler without a dedicated thread.
+ */
+pub fn active_window_changed(previous: &WindowInfo) -> Result<Option<WindowInfo>, XWinError> {
+  let window_info = get_active_window()?;
+  Ok(get_change(previous, &window_info, &WatchedFields::default()).map(|_| window_info))
+}
+
+/**
+ * Retrieve the frontmost window intersecting the monitor at `monitor_index` (index in the monitors list).
+ * Return `None` when no window overlaps this monitor or when the monitor does not exist.
+ */
+pub fn get_active_window_for_display(monitor_index: u32) -> Result<Option<WindowInfo>, XWinError> {
   let api = init_platform_api();
-  Ok(api.get_active_window())
+  let monitors = api.get_monitors()?;
+  let Some(monitor) = monitors.get(monitor_index as usize) else {
+    return Ok(None);
+  };
+  let windows = api.get_open_windows(&OpenWindowsOptions::default())?;
+  Ok(get_frontmost_window_on_monitor(windows, monitor))
+}
+
+/**
+ * Retrieve the workspaces (virtual desktops) of the session in their display order with the current one marked,
+ * from `_NET_NUMBER_OF_DESKTOPS`, `_NET_DESKTOP_NAMES` and `_NET_CURRENT_DESKTOP` on X11 and the virtual desktops
+ * of explorer on Windows. The list is empty on macOS where Spaces are not exposed by public APIs and on Wayland.
+ */
+pub fn get_workspaces() -> Result<Vec<Workspace>, XWinError> {
+  let api = init_platform_api();
+  api.get_workspaces()
+}
+
+/**
+ * Retrieve the connected monitors with their position, size and scale factor, from RandR on X11,
+ * `EnumDisplayMonitors` on Windows and the CoreGraphics displays (with their `NSScreen`) on macOS.
+ * The list is empty on Wayland.
+ */
+pub fn get_monitors() -> Result<Vec<MonitorInfo>, XWinError> {
+  let api = init_platform_api();
+  api.get_monitors()
+}
+
+/**
+ * Retrieve the bounds of the virtual desktop, the combined extent of all monitors.
+ * The origin is negative when a monitor is placed left of or above the primary monitor.
+ */
+pub fn get_desktop_bounds() -> Result<WindowPosition, XWinError> {
+  let api = init_platform_api();
+  api.get_desktop_bounds()
 }
 
 /**
@@ -76,23 +335,366 @@ pub fn get_active_window() -> Result<WindowInfo, XWinError> {
  * Return `Vec<WindowInfo>` each containing details about a specific open window.
  */
 pub fn get_open_windows() -> Result<Vec<WindowInfo>, XWinError> {
+  get_open_windows_with_options(&OpenWindowsOptions::default())
+}
+
+/**
+ * Retrieve information about the currently open windows using options.
+ * Return `Vec<WindowInfo>` each containing details about a specific open window.
+ */
+pub fn get_open_windows_with_options(
+  options: &OpenWindowsOptions,
+) -> Result<Vec<WindowInfo>, XWinError> {
+  let api = init_platform_api_with_display(options.display.clone());
+  let mut windows = api.get_open_windows(options)?;
+  if options.logical_pixels {
+    windows.iter_mut().for_each(to_logical_position);
+  }
+  Ok(windows)
+}
+
+/**
+ * Retrieve information about the currently open windows accepted by `filter`, without gathering a full list first.
+ * The filter is called during the enumeration and the details needing another read are only recovered for accepted
+ * windows, the filter sees them empty: the process details (`info.path`, `info.exec_name`, `info.command_line`,
+ * `info.user`, `info.start_time`) and `usage.memory` on X11, `info.command_line`, `info.user` and `info.start_time`
+ * on macOS, `usage.memory` on Windows. `url` is set after filtering on all platforms.
+ */
+pub fn get_open_windows_filtered<F: FnMut(&WindowInfo) -> bool>(
+  options: &OpenWindowsOptions,
+  mut filter: F,
+) -> Result<Vec<WindowInfo>, XWinError> {
+  let api = init_platform_api_with_display(options.display.clone());
+  let mut windows = if options.logical_pixels {
+    // The filter sees the positions that are returned
+    api.get_open_windows_filtered(options, &mut |window_info| {
+      let mut window_info = window_info.clone();
+      to_logical_position(&mut window_info);
+      filter(&window_info)
+    })?
+  } else {
+    api.get_open_windows_filtered(options, &mut filter)?
+  };
+  if options.logical_pixels {
+    windows.iter_mut().for_each(to_logical_position);
+  }
+  Ok(windows)
+}
+
+/**
+ * Retrieve information about the currently open windows using options and call `on_window` with each window
+ * as soon as it is gathered, to render a long list incrementally. Windows are passed in enumeration order
+ * during the recovery on X11 and Windows, once they are all recovered on macOS and Wayland.
+ * Return the whole `Vec<WindowInfo>` once it is complete, sorted like `get_open_windows_with_options`.
+ */
+pub fn get_open_windows_with_progress<F: FnMut(&WindowInfo)>(
+  options: &OpenWindowsOptions,
+  mut on_window: F,
+) -> Result<Vec<WindowInfo>, XWinError> {
+  let api = init_platform_api_with_display(options.display.clone());
+  api.get_open_windows_with_progress(options, &mut |window_info| {
+    if options.logical_pixels {
+      to_logical_position(window_info);
+    }
+    on_window(window_info);
+  })
+}
+
+/**
+ * Retrieve the active window and the open windows together, the windows are recovered once
+ * (a single X11 connection on Linux) and the active one is taken from the list where it has `is_active` set.
+ * The active window is only recovered separately when it is not part of the list (e.g. a transient window).
+ */
+pub fn get_snapshot() -> Result<WindowSnapshot, XWinError> {
+  let api = init_platform_api();
+  let windows = api.get_open_windows(&OpenWindowsOptions::default())?;
+  let active = match windows.iter().find(|window_info| window_info.is_active) {
+    Some(window_info) => window_info.clone(),
+    None => api.get_active_window(&ActiveWindowOptions::default())?,
+  };
+  Ok(WindowSnapshot::new(active, windows))
+}
+
+/**
+ * Retrieve the open windows grouped by their owning process, applications follow the order of their
+ * frontmost window in `get_open_windows` (one process per application on macOS).
+ */
+pub fn get_open_applications() -> Result<Vec<AppInfo>, XWinError> {
+  let api = init_platform_api();
+  let windows = api.get_open_windows(&OpenWindowsOptions::default())?;
+  Ok(group_windows_by_process(windows))
+}
+
+/**
+ * Retrieve information about the currently open windows owned by a process.
+ * Return `Vec<WindowInfo>` in the same order as `get_open_windows`.
+ */
+pub fn get_windows_by_pid(process_id: u32) -> Result<Vec<WindowInfo>, XWinError> {
+  let api = init_platform_api();
+  api.get_windows_by_pid(process_id)
+}
+
+/**
+ * Retrieve the environment variables of a process (e.g. `VIRTUAL_ENV`, `TERM_PROGRAM`), read from
+ * `/proc/<pid>/environ` on Linux and `KERN_PROCARGS2` on macOS, only the current process is supported on Windows.
+ * Reading the environment requires the process to belong to the same user, an empty map is returned when
+ * it is not accessible.
+ */
+pub fn get_process_env(process_id: u32) -> HashMap<String, String> {
+  #[cfg(target_os = "linux")]
+  {
+    linux::get_process_env(process_id)
+  }
+  #[cfg(target_os = "macos")]
+  {
+    macos::get_process_env(process_id)
+  }
+  #[cfg(target_os = "windows")]
+  {
+    win32::get_process_env(process_id)
+  }
+}
+
+/**
+ * Resolve the freedesktop `.desktop` entry of the application owning the window to get its display name and icon name.
+ * The entry is matched by `StartupWMClass` or desktop file id with the WM_CLASS class (`info.name`), then by the program
+ * of `Exec` with `info.exec_name`, in the `applications` directories of `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`.
+ * Only supported on Linux, an empty entry is returned on other platforms and when no entry matches.
+ */
+pub fn get_desktop_entry(window_info: &WindowInfo) -> DesktopEntry {
+  #[cfg(not(target_os = "linux"))]
+  {
+    let _ = window_info;
+    DesktopEntry::default()
+  }
+  #[cfg(target_os = "linux")]
+  {
+    linux::get_desktop_entry(&window_info.info)
+  }
+}
+
+/**
+ * Retrieve information about the open windows matching an accessibility identifier
+ * (AX identifier on macOS, UIAutomation `AutomationId` on Windows).
+ * Accessibility permission is required on macOS (`XWinError::PermissionDenied` without it), an empty list will be returned on Linux.
+ */
+pub fn find_windows_by_ax_identifier(identifier: &str) -> Result<Vec<WindowInfo>, XWinError> {
+  let api = init_platform_api();
+  api.find_windows_by_ax_identifier(identifier)
+}
+
+/**
+ * Retrieve information about the open windows with a title containing `query` (or equal to it with `options.exact`),
+ * in the same order as `get_open_windows`. Titles are matched in their decoded form (UTF-8 `_NET_WM_NAME` or
+ * Latin-1 `WM_NAME` on X11), `options.case_insensitive` compares their Unicode lowercase.
+ */
+pub fn find_windows_by_title(
+  query: &str,
+  options: &TitleMatchOptions,
+) -> Result<Vec<WindowInfo>, XWinError> {
+  get_open_windows_filtered(&OpenWindowsOptions::default(), |window_info| {
+    options.matches(&window_info.title, query)
+  })
+}
+
+/**
+ * Capture a thumbnail of the window as PNG bytes, downscaled to fit in `max_dimension` when given.
+ * Return an error when the window is minimized, occluded (X11) or cannot be captured (e.g. Wayland).
+ */
+pub fn capture_window(window_id: u32, max_dimension: Option<u32>) -> Result<Vec<u8>, XWinError> {
+  encode_png(&capture_window_rgba(window_id, max_dimension)?)
+}
+
+/**
+ * Capture the window as raw RGBA pixels without PNG encoding, downscaled to fit in `max_dimension` when given.
+ * The buffer can be handed to image processing libraries without a decode step, errors are the same as `capture_window`.
+ */
+pub fn capture_window_rgba(
+  window_id: u32,
+  max_dimension: Option<u32>,
+) -> Result<RawImage, XWinError> {
+  let api = init_platform_api();
+  api.capture_window(window_id, max_dimension)
+}
+
+/**
+ * Capture thumbnails of many windows as PNG bytes with a single session (X11 connection, GDI device context,
+ * CoreGraphics window list). Windows that cannot be captured are skipped, the others are returned with their id.
+ */
+pub fn capture_windows(
+  window_ids: &[u32],
+  max_dimension: Option<u32>,
+) -> Result<Vec<(u32, Vec<u8>)>, XWinError> {
+  let api = init_platform_api();
+  Ok(
+    api
+      .capture_windows(window_ids, max_dimension)?
+      .into_iter()
+      .filter_map(|(window_id, image)| encode_png(&image).ok().map(|image| (window_id, image)))
+      .collect(),
+  )
+}
+
+/**
+ * Ask the window to close (`WM_CLOSE` on Windows, `_NET_CLOSE_WINDOW` or `WM_DELETE_WINDOW` on X11, close button
+ * with accessibility on macOS), the process is never killed.
+ * Return `false` when the window cannot receive the request.
+ */
+pub fn close_window(window_id: u32) -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.close_window(window_id)
+}
+
+/**
+ * Move and resize the window (`SetWindowPos` on Windows, `ConfigureWindow` and `_NET_MOVERESIZE_WINDOW` on X11,
+ * accessibility on macOS), fullscreen is toggled when `is_full_screen` differs from the current state.
+ * Return `false` when the window cannot receive the request.
+ */
+pub fn set_window_position(window_id: u32, position: &WindowPosition) -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.set_window_position(window_id, position)
+}
+
+/**
+ * Retrieve the position of the window only (`GetGeometry` and `TranslateCoordinates` on X11, `GetWindowRect`
+ * on Windows, window bounds of CoreGraphics on macOS), cheaper than recovering the whole window information.
+ * Return `None` when the window no longer exists.
+ */
+pub fn get_window_geometry(window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+  let api = init_platform_api();
+  api.get_window_geometry(window_id)
+}
+
+/**
+ * Read the raw value of a window property not modeled by `WindowInfo` (e.g. `_GTK_APPLICATION_ID`, `_STEAM_GAME`)
+ * to decode it without forking the crate, the atom `name` is interned only if it exists.
+ * Return the bytes with the name of the type atom and the format, `None` when the property is not set or the window
+ * no longer exists. Only supported on X11, `None` is returned on Wayland and other platforms.
+ */
+pub fn get_window_property(
+  window_id: u32,
+  name: &str,
+) -> Result<Option<WindowProperty>, XWinError> {
+  let api = init_platform_api();
+  api.get_window_property(window_id, name)
+}
+
+/**
+ * Minimize the window (`ShowWindow` on Windows, `WM_CHANGE_STATE` like `XIconifyWindow` on X11, `AXMinimized`
+ * with accessibility on macOS).
+ * Return `false` when the window cannot receive the request.
+ */
+pub fn minimize_window(window_id: u32) -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.minimize_window(window_id)
+}
+
+/**
+ * Maximize the window (`ShowWindow` on Windows, `_NET_WM_STATE` on X11, zoom button with accessibility on macOS).
+ * Return `false` when the window cannot receive the request.
+ */
+pub fn maximize_window(window_id: u32) -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.maximize_window(window_id)
+}
+
+/**
+ * Restore the minimized or maximized window (`ShowWindow` on Windows, `MapWindow` and `_NET_WM_STATE` on X11,
+ * `AXMinimized` with accessibility on macOS).
+ * Return `false` when the window cannot receive the request.
+ */
+pub fn restore_window(window_id: u32) -> Result<bool, XWinError> {
+  let api = init_platform_api();
+  api.restore_window(window_id)
+}
+
+/**
+ * Retrieve the topmost window under the mouse cursor (`QueryPointer` on X11, `WindowFromPoint` on Windows,
+ * hit test of on screen windows on macOS). Return `None` when the cursor is over the desktop.
+ */
+pub fn get_window_under_cursor() -> Result<Option<WindowInfo>, XWinError> {
+  let api = init_platform_api();
+  api.get_window_under_cursor()
+}
+
+/**
+ * Recover a JSON dump of the environment (os, session, window manager, permissions, monitors and windows) to join with bug reports.
+ * Titles will be replaced by `<redacted>` when `redact_titles` is `true`.
+ */
+pub fn diagnostics(redact_titles: bool) -> Result<String, XWinError> {
   let api = init_platform_api();
-  Ok(api.get_open_windows())
+  serde_json::to_string_pretty(&common::diagnostics::get_diagnostics(&api, redact_titles))
+    .map_err(|error| XWinError::PlatformError(error.to_string()))
 }
 
 /**
  * Install "@mininben90/x-win" Gnome extensions required for Linux using Gnome > 41.
  * This function will write extension files needed to correctly detect working windows with Wayland desktop environment.
- * **Restart session will be require to install the gnome extension.**
+ * Return `ExtensionInstallInfo` with the extension folder, `installed` is `false` on X11 sessions which don't need the extension.
+ * An error describing the detected version is returned when the running Gnome shell can't load the extension.
+ * **Restart session will be require to install the gnome extension** when `needs_reload` is `true`.
  */
-pub fn install_extension() -> Result<bool, XWinError> {
+pub fn install_extension() -> Result<ExtensionInstallInfo, XWinError> {
+  install_extension_with_options(&ExtensionInstallOptions::default())
+}
+
+/**
+ * Install "@mininben90/x-win" Gnome extensions using options.
+ * With `dry_run` nothing is written, the returned `ExtensionInstallInfo` lists the planned `files`, tells if the
+ * session needs the extension with `applicable` and if files would be written with `needs_reload`.
+ * A dry run never fails where the extension is not applicable (other platforms, Wayland without Gnome shell).
+ */
+pub fn install_extension_with_options(
+  options: &ExtensionInstallOptions,
+) -> Result<ExtensionInstallInfo, XWinError> {
   #[cfg(not(target_os = "linux"))]
   {
-    Ok(false)
+    if options.dry_run {
+      return Ok(ExtensionInstallInfo::new(
+        false,
+        "".to_owned(),
+        false,
+        false,
+        Vec::new(),
+      ));
+    }
+    Err(XWinError::PlatformError(
+      "Gnome extension is only supported on Linux".to_owned(),
+    ))
   }
   #[cfg(target_os = "linux")]
   {
-    Ok(linux::gnome_install_extension())
+    linux::gnome_install_extension(options)
+  }
+}
+
+/**
+ * Recover the states of the permissions required to recover titles, urls and captures without prompting the user.
+ * Allows to tell a permission wall from a genuine empty result on macOS, permissions are always granted on other platforms.
+ */
+pub fn check_permissions() -> Result<PermissionStatus, XWinError> {
+  #[cfg(target_os = "macos")]
+  {
+    Ok(macos::check_permissions())
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    Ok(PermissionStatus::new(true, true))
+  }
+}
+
+/**
+ * Show the system prompts of the macOS permissions not granted yet and return their current states,
+ * the application has to be restarted once a permission is granted. Nothing is prompted on other platforms.
+ */
+pub fn request_permissions() -> Result<PermissionStatus, XWinError> {
+  #[cfg(target_os = "macos")]
+  {
+    Ok(macos::request_permissions())
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    check_permissions()
   }
 }
 
@@ -187,6 +789,179 @@ mod tests {
     test_struct(window_info)
   }
 
+  #[test]
+  fn test_get_open_windows_with_options() -> Result<(), String> {
+    let options = OpenWindowsOptions {
+      include_url: true,
+      ..Default::default()
+    };
+    let open_windows = get_open_windows_with_options(&options).unwrap();
+    assert_ne!(open_windows.len(), 0);
+    let window_info = open_windows.first().unwrap().to_owned();
+    test_struct(window_info)
+  }
+
+  #[test]
+  fn test_get_open_windows_filtered() {
+    let open_windows = get_open_windows().unwrap();
+    let window_id = open_windows.first().unwrap().id;
+    let mut calls = 0;
+    let windows = get_open_windows_filtered(&OpenWindowsOptions::default(), |window_info| {
+      calls += 1;
+      window_info.id == window_id
+    })
+    .unwrap();
+    assert_eq!(calls, open_windows.len());
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0].info.start_time, open_windows[0].info.start_time);
+  }
+
+  #[test]
+  fn test_get_open_windows_with_progress() {
+    let mut gathered: Vec<u32> = Vec::new();
+    let windows = get_open_windows_with_progress(&OpenWindowsOptions::default(), |window_info| {
+      gathered.push(window_info.id)
+    })
+    .unwrap();
+    assert_eq!(gathered.len(), windows.len());
+    assert!(windows
+      .iter()
+      .all(|window_info| gathered.contains(&window_info.id)));
+  }
+
+  #[test]
+  fn test_get_windows_by_pid() -> Result<(), String> {
+    let open_windows = get_open_windows().unwrap();
+    let window_info = open_windows.first().unwrap().to_owned();
+    let process_id = window_info.info.process_id;
+    let windows = get_windows_by_pid(process_id).unwrap();
+    assert_ne!(windows.len(), 0);
+    assert!(windows
+      .iter()
+      .all(|window| window.info.process_id == process_id));
+    let expected: Vec<u32> = open_windows
+      .iter()
+      .filter(|window| window.info.process_id == process_id)
+      .map(|window| window.id)
+      .collect();
+    let ids: Vec<u32> = windows.iter().map(|window| window.id).collect();
+    assert_eq!(ids, expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_diagnostics() -> Result<(), String> {
+    let diagnostics = diagnostics(true).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&diagnostics).unwrap();
+    for key in [
+      "os",
+      "os_version",
+      "session_type",
+      "window_manager",
+      "permissions",
+      "monitors",
+      "active_window",
+      "open_windows",
+    ] {
+      assert!(value.get(key).is_some(), "missing key {}", key);
+    }
+    assert_eq!(value["os"], test_osname());
+    Ok(())
+  }
+
+  #[test]
+  fn test_xwin_error_display() -> Result<(), String> {
+    assert_eq!(
+      XWinError::NoDisplay.to_string(),
+      "Unable to connect to the display server"
+    );
+    assert_eq!(
+      XWinError::NoScreen.to_string(),
+      "No screen available on the display server"
+    );
+    assert_eq!(
+      XWinError::PlatformError("failure".to_owned()).to_string(),
+      "Platform error: failure"
+    );
+    let error: Box<dyn std::error::Error> = Box::new(XWinError::PermissionDenied);
+    assert_eq!(
+      error.to_string(),
+      "Permission denied to recover windows information"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_public_api_returns_xwin_error() -> Result<(), String> {
+    let _: fn() -> Result<WindowInfo, XWinError> = get_active_window;
+    let _: fn() -> Result<Vec<WindowInfo>, XWinError> = get_open_windows;
+    type WindowPredicate = fn(&WindowInfo) -> bool;
+    let _: fn(&OpenWindowsOptions, WindowPredicate) -> Result<Vec<WindowInfo>, XWinError> =
+      get_open_windows_filtered;
+    type WindowObserver = fn(&WindowInfo);
+    let _: fn(&OpenWindowsOptions, WindowObserver) -> Result<Vec<WindowInfo>, XWinError> =
+      get_open_windows_with_progress;
+    let _: fn(u32) -> Result<Vec<WindowInfo>, XWinError> = get_windows_by_pid;
+    let _: fn(&str, &TitleMatchOptions) -> Result<Vec<WindowInfo>, XWinError> =
+      find_windows_by_title;
+    let _: fn(&WindowInfo) -> Result<IconInfo, XWinError> = get_window_icon;
+    let _: fn(u32, Option<u32>) -> Result<Vec<u8>, XWinError> = capture_window;
+    let _: fn(u32, Option<u32>) -> Result<RawImage, XWinError> = capture_window_rgba;
+    let _: fn(&[u32], Option<u32>) -> Result<Vec<_>, XWinError> = capture_windows;
+    let _: fn(u32) -> Result<bool, XWinError> = close_window;
+    let _: fn(u32, &WindowPosition) -> Result<bool, XWinError> = set_window_position;
+    let _: fn(u32) -> Result<Option<WindowPosition>, XWinError> = get_window_geometry;
+    let _: fn(u32, &str) -> Result<Option<WindowProperty>, XWinError> = get_window_property;
+    let _: fn(u32) -> Result<bool, XWinError> = minimize_window;
+    let _: fn(u32) -> Result<bool, XWinError> = maximize_window;
+    let _: fn(u32) -> Result<bool, XWinError> = restore_window;
+    let _: fn(u32) -> Result<Option<WindowInfo>, XWinError> = get_active_window_for_display;
+    let _: fn() -> Result<Option<WindowInfo>, XWinError> = get_window_under_cursor;
+    let _: fn(&WindowInfo) -> Result<Option<WindowInfo>, XWinError> = active_window_changed;
+    let _: fn() -> Result<ExtensionInstallInfo, XWinError> = install_extension;
+    let _: fn(&ExtensionInstallOptions) -> Result<ExtensionInstallInfo, XWinError> =
+      install_extension_with_options;
+    let _: fn() -> Result<Vec<MonitorInfo>, XWinError> = get_monitors;
+    let _: fn() -> Result<Vec<Workspace>, XWinError> = get_workspaces;
+    let _: fn() -> Result<WindowPosition, XWinError> = get_desktop_bounds;
+    let _: fn(&ActiveWindowOptions, bool) -> Result<WindowInfo, XWinError> =
+      get_active_window_cached;
+    let _: fn() -> Result<u32, XWinError> = get_active_window_id;
+    let _: fn() -> Result<Option<WindowInfo>, XWinError> = get_previous_active_window;
+    let _: fn() -> Result<bool, XWinError> = is_fullscreen_active;
+    let _: fn() -> Result<WindowSnapshot, XWinError> = get_snapshot;
+    let _: fn() -> Result<Vec<AppInfo>, XWinError> = get_open_applications;
+    let _: fn() -> Result<PermissionStatus, XWinError> = check_permissions;
+    let _: fn() -> Result<PermissionStatus, XWinError> = request_permissions;
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(not(target_os = "linux"))]
+  fn test_install_extension_dry_run_not_applicable() {
+    let info = install_extension_with_options(&ExtensionInstallOptions { dry_run: true }).unwrap();
+    assert!(!info.applicable && !info.installed && info.files.is_empty());
+    assert!(install_extension().is_err());
+  }
+
+  #[test]
+  #[cfg(not(target_os = "macos"))]
+  fn test_check_permissions_granted() {
+    assert_eq!(check_permissions(), Ok(PermissionStatus::new(true, true)));
+  }
+
+  #[test]
+  fn test_get_process_env() {
+    let env = get_process_env(std::process::id());
+    assert_eq!(env.get("PATH"), std::env::var("PATH").ok().as_ref());
+    assert!(get_process_env(u32::MAX).is_empty());
+  }
+
+  #[test]
+  fn test_get_desktop_entry_without_class() {
+    assert_eq!(get_desktop_entry(&empty_entity()), DesktopEntry::default());
+  }
+
   #[test]
   fn test_os_name() -> Result<(), String> {
     let os_name = os_name();
@@ -207,7 +982,7 @@ mod tests {
   fn test_get_window_icon() -> Result<(), String> {
     let window_info: &WindowInfo = &get_active_window().unwrap();
     test_struct(window_info.clone()).unwrap();
-    let icon_info = get_window_icon(&window_info).unwrap();
+    let icon_info = get_window_icon(window_info).unwrap();
     assert_ne!(icon_info.data, "");
     assert_ne!(icon_info.height, 0);
     assert_ne!(icon_info.width, 0);
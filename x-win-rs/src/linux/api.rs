@@ -1,65 +1,338 @@
 #![deny(unused_imports)]
 
+mod atspi_api;
 mod common_api;
+mod desktop_entry_api;
 mod gnome_shell;
 mod wayland_api;
 mod wayland_eval_api;
 mod wayland_extension_api;
 mod x11_api;
+mod x11_connection;
 
+use atspi_api::{get_browser_url, get_focused_element, is_accessibility_bus_available, is_browser};
 use common_api::is_wayland_desktop;
+
+pub use common_api::{get_process_cpu_time, get_process_env, os_version};
+pub use desktop_entry_api::get_desktop_entry;
 use wayland_api::WaylandApi;
+use wayland_extension_api::{get_extension_file_names, get_extension_path};
 use x11_api::X11Api;
 
-use crate::common::{
-  api::Api,
-  x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+use crate::{
+  common::{
+    api::Api,
+    change_notifier::ChangeSource,
+    x_win_struct::{
+      active_window_options::ActiveWindowOptions, extension_install_info::ExtensionInstallInfo,
+      extension_install_options::ExtensionInstallOptions, icon_info::IconInfo,
+      monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions, raw_image::RawImage,
+      window_info::WindowInfo, window_position::WindowPosition, window_property::WindowProperty,
+      workspace::Workspace,
+    },
+  },
+  XWinError,
 };
 
+/**
+ * Name of the window manager from _NET_SUPPORTING_WM_CHECK, it's also set by Wayland compositors running XWayland
+ */
+pub fn window_manager_name() -> String {
+  X11Api::default().get_window_manager_name()
+}
+
 pub trait APIGnome {
-  fn install_extension() -> bool;
+  fn install_extension(
+    options: &ExtensionInstallOptions,
+  ) -> Result<ExtensionInstallInfo, XWinError>;
   fn uninstall_extension() -> bool;
   fn enable_extension() -> bool;
   fn disable_extension() -> bool;
 }
 
-pub struct LinuxAPI {}
+pub struct LinuxAPI {
+  display: Option<String>,
+}
+
+impl LinuxAPI {
+  /**
+   * Create the API, windows are always recovered from the X display `display` when set
+   */
+  pub fn new(display: Option<String>) -> Self {
+    Self { display }
+  }
+
+  /**
+   * To know if the Wayland API has to be used, an explicit X display is always used through X11
+   */
+  fn is_wayland(&self) -> bool {
+    self.display.is_none() && is_wayland_desktop()
+  }
+
+  fn x11_api(&self) -> X11Api {
+    X11Api::new(self.display.clone())
+  }
+}
 
 /**
  * Impl. for windows system
  */
 impl Api for LinuxAPI {
-  fn get_active_window(&self) -> WindowInfo {
-    if is_wayland_desktop() {
-      (WaylandApi {}).get_active_window()
+  fn get_active_window(&self, options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError> {
+    let mut window_info = if self.is_wayland() {
+      (WaylandApi {}).get_active_window(options)
     } else {
-      (X11Api {}).get_active_window()
+      self.x11_api().get_active_window(options)
+    }?;
+    if options.include_url {
+      set_browser_url(&mut window_info);
     }
+    if options.include_focused_element && window_info.info.process_id != 0 {
+      window_info.focused_element =
+        get_focused_element(window_info.info.process_id, &window_info.title);
+    }
+    Ok(window_info)
   }
 
-  fn get_open_windows(&self) -> Vec<WindowInfo> {
-    if is_wayland_desktop() {
-      (WaylandApi {}).get_open_windows()
+  fn get_active_window_id(&self) -> Result<u32, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_active_window_id()
+    } else {
+      self.x11_api().get_active_window_id()
+    }
+  }
+
+  fn is_fullscreen_active(&self) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).is_fullscreen_active()
+    } else {
+      self.x11_api().is_fullscreen_active()
+    }
+  }
+
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> {
+    self.get_open_windows_filtered(options, &mut |_window_info| true)
+  }
+
+  fn get_open_windows_filtered(
+    &self,
+    options: &OpenWindowsOptions,
+    filter: &mut dyn FnMut(&WindowInfo) -> bool,
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut windows = if self.is_wayland() {
+      (WaylandApi {}).get_open_windows_filtered(options, filter)
+    } else {
+      self.x11_api().get_open_windows_filtered(options, filter)
+    }?;
+    if options.include_url {
+      windows.iter_mut().for_each(set_browser_url);
+    }
+    Ok(windows)
+  }
+
+  fn get_open_windows_with_progress(
+    &self,
+    options: &OpenWindowsOptions,
+    on_window: &mut dyn FnMut(&mut WindowInfo),
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    // Urls are recovered before the window is passed
+    let on_window = &mut |window_info: &mut WindowInfo| {
+      if options.include_url {
+        set_browser_url(window_info);
+      }
+      on_window(window_info);
+    };
+    if self.is_wayland() {
+      (WaylandApi {}).get_open_windows_with_progress(options, on_window)
+    } else {
+      self
+        .x11_api()
+        .get_open_windows_with_progress(options, on_window)
+    }
+  }
+
+  fn get_windows_by_pid(&self, process_id: u32) -> Result<Vec<WindowInfo>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_windows_by_pid(process_id)
+    } else {
+      self.x11_api().get_windows_by_pid(process_id)
+    }
+  }
+
+  fn capture_window(
+    &self,
+    window_id: u32,
+    max_dimension: Option<u32>,
+  ) -> Result<RawImage, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).capture_window(window_id, max_dimension)
+    } else {
+      self.x11_api().capture_window(window_id, max_dimension)
+    }
+  }
+
+  fn capture_windows(
+    &self,
+    window_ids: &[u32],
+    max_dimension: Option<u32>,
+  ) -> Result<Vec<(u32, RawImage)>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).capture_windows(window_ids, max_dimension)
+    } else {
+      self.x11_api().capture_windows(window_ids, max_dimension)
+    }
+  }
+
+  fn close_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).close_window(window_id)
+    } else {
+      self.x11_api().close_window(window_id)
+    }
+  }
+
+  fn get_window_under_cursor(&self) -> Result<Option<WindowInfo>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_window_under_cursor()
+    } else {
+      self.x11_api().get_window_under_cursor()
+    }
+  }
+
+  fn set_window_position(
+    &self,
+    window_id: u32,
+    position: &WindowPosition,
+  ) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).set_window_position(window_id, position)
+    } else {
+      self.x11_api().set_window_position(window_id, position)
+    }
+  }
+
+  fn get_window_geometry(&self, window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_window_geometry(window_id)
+    } else {
+      self.x11_api().get_window_geometry(window_id)
+    }
+  }
+
+  fn minimize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).minimize_window(window_id)
+    } else {
+      self.x11_api().minimize_window(window_id)
+    }
+  }
+
+  fn maximize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).maximize_window(window_id)
+    } else {
+      self.x11_api().maximize_window(window_id)
+    }
+  }
+
+  fn restore_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).restore_window(window_id)
     } else {
-      (X11Api {}).get_open_windows()
+      self.x11_api().restore_window(window_id)
     }
   }
 
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo {
-    if is_wayland_desktop() {
+    if self.is_wayland() {
       (WaylandApi {}).get_app_icon(window_info)
     } else {
-      (X11Api {}).get_app_icon(window_info)
+      self.x11_api().get_app_icon(window_info)
+    }
+  }
+
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_monitors()
+    } else {
+      self.x11_api().get_monitors()
+    }
+  }
+
+  fn get_workspaces(&self) -> Result<Vec<Workspace>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_workspaces()
+    } else {
+      self.x11_api().get_workspaces()
+    }
+  }
+
+  fn get_window_property(
+    &self,
+    window_id: u32,
+    name: &str,
+  ) -> Result<Option<WindowProperty>, XWinError> {
+    if self.is_wayland() {
+      (WaylandApi {}).get_window_property(window_id, name)
+    } else {
+      self.x11_api().get_window_property(window_id, name)
+    }
+  }
+
+  fn change_source(&self) -> Option<Box<dyn ChangeSource>> {
+    if self.is_wayland() {
+      (WaylandApi {}).change_source()
+    } else {
+      self.x11_api().change_source()
     }
   }
 }
 
+/**
+ * Session type of the desktop (`wayland` or `x11`)
+ */
+pub fn session_type() -> String {
+  if is_wayland_desktop() {
+    "wayland".to_owned()
+  } else {
+    "x11".to_owned()
+  }
+}
+
+/**
+ * Permissions states required by some features
+ */
+pub fn get_permissions() -> Vec<(String, bool)> {
+  vec![(
+    "accessibility_bus".to_owned(),
+    is_accessibility_bus_available(),
+  )]
+}
+
+/**
+ * Set url of the window when it belongs to a supported browser
+ */
+fn set_browser_url(window_info: &mut WindowInfo) {
+  if is_browser(&window_info.info.exec_name) {
+    window_info.url = get_browser_url(window_info.info.process_id, &window_info.title);
+  }
+}
+
 impl APIGnome for LinuxAPI {
-  fn install_extension() -> bool {
+  fn install_extension(
+    options: &ExtensionInstallOptions,
+  ) -> Result<ExtensionInstallInfo, XWinError> {
     if is_wayland_desktop() {
-      WaylandApi::install_extension()
+      WaylandApi::install_extension(options)
     } else {
-      false
+      // X11 sessions recover windows without the extension
+      Ok(ExtensionInstallInfo::new(
+        false,
+        get_extension_path().to_string_lossy().to_string(),
+        false,
+        false,
+        get_extension_file_names(),
+      ))
     }
   }
 
@@ -87,3 +360,33 @@ impl APIGnome for LinuxAPI {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_api<T: Api>(_api: &T) {
+    let _: fn(&T, &ActiveWindowOptions) -> Result<WindowInfo, XWinError> = T::get_active_window;
+    let _: fn(&T, &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> = T::get_open_windows;
+  }
+
+  #[test]
+  fn test_linux_backends_share_api_trait() {
+    assert_api(&LinuxAPI::new(None));
+    assert_api(&X11Api::default());
+    assert_api(&WaylandApi {});
+  }
+
+  #[test]
+  fn test_url_initialized_for_other_applications() {
+    let mut window_info = crate::empty_entity();
+    window_info.info.exec_name = "gedit".to_owned();
+    set_browser_url(&mut window_info);
+    assert_eq!(window_info.url, "");
+  }
+
+  #[test]
+  fn test_display_forces_x11() {
+    assert!(!LinuxAPI::new(Some(":1".to_owned())).is_wayland());
+  }
+}
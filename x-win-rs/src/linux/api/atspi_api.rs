@@ -0,0 +1,339 @@
+#![deny(unused_imports)]
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+use zbus::{
+  zvariant::{OwnedObjectPath, OwnedValue},
+  Connection,
+};
+
+use crate::common::x_win_struct::{
+  focused_element::FocusedElement, window_position::WindowPosition,
+};
+
+const ATSPI_REGISTRY: &str = "org.a11y.atspi.Registry";
+const ATSPI_ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const ATSPI_ACCESSIBLE_INTERFACE: &str = "org.a11y.atspi.Accessible";
+const ATSPI_DOCUMENT_INTERFACE: &str = "org.a11y.atspi.Document";
+const ATSPI_COMPONENT_INTERFACE: &str = "org.a11y.atspi.Component";
+
+const ATSPI_ROLE_DOCUMENT_WEB: u32 = 95;
+const ATSPI_STATE_ACTIVE: u32 = 1;
+const ATSPI_STATE_FOCUSED: u32 = 12;
+const ATSPI_STATE_SHOWING: u32 = 25;
+
+/**
+ * Screen coordinates of AtspiCoordType
+ */
+const ATSPI_COORD_TYPE_SCREEN: u32 = 0;
+
+/**
+ * Limit of accessible nodes visited for one window to avoid walking huge trees
+ */
+const ATSPI_MAX_NODES: usize = 1000;
+
+/**
+ * To know if the executable name is one of browsers supported by AT-SPI url recovery
+ */
+pub fn is_browser(exec_name: &str) -> bool {
+  matches!(
+    exec_name.to_lowercase().as_str(),
+    "firefox"
+      | "firefox-bin"
+      | "firefox-esr"
+      | "firefox-developer-edition"
+      | "chrome"
+      | "google-chrome"
+      | "chromium"
+      | "chromium-browser"
+      | "brave"
+      | "brave-browser"
+      | "msedge"
+      | "microsoft-edge"
+      | "opera"
+      | "vivaldi"
+      | "vivaldi-bin"
+  )
+}
+
+/**
+ * Recover url of the active tab of a browser window with help of AT-SPI.
+ * Return an empty string when the accessibility bus or the document is not available.
+ */
+pub fn get_browser_url(process_id: u32, title: &str) -> String {
+  if let Some(connection) = accessibility_connection() {
+    for (bus_name, path) in get_children(&connection, ATSPI_REGISTRY, ATSPI_ROOT_PATH) {
+      if get_bus_process_id(&connection, &bus_name) == Some(process_id) {
+        if let Some(url) = find_document_url(&connection, &bus_name, path.as_str(), title) {
+          return url;
+        }
+      }
+    }
+  }
+  "".to_owned()
+}
+
+/**
+ * Recover the focused element of the application window with help of AT-SPI.
+ * Return `None` when the accessibility bus is not available or no element is focused.
+ */
+pub fn get_focused_element(process_id: u32, title: &str) -> Option<FocusedElement> {
+  let connection = accessibility_connection()?;
+  get_children(&connection, ATSPI_REGISTRY, ATSPI_ROOT_PATH)
+    .into_iter()
+    .filter(|(bus_name, _)| get_bus_process_id(&connection, bus_name) == Some(process_id))
+    .find_map(|(bus_name, path)| find_focused_element(&connection, &bus_name, path.as_str(), title))
+}
+
+/**
+ * To know if the accessibility bus can be reached
+ */
+pub fn is_accessibility_bus_available() -> bool {
+  accessibility_connection().is_some()
+}
+
+/**
+ * Open a connection to the accessibility bus
+ */
+fn accessibility_connection() -> Option<Connection> {
+  let session = Connection::new_session().ok()?;
+  let response = session
+    .call_method(
+      Some("org.a11y.Bus"),
+      "/org/a11y/bus",
+      Some("org.a11y.Bus"),
+      "GetAddress",
+      &(),
+    )
+    .ok()?;
+  let address: String = response.body().ok()?;
+  Connection::new_for_address(&address, true).ok()
+}
+
+fn get_bus_process_id(connection: &Connection, bus_name: &str) -> Option<u32> {
+  let response = connection
+    .call_method(
+      Some("org.freedesktop.DBus"),
+      "/org/freedesktop/DBus",
+      Some("org.freedesktop.DBus"),
+      "GetConnectionUnixProcessID",
+      &bus_name,
+    )
+    .ok()?;
+  response.body::<u32>().ok()
+}
+
+fn get_children(
+  connection: &Connection,
+  bus_name: &str,
+  path: &str,
+) -> Vec<(String, OwnedObjectPath)> {
+  connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_ACCESSIBLE_INTERFACE),
+      "GetChildren",
+      &(),
+    )
+    .ok()
+    .and_then(|response| response.body::<Vec<(String, OwnedObjectPath)>>().ok())
+    .unwrap_or_default()
+}
+
+fn get_role(connection: &Connection, bus_name: &str, path: &str) -> u32 {
+  connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_ACCESSIBLE_INTERFACE),
+      "GetRole",
+      &(),
+    )
+    .ok()
+    .and_then(|response| response.body::<u32>().ok())
+    .unwrap_or(0)
+}
+
+fn has_state(connection: &Connection, bus_name: &str, path: &str, state: u32) -> bool {
+  let states = connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_ACCESSIBLE_INTERFACE),
+      "GetState",
+      &(),
+    )
+    .ok()
+    .and_then(|response| response.body::<Vec<u32>>().ok())
+    .unwrap_or_default();
+  states
+    .get((state / 32) as usize)
+    .map(|bits| bits & (1 << (state % 32)) != 0)
+    .unwrap_or(false)
+}
+
+fn get_name(connection: &Connection, bus_name: &str, path: &str) -> String {
+  connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some("org.freedesktop.DBus.Properties"),
+      "Get",
+      &(ATSPI_ACCESSIBLE_INTERFACE, "Name"),
+    )
+    .ok()
+    .and_then(|response| response.body::<OwnedValue>().ok())
+    .and_then(|value| String::try_from(value).ok())
+    .unwrap_or_default()
+}
+
+fn get_document_url(connection: &Connection, bus_name: &str, path: &str) -> String {
+  connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_DOCUMENT_INTERFACE),
+      "GetAttributeValue",
+      &"DocURL",
+    )
+    .ok()
+    .and_then(|response| response.body::<String>().ok())
+    .unwrap_or_default()
+}
+
+fn get_role_name(connection: &Connection, bus_name: &str, path: &str) -> String {
+  connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_ACCESSIBLE_INTERFACE),
+      "GetRoleName",
+      &(),
+    )
+    .ok()
+    .and_then(|response| response.body::<String>().ok())
+    .unwrap_or_default()
+}
+
+fn get_extents(connection: &Connection, bus_name: &str, path: &str) -> WindowPosition {
+  let (x, y, width, height) = connection
+    .call_method(
+      Some(bus_name),
+      path,
+      Some(ATSPI_COMPONENT_INTERFACE),
+      "GetExtents",
+      &ATSPI_COORD_TYPE_SCREEN,
+    )
+    .ok()
+    .and_then(|response| response.body::<(i32, i32, i32, i32)>().ok())
+    .unwrap_or_default();
+  WindowPosition {
+    x,
+    y,
+    width,
+    height,
+    is_full_screen: false,
+  }
+}
+
+/**
+ * Get application frames with the one matching the window title (or the active one) first
+ */
+fn get_sorted_frames(
+  connection: &Connection,
+  bus_name: &str,
+  app_path: &str,
+  title: &str,
+) -> Vec<(String, OwnedObjectPath)> {
+  let mut frames = get_children(connection, bus_name, app_path);
+  frames.sort_by_cached_key(|(frame_bus_name, frame_path)| {
+    if !title.is_empty() && get_name(connection, frame_bus_name, frame_path.as_str()).eq(title) {
+      0
+    } else if has_state(
+      connection,
+      frame_bus_name,
+      frame_path.as_str(),
+      ATSPI_STATE_ACTIVE,
+    ) {
+      1
+    } else {
+      2
+    }
+  });
+  frames
+}
+
+/**
+ * Search the focused element among the showing elements of the application frames
+ */
+fn find_focused_element(
+  connection: &Connection,
+  bus_name: &str,
+  app_path: &str,
+  title: &str,
+) -> Option<FocusedElement> {
+  let mut visited: usize = 0;
+  for frame in get_sorted_frames(connection, bus_name, app_path, title) {
+    let mut queue: VecDeque<(String, OwnedObjectPath)> = VecDeque::from(vec![frame]);
+    while let Some((node_bus_name, node_path)) = queue.pop_front() {
+      visited += 1;
+      if visited > ATSPI_MAX_NODES {
+        return None;
+      }
+      let path = node_path.as_str();
+      if has_state(connection, &node_bus_name, path, ATSPI_STATE_FOCUSED) {
+        return Some(FocusedElement::new(
+          get_role_name(connection, &node_bus_name, path),
+          get_name(connection, &node_bus_name, path),
+          get_extents(connection, &node_bus_name, path),
+        ));
+      }
+      // Hidden subtrees can't hold the focus
+      if has_state(connection, &node_bus_name, path, ATSPI_STATE_SHOWING) {
+        queue.extend(get_children(connection, &node_bus_name, path));
+      }
+    }
+  }
+  None
+}
+
+/**
+ * Search the showing web document of the application frame matching the window title
+ * (or the active frame) and return its url.
+ */
+fn find_document_url(
+  connection: &Connection,
+  bus_name: &str,
+  app_path: &str,
+  title: &str,
+) -> Option<String> {
+  let mut visited: usize = 0;
+  for frame in get_sorted_frames(connection, bus_name, app_path, title) {
+    let mut queue: VecDeque<(String, OwnedObjectPath)> = VecDeque::from(vec![frame]);
+    while let Some((node_bus_name, node_path)) = queue.pop_front() {
+      visited += 1;
+      if visited > ATSPI_MAX_NODES {
+        return None;
+      }
+      if get_role(connection, &node_bus_name, node_path.as_str()) == ATSPI_ROLE_DOCUMENT_WEB {
+        // Page content is never walked, only the showing document is relevant
+        if has_state(
+          connection,
+          &node_bus_name,
+          node_path.as_str(),
+          ATSPI_STATE_SHOWING,
+        ) {
+          let url = get_document_url(connection, &node_bus_name, node_path.as_str());
+          if !url.is_empty() {
+            return Some(url);
+          }
+        }
+        continue;
+      }
+      queue.extend(get_children(connection, &node_bus_name, node_path.as_str()));
+    }
+  }
+  None
+}
@@ -1,20 +1,15 @@
 #![deny(unused_imports)]
 
 use std::{
+  collections::HashMap,
   fs::{read_link, File},
   io::Read,
+  time::Duration,
 };
 
 use std::process::Command;
 
-use crate::common::{api::empty_entity, x_win_struct::window_info::WindowInfo};
-
-/**
- * To know the os
- */
-pub fn os_name() -> String {
-  r#"linux"#.to_owned()
-}
+use crate::common::api::parse_environ;
 
 /**
  * To known if desktop run with wayland or not
@@ -26,40 +21,276 @@ pub fn is_wayland_desktop() -> bool {
 }
 
 /**
- * Get usage memory of window from proc
+ * Recover distribution name from os-release and fallback to kernel release
+ */
+pub fn os_version() -> String {
+  if let Ok(os_release) = std::fs::read_to_string("/etc/os-release") {
+    if let Some(pretty_name) = os_release
+      .lines()
+      .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+    {
+      return pretty_name.trim_matches('"').to_owned();
+    }
+  }
+  std::fs::read_to_string("/proc/sys/kernel/osrelease")
+    .map(|release| release.trim().to_owned())
+    .unwrap_or_default()
+}
+
+/**
+ * Get usage memory of window from proc, `0` when the process is not readable (e.g. it exited meanwhile)
  */
 pub fn get_window_memory_usage(pid: u32) -> u32 {
-  let mut statm_file = File::open(format!("/proc/{}/statm", pid)).unwrap();
   let mut statm_content = String::new();
-  statm_file.read_to_string(&mut statm_content).unwrap();
-  let statm_parts: Vec<&str> = statm_content.split(" ").collect();
-  statm_parts[0].parse().unwrap()
+  if File::open(format!("/proc/{}/statm", pid))
+    .and_then(|mut statm_file| statm_file.read_to_string(&mut statm_content))
+    .is_err()
+  {
+    return 0;
+  }
+  statm_content
+    .split(' ')
+    .next()
+    .and_then(|size| size.parse().ok())
+    .unwrap_or(0)
 }
 
 /**
- * Recover path and name of application from proc
+ * Recover path and name of application from proc, empty when the process is not readable (e.g. it exited meanwhile)
  */
 pub fn get_window_path_name(pid: u32) -> (String, String) {
-  let executable_path = read_link(format!("/proc/{}/exe", pid)).unwrap();
+  let Ok(executable_path) = read_link(format!("/proc/{}/exe", pid)) else {
+    return ("".to_owned(), "".to_owned());
+  };
   let path = executable_path.display().to_string();
-  let name = executable_path.file_name().unwrap();
-  let name = name.to_string_lossy().to_string();
+  let name = executable_path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_default();
   (path, name)
 }
 
-pub fn init_entity() -> WindowInfo {
-  let mut window_info: WindowInfo = empty_entity();
-  window_info.os = os_name();
-  window_info
+/**
+ * Recover arguments of the process from proc (null separated), empty when the process is not readable
+ */
+pub fn get_process_command_line(pid: u32) -> String {
+  std::fs::read(format!("/proc/{}/cmdline", pid))
+    .map(|cmdline| {
+      cmdline
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .unwrap_or_default()
 }
 
-pub fn get_gnome_version() -> String {
-  if let Ok(output) = Command::new("gnome-shell").arg("--version").output() {
-    if output.status.success() {
-      let stdout = String::from_utf8_lossy(&output.stdout);
-      let version = stdout.split_whitespace().nth(2).unwrap_or("999");
-      return version.to_owned();
+/**
+ * Recover name of the user owning the process from the effective uid of proc status,
+ * empty when the process is not readable
+ */
+pub fn get_process_user(pid: u32) -> String {
+  std::fs::read_to_string(format!("/proc/{}/status", pid))
+    .ok()
+    .and_then(|status| get_status_uid(&status))
+    .and_then(get_user_name)
+    .unwrap_or_default()
+}
+
+fn get_status_uid(status: &str) -> Option<u32> {
+  status
+    .lines()
+    .find_map(|line| line.strip_prefix("Uid:"))
+    .and_then(|uids| uids.split_whitespace().nth(1))
+    .and_then(|uid| uid.parse().ok())
+}
+
+/**
+ * Resolve user name of uid with getpwuid_r
+ */
+fn get_user_name(uid: u32) -> Option<String> {
+  let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+  let mut result: *mut libc::passwd = std::ptr::null_mut();
+  let mut buffer: Vec<libc::c_char> = vec![0; 4096];
+  let status = unsafe {
+    libc::getpwuid_r(
+      uid,
+      &mut passwd,
+      buffer.as_mut_ptr(),
+      buffer.len(),
+      &mut result,
+    )
+  };
+  if status != 0 || result.is_null() || passwd.pw_name.is_null() {
+    return None;
+  }
+  let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+  Some(name.to_string_lossy().to_string())
+}
+
+/**
+ * Recover start time of the process as a Unix timestamp in milliseconds from `/proc/<pid>/stat`, `0` when unavailable
+ */
+pub fn get_process_start_time(pid: u32) -> u64 {
+  let start_ticks = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+    .ok()
+    .and_then(|stat| parse_start_ticks(&stat));
+  let boot_time = std::fs::read_to_string("/proc/stat")
+    .ok()
+    .and_then(|stat| parse_boot_time(&stat));
+  let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+  match (start_ticks, boot_time) {
+    (Some(start_ticks), Some(boot_time)) if clock_ticks > 0 => {
+      boot_time * 1000 + start_ticks * 1000 / clock_ticks as u64
     }
+    _ => 0,
+  }
+}
+
+/**
+ * Recover CPU time (user and system) used by the process from `/proc/<pid>/stat`, `None` when unavailable
+ */
+pub fn get_process_cpu_time(pid: u32) -> Option<Duration> {
+  let cpu_ticks = parse_cpu_ticks(&std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)?;
+  let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+  if clock_ticks <= 0 {
+    return None;
+  }
+  Some(Duration::from_secs_f64(
+    cpu_ticks as f64 / clock_ticks as f64,
+  ))
+}
+
+/**
+ * Recover environment variables of the process from `/proc/<pid>/environ`, empty when it is not readable
+ * (processes of other users). Variables set by the process after its start are not visible.
+ */
+pub fn get_process_env(pid: u32) -> HashMap<String, String> {
+  std::fs::read(format!("/proc/{}/environ", pid))
+    .map(|data| parse_environ(&data))
+    .unwrap_or_default()
+}
+
+/**
+ * Get user and system times (fields 14 and 15, in clock ticks) of `/proc/<pid>/stat`
+ */
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+  let (_, fields) = stat.rsplit_once(')')?;
+  let mut fields = fields.split_whitespace().skip(11);
+  let user_ticks: u64 = fields.next()?.parse().ok()?;
+  let system_ticks: u64 = fields.next()?.parse().ok()?;
+  Some(user_ticks + system_ticks)
+}
+
+/**
+ * Get start time (field 22, in clock ticks since boot) of `/proc/<pid>/stat`,
+ * fields are counted after the last `)` as the executable name can contain spaces
+ */
+fn parse_start_ticks(stat: &str) -> Option<u64> {
+  let (_, fields) = stat.rsplit_once(')')?;
+  fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+/**
+ * Get boot time (`btime`, in seconds since epoch) of `/proc/stat`
+ */
+fn parse_boot_time(stat: &str) -> Option<u64> {
+  stat
+    .lines()
+    .find_map(|line| line.strip_prefix("btime "))
+    .and_then(|boot_time| boot_time.trim().parse().ok())
+}
+
+pub fn get_gnome_version() -> String {
+  get_installed_gnome_version().unwrap_or("999".into())
+}
+
+/**
+ * Recover version printed by `gnome-shell --version`, `None` when gnome shell is not installed
+ */
+pub fn get_installed_gnome_version() -> Option<String> {
+  let output = Command::new("gnome-shell").arg("--version").output().ok()?;
+  if output.status.success() {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    return Some(stdout.split_whitespace().nth(2).unwrap_or("999").to_owned());
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_exited_process() {
+    assert_eq!(get_window_memory_usage(u32::MAX), 0);
+    assert_eq!(
+      get_window_path_name(u32::MAX),
+      ("".to_owned(), "".to_owned())
+    );
+    assert_ne!(get_window_memory_usage(std::process::id()), 0);
+    assert_ne!(get_window_path_name(std::process::id()).1, "");
+  }
+
+  #[test]
+  fn test_get_process_command_line() {
+    let command_line = get_process_command_line(std::process::id());
+    let args: Vec<String> = std::env::args().collect();
+    assert_eq!(command_line, args.join(" "));
+    assert_eq!(get_process_command_line(u32::MAX), "");
+  }
+
+  #[test]
+  fn test_get_status_uid() {
+    let status = "Name:\tbash\nUid:\t1000\t1001\t1001\t1001\nGid:\t1000\t1000\t1000\t1000\n";
+    assert_eq!(get_status_uid(status), Some(1001));
+    assert_eq!(get_status_uid("Name:\tbash\n"), None);
+  }
+
+  #[test]
+  fn test_get_process_user() {
+    let uid = unsafe { libc::geteuid() };
+    assert_eq!(
+      get_process_user(std::process::id()),
+      get_user_name(uid).unwrap_or_default()
+    );
+    assert_eq!(get_process_user(u32::MAX), "");
+  }
+
+  #[test]
+  fn test_parse_start_ticks() {
+    let stat =
+      "1234 (my (app) name) S 1 1234 1234 0 -1 4194560 100 0 0 0 5 3 0 0 20 0 1 0 98765 1000 200";
+    assert_eq!(parse_start_ticks(stat), Some(98765));
+    assert_eq!(parse_start_ticks("1234 (app) S 1"), None);
+  }
+
+  #[test]
+  fn test_parse_cpu_ticks() {
+    let stat =
+      "1234 (my (app) name) S 1 1234 1234 0 -1 4194560 100 0 0 0 5 3 0 0 20 0 1 0 98765 1000 200";
+    assert_eq!(parse_cpu_ticks(stat), Some(8));
+    assert_eq!(parse_cpu_ticks("1234 (app) S 1"), None);
+    assert!(get_process_cpu_time(std::process::id()).is_some());
+    assert_eq!(get_process_cpu_time(u32::MAX), None);
+  }
+
+  #[test]
+  fn test_parse_boot_time() {
+    let stat = "cpu  1 2 3 4\nintr 5\nbtime 1700000000\nprocesses 42\n";
+    assert_eq!(parse_boot_time(stat), Some(1700000000));
+    assert_eq!(parse_boot_time("cpu  1 2 3 4\n"), None);
+  }
+
+  #[test]
+  fn test_get_process_start_time() {
+    let now = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .unwrap()
+      .as_millis() as u64;
+    let start_time = get_process_start_time(std::process::id());
+    assert!(start_time > 0 && start_time <= now + 1000);
+    assert_eq!(get_process_start_time(u32::MAX), 0);
   }
-  "999".into()
 }
@@ -0,0 +1,509 @@
+#![deny(unused_imports)]
+
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+  time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::common::x_win_struct::desktop_entry::DesktopEntry;
+
+/**
+ * Depth of the sub-directories of `applications` walked for `.desktop` files (e.g. `applications/kde4`)
+ */
+const APPLICATIONS_MAX_DEPTH: usize = 4;
+
+/**
+ * Keys of the `[Desktop Entry]` group used to match an application
+ */
+#[derive(Debug, Default, PartialEq)]
+struct ParsedEntry {
+  /**
+   * Desktop file id, the path relative to `applications` without `.desktop` and `/` replaced by `-`
+   */
+  id: String,
+  startup_wm_class: String,
+  /**
+   * File name of the program started by `Exec`
+   */
+  exec_name: String,
+  name: String,
+  icon: String,
+}
+
+/**
+ * Entries parsed from the data directories and locales they were read with
+ */
+#[derive(Default)]
+struct DesktopEntriesCache {
+  data_dirs: Vec<PathBuf>,
+  locales: Vec<String>,
+  /**
+   * Modification time of each walked directory, an installed or removed desktop file changes the one of its directory
+   */
+  dir_mtimes: Vec<(PathBuf, Option<SystemTime>)>,
+  entries: Option<Arc<Vec<ParsedEntry>>>,
+}
+
+impl DesktopEntriesCache {
+  /**
+   * Entries of `data_dirs`, parsed again when the data directories or the locales are not the cached ones
+   * or when one of the walked directories has been modified
+   */
+  fn entries(&mut self, data_dirs: &[PathBuf], locales: &[String]) -> Arc<Vec<ParsedEntry>> {
+    if let Some(entries) = self.entries.as_ref() {
+      if self.data_dirs == data_dirs
+        && self.locales == locales
+        && self
+          .dir_mtimes
+          .iter()
+          .all(|(dir, mtime)| get_mtime(dir) == *mtime)
+      {
+        return Arc::clone(entries);
+      }
+    }
+    let mut dirs = Vec::new();
+    let entries = Arc::new(load_desktop_entries(data_dirs, locales, &mut dirs));
+    self.data_dirs = data_dirs.to_vec();
+    self.locales = locales.to_vec();
+    self.dir_mtimes = dirs
+      .into_iter()
+      .map(|dir| {
+        let mtime = get_mtime(&dir);
+        (dir, mtime)
+      })
+      .collect();
+    self.entries = Some(Arc::clone(&entries));
+    entries
+  }
+}
+
+/**
+ * Desktop entries shared by all lookups, a window list resolves the entry of each window
+ */
+static DESKTOP_ENTRIES: Lazy<Mutex<DesktopEntriesCache>> =
+  Lazy::new(|| Mutex::new(DesktopEntriesCache::default()));
+
+fn get_mtime(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path)
+    .and_then(|metadata| metadata.modified())
+    .ok()
+}
+
+/**
+ * Resolve the `.desktop` entry of an application from its WM_CLASS class (app id on Wayland) and its executable name,
+ * empty when no entry of the XDG data directories matches
+ */
+pub fn get_desktop_entry(class: &str, exec_name: &str) -> DesktopEntry {
+  if class.is_empty() && exec_name.is_empty() {
+    return DesktopEntry::default();
+  }
+  let entries = DESKTOP_ENTRIES
+    .lock()
+    // The cache is replaced at once, a panic while it is locked can't corrupt it
+    .unwrap_or_else(|poisoned| poisoned.into_inner())
+    .entries(&get_data_dirs(), &get_locales());
+  match_desktop_entry(&entries, class, exec_name)
+}
+
+/**
+ * Data directories by precedence, `$XDG_DATA_HOME` (`~/.local/share` when not set)
+ * then `$XDG_DATA_DIRS` (`/usr/local/share:/usr/share` when not set)
+ */
+fn get_data_dirs() -> Vec<PathBuf> {
+  let mut data_dirs = Vec::new();
+  match env::var_os("XDG_DATA_HOME").filter(|value| !value.is_empty()) {
+    Some(data_home) => data_dirs.push(PathBuf::from(data_home)),
+    None => {
+      if let Some(home) = env::var_os("HOME") {
+        data_dirs.push(Path::new(&home).join(".local/share"));
+      }
+    }
+  }
+  let system_dirs = env::var("XDG_DATA_DIRS")
+    .ok()
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+  data_dirs.extend(
+    system_dirs
+      .split(':')
+      .filter(|dir| !dir.is_empty())
+      .map(PathBuf::from),
+  );
+  data_dirs
+}
+
+/**
+ * Locales to look up localized keys by preference (e.g. `fr_FR@euro`, `fr_FR`, `fr@euro`, `fr` for `fr_FR.UTF-8@euro`),
+ * read from `LC_ALL`, `LC_MESSAGES` then `LANG`
+ */
+fn get_locales() -> Vec<String> {
+  ["LC_ALL", "LC_MESSAGES", "LANG"]
+    .iter()
+    .filter_map(|name| env::var(name).ok())
+    .find(|value| !value.is_empty())
+    .map(|value| parse_locales(&value))
+    .unwrap_or_default()
+}
+
+fn parse_locales(locale: &str) -> Vec<String> {
+  let (locale, modifier) = match locale.split_once('@') {
+    Some((locale, modifier)) => (locale, Some(modifier)),
+    None => (locale, None),
+  };
+  let locale = locale.split('.').next().unwrap_or_default();
+  if locale.is_empty() || locale == "C" || locale == "POSIX" {
+    return Vec::new();
+  }
+  let language = locale.split('_').next().unwrap_or_default();
+  let mut locales = Vec::new();
+  for name in [locale, language] {
+    if let Some(modifier) = modifier {
+      locales.push(format!("{}@{}", name, modifier));
+    }
+    locales.push(name.to_owned());
+  }
+  locales.dedup();
+  locales
+}
+
+/**
+ * Parse the desktop files of the `applications` directory of each data directory, entries of the first data
+ * directories hide those with the same id. The walked directories are added to `dirs`
+ */
+fn load_desktop_entries(
+  data_dirs: &[PathBuf],
+  locales: &[String],
+  dirs: &mut Vec<PathBuf>,
+) -> Vec<ParsedEntry> {
+  let mut entries: Vec<ParsedEntry> = Vec::new();
+  for data_dir in data_dirs {
+    let applications_dir = data_dir.join("applications");
+    let mut files = Vec::new();
+    list_desktop_files(&applications_dir, &applications_dir, 0, &mut files, dirs);
+    for (id, path) in files {
+      if entries.iter().any(|entry| entry.id.eq(&id)) {
+        continue;
+      }
+      // Hidden entries are kept to hide the entries of the next directories
+      let mut entry = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| parse_desktop_file(&content, locales))
+        .unwrap_or_default();
+      entry.id = id;
+      entries.push(entry);
+    }
+  }
+  entries
+}
+
+/**
+ * Find the entry by `StartupWMClass`, then by desktop file id (e.g. `firefox` or `org.gnome.Nautilus` for `Nautilus`),
+ * then by the program of `Exec`
+ */
+fn match_desktop_entry(entries: &[ParsedEntry], class: &str, exec_name: &str) -> DesktopEntry {
+  if class.is_empty() && exec_name.is_empty() {
+    return DesktopEntry::default();
+  }
+  let class_lowercase = class.to_lowercase();
+  let matchers: [&dyn Fn(&ParsedEntry) -> bool; 5] = [
+    &|entry| !class.is_empty() && entry.startup_wm_class.eq(class),
+    &|entry| !class.is_empty() && entry.startup_wm_class.to_lowercase().eq(&class_lowercase),
+    &|entry| !class.is_empty() && entry.id.to_lowercase().eq(&class_lowercase),
+    &|entry| {
+      !class.is_empty()
+        && entry.id.contains('.')
+        && entry
+          .id
+          .rsplit('.')
+          .next()
+          .is_some_and(|name| name.to_lowercase().eq(&class_lowercase))
+    },
+    &|entry| !exec_name.is_empty() && (entry.exec_name.eq(exec_name) || entry.id.eq(exec_name)),
+  ];
+  matchers
+    .iter()
+    .find_map(|matcher| {
+      entries
+        .iter()
+        .find(|entry| !entry.name.is_empty() && matcher(entry))
+    })
+    .map(|entry| DesktopEntry::new(entry.name.clone(), entry.icon.clone()))
+    .unwrap_or_default()
+}
+
+/**
+ * List the `.desktop` files of `dir` and its sub-directories with their desktop file id, sorted by path.
+ * The walked directories are added to `dirs`, including a missing `dir` which can be created later
+ */
+fn list_desktop_files(
+  root: &Path,
+  dir: &Path,
+  depth: usize,
+  files: &mut Vec<(String, PathBuf)>,
+  dirs: &mut Vec<PathBuf>,
+) {
+  dirs.push(dir.to_path_buf());
+  let Ok(read_dir) = fs::read_dir(dir) else {
+    return;
+  };
+  let mut paths: Vec<PathBuf> = read_dir
+    .filter_map(|dir_entry| dir_entry.ok().map(|dir_entry| dir_entry.path()))
+    .collect();
+  paths.sort();
+  for path in paths {
+    if path.is_dir() {
+      if depth < APPLICATIONS_MAX_DEPTH {
+        list_desktop_files(root, &path, depth + 1, files, dirs);
+      }
+    } else if path
+      .extension()
+      .is_some_and(|extension| extension == "desktop")
+    {
+      if let Some(id) = path.strip_prefix(root).ok().and_then(|relative| {
+        relative
+          .with_extension("")
+          .to_str()
+          .map(|id| id.replace('/', "-"))
+      }) {
+        files.push((id, path));
+      }
+    }
+  }
+}
+
+/**
+ * Parse the `[Desktop Entry]` group of a desktop file, `Name` is localized with the first matching locale.
+ * Return `None` for hidden entries and entries which are not applications
+ */
+fn parse_desktop_file(content: &str, locales: &[String]) -> Option<ParsedEntry> {
+  let mut entry = ParsedEntry::default();
+  // Index in `locales` of the locale of `name`, `locales.len()` for the unlocalized name
+  let mut name_rank = usize::MAX;
+  let mut in_desktop_entry = false;
+  for line in content.lines().map(str::trim) {
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    if line.starts_with('[') {
+      in_desktop_entry = line == "[Desktop Entry]";
+      continue;
+    }
+    if !in_desktop_entry {
+      continue;
+    }
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+    let (key, value) = (key.trim(), unescape_value(value.trim()));
+    match key {
+      "Type" if value != "Application" => return None,
+      "Hidden" if value == "true" => return None,
+      "StartupWMClass" => entry.startup_wm_class = value,
+      "Exec" => entry.exec_name = parse_exec_name(&value),
+      "Icon" => entry.icon = value,
+      "Name" if name_rank > locales.len() => {
+        entry.name = value;
+        name_rank = locales.len();
+      }
+      _ => {
+        if let Some(locale) = key
+          .strip_prefix("Name[")
+          .and_then(|key| key.strip_suffix(']'))
+        {
+          if let Some(rank) = locales.iter().position(|name| name == locale) {
+            if rank < name_rank {
+              entry.name = value;
+              name_rank = rank;
+            }
+          }
+        }
+      }
+    }
+  }
+  Some(entry)
+}
+
+/**
+ * Replace the escape sequences of string values (`\s`, `\n`, `\t`, `\r` and `\\`)
+ */
+fn unescape_value(value: &str) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut chars = value.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      result.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('s') => result.push(' '),
+      Some('n') => result.push('\n'),
+      Some('t') => result.push('\t'),
+      Some('r') => result.push('\r'),
+      Some(other) => result.push(other),
+      None => result.push('\\'),
+    }
+  }
+  result
+}
+
+/**
+ * File name of the program started by an `Exec` value, `env` and its variable assignments are skipped
+ * (e.g. `code` for `env GDK_BACKEND=x11 /usr/bin/code %F`)
+ */
+fn parse_exec_name(exec: &str) -> String {
+  exec
+    .split_whitespace()
+    .map(|arg| arg.trim_matches('"'))
+    .find(|arg| *arg != "env" && !arg.contains('='))
+    .and_then(|program| Path::new(program).file_name())
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn write_desktop_file(data_dir: &Path, relative_path: &str, content: &str) {
+    let path = data_dir.join("applications").join(relative_path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+  }
+
+  #[test]
+  fn test_parse_desktop_file() {
+    let content = "[Desktop Entry]\nType=Application\nName=Files\nName[fr]=Fichiers\nName[fr_FR]=Fichiers FR\nIcon=org.gnome.Nautilus\nExec=\"/usr/bin/nautilus\" --new-window %U\nStartupWMClass=Nautilus\n\n[Desktop Action new-window]\nName=New Window\n";
+    let entry = parse_desktop_file(content, &[]).unwrap();
+    assert_eq!(entry.name, "Files");
+    assert_eq!(entry.icon, "org.gnome.Nautilus");
+    assert_eq!(entry.exec_name, "nautilus");
+    assert_eq!(entry.startup_wm_class, "Nautilus");
+    let locales = parse_locales("fr_FR.UTF-8");
+    assert_eq!(locales, vec!["fr_FR", "fr"]);
+    assert_eq!(
+      parse_desktop_file(content, &locales).unwrap().name,
+      "Fichiers FR"
+    );
+    assert_eq!(
+      parse_desktop_file(content, &parse_locales("fr_BE"))
+        .unwrap()
+        .name,
+      "Fichiers"
+    );
+    assert!(parse_desktop_file("[Desktop Entry]\nType=Link\nName=Link", &[]).is_none());
+    assert!(parse_desktop_file("[Desktop Entry]\nName=Hidden\nHidden=true", &[]).is_none());
+  }
+
+  #[test]
+  fn test_parse_locales() {
+    assert_eq!(
+      parse_locales("sr_RS.UTF-8@latin"),
+      vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]
+    );
+    assert_eq!(parse_locales("de"), vec!["de"]);
+    assert!(parse_locales("C.UTF-8").is_empty());
+    assert!(parse_locales("POSIX").is_empty());
+  }
+
+  #[test]
+  fn test_parse_exec_name() {
+    assert_eq!(
+      parse_exec_name("env GDK_BACKEND=x11 /usr/bin/code %F"),
+      "code"
+    );
+    assert_eq!(parse_exec_name("firefox %u"), "firefox");
+    assert_eq!(parse_exec_name(""), "");
+    assert_eq!(unescape_value("a\\sb\\\\c"), "a b\\c");
+  }
+
+  #[test]
+  fn test_find_desktop_entry() {
+    let root = env::temp_dir().join(format!("x-win-desktop-entry-{}", std::process::id()));
+    let (home, system) = (root.join("home"), root.join("system"));
+    write_desktop_file(
+      &system,
+      "code.desktop",
+      "[Desktop Entry]\nName=Visual Studio Code\nIcon=vscode\nExec=/usr/share/code/code %F\nStartupWMClass=Code",
+    );
+    write_desktop_file(
+      &system,
+      "org.gnome.Nautilus.desktop",
+      "[Desktop Entry]\nName=Files\nIcon=org.gnome.Nautilus\nExec=nautilus",
+    );
+    write_desktop_file(
+      &system,
+      "kde/konsole.desktop",
+      "[Desktop Entry]\nName=Konsole\nIcon=utilities-terminal\nExec=konsole",
+    );
+    write_desktop_file(
+      &system,
+      "firefox.desktop",
+      "[Desktop Entry]\nName=Firefox\nIcon=firefox\nExec=firefox %u",
+    );
+    // Entry of the user hiding the system one
+    write_desktop_file(
+      &home,
+      "firefox.desktop",
+      "[Desktop Entry]\nName=Firefox\nHidden=true",
+    );
+    let data_dirs = vec![home, system];
+
+    let entries = load_desktop_entries(&data_dirs, &[], &mut Vec::new());
+    let find = |class: &str, exec_name: &str| match_desktop_entry(&entries, class, exec_name);
+    assert_eq!(
+      find("Code", "code"),
+      DesktopEntry::new("Visual Studio Code".to_owned(), "vscode".to_owned())
+    );
+    assert_eq!(find("code", "").display_name, "Visual Studio Code");
+    assert_eq!(find("org.gnome.Nautilus", "").display_name, "Files");
+    assert_eq!(find("Nautilus", "").icon_name, "org.gnome.Nautilus");
+    assert_eq!(find("", "konsole").display_name, "Konsole");
+    assert_eq!(find("kde-konsole", "").display_name, "Konsole");
+    assert_eq!(find("firefox", "firefox"), DesktopEntry::default());
+    assert_eq!(find("unknown", "unknown"), DesktopEntry::default());
+    assert_eq!(find("", ""), DesktopEntry::default());
+    fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn test_desktop_entries_cache() {
+    let root = env::temp_dir().join(format!("x-win-desktop-cache-{}", std::process::id()));
+    let data_dirs = vec![root.clone()];
+    write_desktop_file(
+      &root,
+      "code.desktop",
+      "[Desktop Entry]\nName=Visual Studio Code\nExec=code",
+    );
+    let mut cache = DesktopEntriesCache::default();
+    let entries = cache.entries(&data_dirs, &[]);
+    assert_eq!(entries.len(), 1);
+    // Unchanged directories are not parsed again
+    assert!(Arc::ptr_eq(&entries, &cache.entries(&data_dirs, &[])));
+    assert!(!Arc::ptr_eq(
+      &entries,
+      &cache.entries(&data_dirs, &["fr".to_owned()])
+    ));
+    // Installed in a new sub-directory, the mtime of `applications` changes
+    write_desktop_file(
+      &root,
+      "kde/konsole.desktop",
+      "[Desktop Entry]\nName=Konsole\nExec=konsole",
+    );
+    let entries = cache.entries(&data_dirs, &["fr".to_owned()]);
+    assert_eq!(
+      match_desktop_entry(&entries, "", "konsole").display_name,
+      "Konsole"
+    );
+    fs::remove_file(root.join("applications/code.desktop")).unwrap();
+    let entries = cache.entries(&data_dirs, &["fr".to_owned()]);
+    assert_eq!(
+      match_desktop_entry(&entries, "", "code"),
+      DesktopEntry::default()
+    );
+    fs::remove_dir_all(root).unwrap();
+  }
+}
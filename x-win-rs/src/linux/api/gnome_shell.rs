@@ -2,12 +2,22 @@ use std::sync::Mutex;
 
 use once_cell::sync::Lazy;
 
-use crate::common::x_win_struct::{
-  icon_info::IconInfo, process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
-  window_position::WindowPosition,
+use std::convert::TryFrom;
+
+use zbus::{zvariant::OwnedValue, Connection};
+
+use crate::{
+  common::x_win_struct::{
+    icon_info::IconInfo, process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
+    window_position::WindowPosition,
+  },
+  XWinError,
 };
 
-use super::common_api::get_gnome_version;
+use super::common_api::{
+  get_gnome_version, get_installed_gnome_version, get_process_command_line, get_process_start_time,
+  get_process_user,
+};
 
 pub const GNOME_XWIN_UUID: &str = r#"x-win@miniben90.org"#;
 
@@ -380,6 +390,66 @@ export default class XWinWaylandExtension extends Extension {
 }
 "#;
 
+/**
+ * Open a connection to the session bus used to reach gnome shell
+ */
+pub fn session_connection() -> Result<Connection, XWinError> {
+  Connection::new_session().map_err(|_| XWinError::NoDisplay)
+}
+
+/**
+ * Recover version of the running gnome shell from D-Bus and fallback to `gnome-shell --version`
+ */
+pub fn get_shell_version() -> Option<String> {
+  session_connection()
+    .ok()
+    .and_then(|connection| {
+      connection
+        .call_method(
+          Some("org.gnome.Shell"),
+          "/org/gnome/Shell",
+          Some("org.freedesktop.DBus.Properties"),
+          "Get",
+          &("org.gnome.Shell", "ShellVersion"),
+        )
+        .ok()
+    })
+    .and_then(|response| response.body::<OwnedValue>().ok())
+    .and_then(|value| String::try_from(value).ok())
+    .or_else(get_installed_gnome_version)
+}
+
+/**
+ * Major version of a gnome shell version (e.g. `46` for `46.2`)
+ */
+pub fn parse_gnome_major_version(version: &str) -> Option<u32> {
+  version.split('.').next()?.trim().parse().ok()
+}
+
+/**
+ * Gnome shell major versions listed in `shell-version` of the extension metadata
+ */
+pub fn get_extension_shell_versions() -> Vec<u32> {
+  serde_json::from_str::<serde_json::Value>(GNOME_XWIN_EXTENSION_META)
+    .ok()
+    .and_then(|metadata| {
+      metadata["shell-version"].as_array().map(|versions| {
+        versions
+          .iter()
+          .filter_map(|version| version.as_str().and_then(parse_gnome_major_version))
+          .collect()
+      })
+    })
+    .unwrap_or_default()
+}
+
+/**
+ * Parse JSON response returned by gnome shell scripts
+ */
+pub fn parse_response(response: &str) -> Result<serde_json::Value, XWinError> {
+  serde_json::from_str(response).map_err(|error| XWinError::PlatformError(error.to_string()))
+}
+
 pub fn number_to_u32(value: &serde_json::Value) -> u32 {
   if value.is_number() {
     return value.as_u64().unwrap() as u32;
@@ -424,16 +494,43 @@ pub fn value_to_window_info(response: &serde_json::Value) -> WindowInfo {
       name: info["name"].as_str().unwrap().to_string(),
       path: info["path"].as_str().unwrap().to_string(),
       process_id: number_to_u32(&info["process_id"]),
+      command_line: get_process_command_line(number_to_u32(&info["process_id"])),
+      user: get_process_user(number_to_u32(&info["process_id"])),
+      start_time: get_process_start_time(number_to_u32(&info["process_id"])),
+      bundle_id: "".to_owned(),
+      app_name: "".to_owned(),
+      instance_name: "".to_owned(),
     },
     usage: UsageInfo {
       memory: number_to_u32(&usage["memory"]),
+      cpu: 0.0,
     },
     url: "".to_owned(),
+    on_external_display: None,
+    z_order: 0,
+    workspace: None,
+    content_language: None,
+    monitor: None,
+    is_focused: false,
+    is_active: false,
+    opacity: 1.0,
+    parent_id: None,
+    is_minimized: false,
+    // Gnome shell scripts don't expose the map state
+    is_visible: true,
+    role: "".to_owned(),
+    focused_element: None,
+    // Gnome shell scripts only expose the actor geometry
+    frame: None,
+    // Gnome shell scripts don't expose the stacking layer
+    always_on_top: false,
+    always_on_bottom: false,
+    // Meta windows have no native handle reachable by clients
+    native_handle: 0,
   }
 }
 
 pub struct GnomeVersion {
-  pub version: u32,
   pub use_eval: bool,
 }
 
@@ -444,9 +541,27 @@ impl GnomeVersion {
       .parse()
       .unwrap_or(999);
     let use_eval = version < 41;
-    Self { use_eval, version }
+    Self { use_eval }
   }
 }
 
 pub static GNOME_SINGLETON: Lazy<Mutex<GnomeVersion>> =
   Lazy::new(|| Mutex::new(GnomeVersion::new()));
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_gnome_major_version() {
+    assert_eq!(parse_gnome_major_version("46.2"), Some(46));
+    assert_eq!(parse_gnome_major_version("3.38.4"), Some(3));
+    assert_eq!(parse_gnome_major_version("45"), Some(45));
+    assert_eq!(parse_gnome_major_version(""), None);
+  }
+
+  #[test]
+  fn test_get_extension_shell_versions() {
+    assert_eq!(get_extension_shell_versions(), vec![42, 43, 44, 45, 46]);
+  }
+}
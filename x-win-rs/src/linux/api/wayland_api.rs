@@ -8,16 +8,21 @@ use std::{
 use crate::{
   common::{
     api::Api,
-    x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+    x_win_struct::{
+      active_window_options::ActiveWindowOptions, extension_install_info::ExtensionInstallInfo,
+      extension_install_options::ExtensionInstallOptions, icon_info::IconInfo,
+      monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions, window_info::WindowInfo,
+    },
   },
   linux::api::{
     common_api::{get_window_memory_usage, get_window_path_name},
     gnome_shell::GNOME_XWIN_EXTENSION_FOLDER_PATH,
   },
+  XWinError,
 };
 
 use super::{
-  common_api::{get_gnome_version, init_entity},
+  common_api::get_gnome_version,
   gnome_shell::{self, GNOME_SINGLETON},
   wayland_eval_api, wayland_extension_api, APIGnome,
 };
@@ -38,20 +43,34 @@ pub struct WaylandApi {}
  * Impl. for Linux system
  */
 impl Api for WaylandApi {
-  fn get_active_window(&self) -> WindowInfo {
-    if gnome_use_eval() {
+  fn get_active_window(&self, _options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError> {
+    let mut window_info = if gnome_use_eval() {
       wayland_eval_api::get_active_window()
     } else {
       wayland_extension_api::get_active_window()
-    }
+    }?;
+    window_info.is_focused = window_info.id.ne(&0);
+    window_info.is_active = window_info.is_focused;
+    Ok(window_info)
   }
 
-  fn get_open_windows(&self) -> Vec<WindowInfo> {
-    if gnome_use_eval() {
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut windows = if gnome_use_eval() {
       wayland_eval_api::get_open_windows()
     } else {
       wayland_extension_api::get_open_windows()
+    }?;
+    // Gnome shell scripts don't expose the focus, it is recovered from the active window
+    let active_window = self.get_active_window(&ActiveWindowOptions::default())?;
+    for window_info in windows.iter_mut() {
+      window_info.is_focused = active_window.is_focused && window_info.id.eq(&active_window.id);
+      window_info.is_active = window_info.is_focused;
+      // Memory is read by gnome shell scripts, it is only dropped here
+      if !options.include_usage {
+        window_info.usage.memory = 0;
+      }
     }
+    Ok(windows)
   }
 
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo {
@@ -61,15 +80,18 @@ impl Api for WaylandApi {
       wayland_extension_api::get_icon(window_info)
     }
   }
+
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError> {
+    // Monitors layout is not exposed by the gnome shell scripts
+    Ok(Vec::new())
+  }
 }
 
 impl APIGnome for WaylandApi {
-  fn install_extension() -> bool {
-    if !gnome_use_eval() {
-      wayland_extension_api::install_extension()
-    } else {
-      false
-    }
+  fn install_extension(
+    options: &ExtensionInstallOptions,
+  ) -> Result<ExtensionInstallInfo, XWinError> {
+    wayland_extension_api::install_extension(options)
   }
 
   fn uninstall_extension() -> bool {
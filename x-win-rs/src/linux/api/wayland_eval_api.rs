@@ -1,16 +1,18 @@
-use zbus::Connection;
-
 use crate::{
-  common::x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+  common::{
+    api::empty_entity,
+    x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+  },
   linux::api::gnome_shell::GNOME_XWIN_EVAL_SCRIPT,
+  XWinError,
 };
 
-use super::{
-  common_api::init_entity,
-  gnome_shell::{value_to_icon_info, value_to_window_info, GNOME_XWIN_GET_ICON_SCRIPT},
+use super::gnome_shell::{
+  parse_response, session_connection, value_to_icon_info, value_to_window_info,
+  GNOME_XWIN_GET_ICON_SCRIPT,
 };
 
-pub fn get_active_window() -> WindowInfo {
+pub fn get_active_window() -> Result<WindowInfo, XWinError> {
   let script = format!(
     r#"
 {}
@@ -19,19 +21,19 @@ get_active_window();
     GNOME_XWIN_EVAL_SCRIPT
   );
 
-  let response = call_script(&script);
+  let response = call_script(&script)?;
 
-  if response.ne(&"") {
-    let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
+  if !response.is_empty() {
+    let response: serde_json::Value = parse_response(&response)?;
     if response.is_object() {
-      return value_to_window_info(&response);
+      return Ok(value_to_window_info(&response));
     }
   }
 
-  init_entity()
+  Ok(empty_entity())
 }
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
+pub fn get_open_windows() -> Result<Vec<WindowInfo>, XWinError> {
   let script = format!(
     r#"
 {}
@@ -41,25 +43,27 @@ get_open_windows();
     GNOME_XWIN_EVAL_SCRIPT
   );
 
-  let response = call_script(&script);
+  let response = call_script(&script)?;
   if !response.is_empty() {
-    let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
+    let response: serde_json::Value = parse_response(&response)?;
 
     if response.is_array() {
-      return response
-        .as_array()
-        .unwrap()
-        .iter()
-        .map(value_to_window_info)
-        .collect();
+      return Ok(
+        response
+          .as_array()
+          .unwrap()
+          .iter()
+          .map(value_to_window_info)
+          .collect(),
+      );
     }
   }
 
-  vec![]
+  Ok(vec![])
 }
 
-fn call_script(script: &String) -> String {
-  let connection = Connection::new_session().unwrap();
+fn call_script(script: &String) -> Result<String, XWinError> {
+  let connection = session_connection()?;
 
   let response = connection
     .call_method(
@@ -69,12 +73,12 @@ fn call_script(script: &String) -> String {
       "Eval",
       script,
     )
-    .unwrap();
+    .map_err(|error| XWinError::PlatformError(error.to_string()))?;
 
   if let Ok((_actor, json)) = response.body::<(bool, String)>() {
-    return json;
+    return Ok(json);
   }
-  "".to_owned()
+  Ok("".to_owned())
 }
 
 pub fn get_icon(window_info: &WindowInfo) -> IconInfo {
@@ -88,7 +92,7 @@ get_icon({});
       GNOME_XWIN_EVAL_SCRIPT, GNOME_XWIN_GET_ICON_SCRIPT, window_info.id
     );
 
-    let response = call_script(&script);
+    let response = call_script(&script).unwrap_or_default();
 
     if !response.is_empty() {
       let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
@@ -1,56 +1,66 @@
 use zbus::Connection;
 
-use std::{env, fs, io, ops::Deref, path};
+use std::{env, fs, io, path};
 
 use crate::{
-  common::x_win_struct::{icon_info::IconInfo, window_info::WindowInfo},
+  common::{
+    api::empty_entity,
+    x_win_struct::{
+      extension_install_info::ExtensionInstallInfo,
+      extension_install_options::ExtensionInstallOptions, icon_info::IconInfo,
+      window_info::WindowInfo,
+    },
+  },
   linux::api::gnome_shell::{
-    value_to_window_info, GNOME45_XWIN_EXTENSION_SCRIPT, GNOME_SINGLETON,
-    GNOME_XWIN_EXTENSION_COMMON_SCRIPT, GNOME_XWIN_EXTENSION_FOLDER_PATH,
-    GNOME_XWIN_EXTENSION_META, GNOME_XWIN_EXTENSION_SCRIPT, GNOME_XWIN_UUID,
+    value_to_window_info, GNOME45_XWIN_EXTENSION_SCRIPT, GNOME_XWIN_EXTENSION_COMMON_SCRIPT,
+    GNOME_XWIN_EXTENSION_FOLDER_PATH, GNOME_XWIN_EXTENSION_META, GNOME_XWIN_EXTENSION_SCRIPT,
+    GNOME_XWIN_UUID,
   },
+  XWinError,
 };
 
-use super::{
-  common_api::init_entity,
-  gnome_shell::{value_to_icon_info, GNOME_XWIN_GET_ICON_SCRIPT},
+use super::gnome_shell::{
+  get_extension_shell_versions, get_shell_version, parse_gnome_major_version, parse_response,
+  session_connection, value_to_icon_info, GNOME_XWIN_GET_ICON_SCRIPT,
 };
 
-pub fn get_active_window() -> WindowInfo {
-  let response = call_script("get_active_window");
+pub fn get_active_window() -> Result<WindowInfo, XWinError> {
+  let response = call_script("get_active_window")?;
 
-  if response.ne(&"") {
-    let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
+  if !response.is_empty() {
+    let response: serde_json::Value = parse_response(&response)?;
     if response.is_object() {
-      return value_to_window_info(&response);
+      return Ok(value_to_window_info(&response));
     }
   }
 
-  init_entity()
+  Ok(empty_entity())
 }
 
-pub fn get_open_windows() -> Vec<WindowInfo> {
-  let response = call_script("get_open_windows");
-  if response.ne(&"") {
-    let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
+pub fn get_open_windows() -> Result<Vec<WindowInfo>, XWinError> {
+  let response = call_script("get_open_windows")?;
+  if !response.is_empty() {
+    let response: serde_json::Value = parse_response(&response)?;
 
     if response.is_array() {
-      return response
-        .as_array()
-        .unwrap()
-        .iter()
-        .map(value_to_window_info)
-        .collect();
+      return Ok(
+        response
+          .as_array()
+          .unwrap()
+          .iter()
+          .map(value_to_window_info)
+          .collect(),
+      );
     }
   }
 
-  vec![]
+  Ok(vec![])
 }
 
 pub fn get_icon(window_info: &WindowInfo) -> IconInfo {
   if window_info.id.ne(&0) {
-    let response = call_script_arg("get_icon", window_info.id);
-    if response.ne(&"") {
+    let response = call_script_arg("get_icon", window_info.id).unwrap_or_default();
+    if !response.is_empty() {
       let response: serde_json::Value = serde_json::from_str(response.as_str()).unwrap();
       if response.is_object() {
         return value_to_icon_info(&response);
@@ -64,59 +74,130 @@ pub fn get_icon(window_info: &WindowInfo) -> IconInfo {
   }
 }
 
-pub fn install_extension() -> bool {
-  if std::fs::metadata(get_extension_path()).is_ok() {
-    if std::fs::metadata(get_extension_file_path()).is_ok() {
-      remove_extension_file().unwrap();
-    }
-    if std::fs::metadata(get_medata_file_path()).is_ok() {
-      remove_metadata_file().unwrap();
-    }
-  } else if let Err(create_folder_err) = std::fs::create_dir_all(get_extension_path()) {
-    panic!(
-      "Not possible to create extension folder to \"{}\"!\nRaison: {}",
-      get_extension_path().to_string_lossy(),
-      create_folder_err
-    );
+/**
+ * Write extension files, files already matching the extension content are kept untouched.
+ * Nothing is written with `dry_run`, the files are only compared with the extension content,
+ * the extension is reported as not applicable when the Gnome shell is not running.
+ */
+pub fn install_extension(
+  options: &ExtensionInstallOptions,
+) -> Result<ExtensionInstallInfo, XWinError> {
+  if options.dry_run && get_shell_version().is_none() {
+    // Other Wayland compositors can't load the extension, like X11 sessions it's never installed
+    return Ok(ExtensionInstallInfo::new(
+      false,
+      get_extension_path().to_string_lossy().to_string(),
+      false,
+      false,
+      get_extension_file_names(),
+    ));
+  }
+  let version = get_supported_shell_version()?;
+  let extension_path = get_extension_path();
+  let script = get_extension_script(version);
+
+  if options.dry_run {
+    let needs_reload = !is_file_content(&get_extension_file_path(), &script)
+      || !is_file_content(&get_medata_file_path(), GNOME_XWIN_EXTENSION_META);
+    return Ok(ExtensionInstallInfo::new(
+      get_extension_files().iter().all(|file| file.exists()),
+      extension_path.to_string_lossy().to_string(),
+      needs_reload,
+      true,
+      get_extension_file_names(),
+    ));
   }
 
-  let script: String = {
-    let gnome_singleton = GNOME_SINGLETON.lock().unwrap();
-    let version: u32 = gnome_singleton.version;
-    let _ = gnome_singleton.deref();
-    let script: String = match version {
-      x if x.lt(&45) => GNOME_XWIN_EXTENSION_SCRIPT.to_string(),
-      _ => GNOME45_XWIN_EXTENSION_SCRIPT.to_string(),
-    };
+  fs::create_dir_all(&extension_path).map_err(|error| io_to_xwin_error(&extension_path, error))?;
+
+  let script_written = write_if_changed(&get_extension_file_path(), &script)?;
+  let metadata_written = write_if_changed(&get_medata_file_path(), GNOME_XWIN_EXTENSION_META)
+    .inspect_err(|_| {
+      if script_written {
+        let _ = remove_extension_file();
+      }
+    })?;
+
+  Ok(ExtensionInstallInfo::new(
+    true,
+    extension_path.to_string_lossy().to_string(),
+    script_written || metadata_written,
+    true,
+    get_extension_file_names(),
+  ))
+}
+
+/**
+ * Build the extension script loadable by the major version of the gnome shell
+ */
+fn get_extension_script(version: u32) -> String {
+  let script: String = match version {
+    x if x.lt(&45) => GNOME_XWIN_EXTENSION_SCRIPT.to_string(),
+    _ => GNOME45_XWIN_EXTENSION_SCRIPT.to_string(),
+  };
 
-    let script: String = format!(
-      r#"{}
+  format!(
+    r#"{}
 
 {}
 
 {}
 "#,
-      script, GNOME_XWIN_EXTENSION_COMMON_SCRIPT, GNOME_XWIN_GET_ICON_SCRIPT
-    );
+    script, GNOME_XWIN_EXTENSION_COMMON_SCRIPT, GNOME_XWIN_GET_ICON_SCRIPT
+  )
+}
 
-    script
-  };
+/**
+ * Recover major version of the running gnome shell, an error describing the detected version is returned
+ * when the extension can't be loaded by it
+ */
+fn get_supported_shell_version() -> Result<u32, XWinError> {
+  let version = get_shell_version().ok_or_else(|| {
+    XWinError::PlatformError(
+      "Gnome shell is not running, the extension can't be installed".to_owned(),
+    )
+  })?;
+  let supported_versions = get_extension_shell_versions();
+  match parse_gnome_major_version(&version) {
+    Some(major) if supported_versions.contains(&major) => Ok(major),
+    _ => Err(XWinError::PlatformError(format!(
+      "Gnome shell {} is not supported by the extension (supported versions: {})",
+      version,
+      supported_versions
+        .iter()
+        .map(|version| version.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+    ))),
+  }
+}
 
-  if fs::write(get_extension_file_path(), script).is_ok() {
-    if fs::write(get_medata_file_path(), GNOME_XWIN_EXTENSION_META).is_ok() {
-      true
-    } else {
-      remove_extension_file().unwrap();
-      panic!(
-        "Not possible to write \"{}\" file!",
-        get_medata_file_path().to_string_lossy()
-      );
-    }
-  } else {
-    panic!(
-      "Not possible to write \"{}\" file!",
-      get_extension_file_path().to_string_lossy()
-    );
+/**
+ * Write `content` to `path` when the file is missing or different, return `true` when the file has been written
+ */
+fn write_if_changed(path: &path::Path, content: &str) -> Result<bool, XWinError> {
+  if is_file_content(path, content) {
+    return Ok(false);
+  }
+  fs::write(path, content).map_err(|error| io_to_xwin_error(path, error))?;
+  Ok(true)
+}
+
+/**
+ * To know if the file at `path` exists with exactly `content`
+ */
+fn is_file_content(path: &path::Path, content: &str) -> bool {
+  fs::read_to_string(path).is_ok_and(|current| current.eq(content))
+}
+
+fn io_to_xwin_error(path: &path::Path, error: io::Error) -> XWinError {
+  match error.kind() {
+    io::ErrorKind::PermissionDenied => XWinError::PermissionDenied,
+    _ => XWinError::PlatformError(format!(
+      "Not possible to write \"{}\": {}",
+      path.to_string_lossy(),
+      error
+    )),
   }
 }
 
@@ -163,7 +244,7 @@ pub fn disable_extension() -> bool {
   false
 }
 
-fn get_extension_path() -> path::PathBuf {
+pub fn get_extension_path() -> path::PathBuf {
   let home_dir: String = env::var_os("HOME")
     .unwrap()
     .clone()
@@ -185,16 +266,29 @@ fn get_medata_file_path() -> path::PathBuf {
   get_extension_path().join("metadata.json")
 }
 
-fn remove_metadata_file() -> Result<(), io::Error> {
-  fs::remove_file(get_medata_file_path())
+/**
+ * Paths of the files written by the installation
+ */
+fn get_extension_files() -> Vec<path::PathBuf> {
+  vec![get_extension_file_path(), get_medata_file_path()]
+}
+
+/**
+ * Paths of the files written by the installation as strings
+ */
+pub fn get_extension_file_names() -> Vec<String> {
+  get_extension_files()
+    .iter()
+    .map(|file| file.to_string_lossy().to_string())
+    .collect()
 }
 
 fn remove_extension_file() -> Result<(), std::io::Error> {
   fs::remove_file(get_extension_file_path())
 }
 
-fn call_script(method_name: &str) -> String {
-  let connection = Connection::new_session().unwrap();
+fn call_script(method_name: &str) -> Result<String, XWinError> {
+  let connection = session_connection()?;
 
   let response = connection
     .call_method(
@@ -204,16 +298,16 @@ fn call_script(method_name: &str) -> String {
       method_name,
       &(),
     )
-    .unwrap();
+    .map_err(|error| XWinError::PlatformError(error.to_string()))?;
 
   if let Ok(json) = response.body::<String>() {
-    return json;
+    return Ok(json);
   }
-  "".to_owned()
+  Ok("".to_owned())
 }
 
-fn call_script_arg(method_name: &str, body: u32) -> String {
-  let connection = Connection::new_session().unwrap();
+fn call_script_arg(method_name: &str, body: u32) -> Result<String, XWinError> {
+  let connection = session_connection()?;
 
   let response = connection
     .call_method(
@@ -223,10 +317,10 @@ fn call_script_arg(method_name: &str, body: u32) -> String {
       method_name,
       &(body as f64),
     )
-    .unwrap();
+    .map_err(|error| XWinError::PlatformError(error.to_string()))?;
 
   if let Ok(json) = response.body::<String>() {
-    return json;
+    return Ok(json);
   }
-  "".to_owned()
+  Ok("".to_owned())
 }
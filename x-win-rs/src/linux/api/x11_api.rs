@@ -3,115 +3,516 @@
 use base64::Engine;
 use image::ImageFormat;
 
-use xcb::{x, Connection, Xid, XidNew};
+use std::time::{Duration, Instant};
+
+use xcb::{randr, x, Xid, XidNew};
 
 use crate::{
   common::{
-    api::Api,
-    x_win_struct::{icon_info::IconInfo, window_info::WindowInfo, window_position::WindowPosition},
+    api::{empty_entity, get_frontmost_window_at, get_window_monitor, sort_front_to_back, Api},
+    capture::to_raw_image,
+    change_notifier::ChangeSource,
+    x_win_struct::{
+      active_window_options::ActiveWindowOptions, icon_info::IconInfo, monitor_info::MonitorInfo,
+      open_windows_options::OpenWindowsOptions, raw_image::RawImage, window_info::WindowInfo,
+      window_position::WindowPosition, window_property::WindowProperty, workspace::Workspace,
+      workspace_info::WorkspaceInfo,
+    },
+  },
+  linux::api::{
+    common_api::{
+      get_process_command_line, get_process_start_time, get_process_user, get_window_memory_usage,
+      get_window_path_name,
+    },
+    x11_connection::X11Connection,
   },
-  linux::api::common_api::{get_window_memory_usage, get_window_path_name},
+  XWinError,
 };
 
-use super::common_api::init_entity;
+/**
+ * Window types kept by default in open windows, dialogs and tool palettes are kept with normal windows
+ * while desktop, dock, splash and menu windows are excluded
+ */
+const DEFAULT_WINDOW_TYPES: [&str; 4] = ["normal", "dialog", "utility", "toolbar"];
+
+/**
+ * IconicState of WM_STATE
+ */
+const ICONIC_STATE: u32 = 3;
+
+/**
+ * Number of 32 bits units requested at once when reading a property
+ */
+const PROPERTY_CHUNK_LENGTH: u32 = 1 << 16;
+
+/**
+ * Depth limit of the QueryTree search, client windows are children of the WM frames on reparenting WMs
+ */
+const FIND_CLIENT_WINDOWS_MAX_DEPTH: u32 = 3;
+
+/**
+ * Depth limit of the ancestors of the focused window, toolkits focus the client window or one of its children
+ */
+const FOCUS_CHAIN_MAX_DEPTH: usize = 8;
+
+/**
+ * Informations of the screen shared by all windows
+ */
+struct ScreenContext {
+  root_window: x::Window,
+  monitors: Vec<MonitorInfo>,
+  desktop_names: Vec<String>,
+  current_desktop: Option<u32>,
+}
 
 /**
  * Struct to use similar as API to get active window and open windows for XOrg desktop
  */
-pub struct X11Api {}
+#[derive(Default)]
+pub struct X11Api {
+  display: Option<String>,
+}
+
+impl X11Api {
+  /**
+   * Create the API for the X display named `display`, `$DISPLAY` is used when not set
+   */
+  pub fn new(display: Option<String>) -> Self {
+    Self { display }
+  }
+
+  /**
+   * Get the name of the running window manager, `""` when it can't be recovered
+   */
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, ret)
+  )]
+  pub fn get_window_manager_name(&self) -> String {
+    let Ok(conn) = connection(self.display.as_deref()) else {
+      return "".to_owned();
+    };
+    get_root_window(&conn)
+      .ok()
+      .and_then(|root_window| get_supporting_wm_check(&conn, root_window))
+      .map(|wm_window| {
+        let net_wm_name_atom = get_net_wm_name_atom(&conn);
+        let utf8_string_atom = get_utf8_string_atom(&conn);
+        if net_wm_name_atom == x::ATOM_NONE || utf8_string_atom == x::ATOM_NONE {
+          return "".to_owned();
+        }
+        _get_string_response(&conn, wm_window, net_wm_name_atom, utf8_string_atom)
+      })
+      .unwrap_or_default()
+  }
+}
 
 /**
  * Impl. for windows system
  */
 impl Api for X11Api {
-  fn get_active_window(&self) -> WindowInfo {
-    let conn = connection();
-    let setup = conn.get_setup();
+  #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+  fn get_active_window(&self, options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError> {
+    let conn = connection_with_timeout(self.display.as_deref(), options.timeout_ms)?;
+    let root_window = get_focused_root_window(&conn)?;
 
-    let mut result: WindowInfo = init_entity();
+    let mut result: WindowInfo = empty_entity();
 
-    let root_window = setup.roots().next();
-    if root_window.is_some() {
-      let root_window = root_window.unwrap().root();
-      let active_window_atom = get_active_window_atom(&conn);
-      if active_window_atom != x::ATOM_NONE {
-        let active_window = conn.send_request(&x::GetProperty {
-          delete: false,
-          window: root_window,
-          property: active_window_atom,
-          r#type: x::ATOM_WINDOW,
-          long_offset: 0,
-          long_length: 1,
-        });
-        if let Ok(active_window) = conn.wait_for_reply(active_window) {
-          let active_window: Option<&x::Window> = active_window.value::<x::Window>().first();
-          if active_window.is_some() {
-            let active_window: &x::Window = active_window.unwrap();
-            result = get_window_information(&conn, active_window);
-          }
-        }
+    if let Some(active_window) = get_active_window_id(&conn, root_window) {
+      let screen = get_screen_context(&conn, root_window);
+      result = get_window_information(&conn, &active_window, &screen, true);
+      result.is_active = result.id.ne(&0);
+      result.is_focused = result.is_active && get_focus_chain(&conn).contains(&active_window);
+      if let Some(z_order) = get_client_list_stacking(&conn, root_window)
+        .iter()
+        .position(|window| *window == active_window)
+      {
+        result.z_order = z_order as u32;
       }
     }
 
-    result
+    conn.check_timeout()?;
+    Ok(result)
   }
 
-  fn get_open_windows(&self) -> Vec<WindowInfo> {
-    let mut results: Vec<WindowInfo> = Vec::new();
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, ret)
+  )]
+  fn get_active_window_id(&self) -> Result<u32, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let root_window = get_focused_root_window(&conn)?;
+    Ok(
+      get_active_window_id(&conn, root_window)
+        .map(|window| window.resource_id())
+        .unwrap_or(0),
+    )
+  }
 
-    let conn = connection();
-    let setup = conn.get_setup();
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, ret)
+  )]
+  fn is_fullscreen_active(&self) -> Result<bool, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let root_window = get_focused_root_window(&conn)?;
+    Ok(
+      get_active_window_id(&conn, root_window)
+        .map(|window| is_full_screen_window(&conn, window))
+        .unwrap_or(false),
+    )
+  }
 
-    let root_window = setup.roots().next();
-    if root_window.is_some() {
-      let root_window = root_window.unwrap().root();
-
-      let open_windows_atom = get_client_list_stacking_atom(&conn);
-      if open_windows_atom != x::ATOM_NONE {
-        let window_list = conn.send_request(&x::GetProperty {
-          delete: false,
-          window: root_window,
-          property: open_windows_atom,
-          r#type: x::ATOM_WINDOW,
-          long_offset: 0,
-          long_length: u32::MAX,
-        });
-        if let Ok(windows_reply) = conn.wait_for_reply(window_list) {
-          let window_list: Vec<x::Window> = windows_reply.value::<x::Window>().to_vec();
-          if window_list.len().ne(&0) {
-            for window in window_list {
-              let window: &x::Window = &window;
-              let result = get_window_information(&conn, window);
-              if result.id.ne(&0) && is_normal_window(&conn, *window) {
-                results.push(result);
-              }
-            }
-          }
-        }
+  #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> {
+    self.get_open_windows_filtered(options, &mut |_window_info| true)
+  }
+
+  #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+  fn get_open_windows_filtered(
+    &self,
+    options: &OpenWindowsOptions,
+    filter: &mut dyn FnMut(&WindowInfo) -> bool,
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    get_client_windows(
+      self.display.as_deref(),
+      |_conn, _window| true,
+      filter,
+      &mut |_window_info| {},
+      options,
+    )
+  }
+
+  #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+  fn get_open_windows_with_progress(
+    &self,
+    options: &OpenWindowsOptions,
+    on_window: &mut dyn FnMut(&mut WindowInfo),
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    get_client_windows(
+      self.display.as_deref(),
+      |_conn, _window| true,
+      &mut |_window_info| true,
+      on_window,
+      options,
+    )
+  }
+
+  #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+  fn get_windows_by_pid(&self, process_id: u32) -> Result<Vec<WindowInfo>, XWinError> {
+    get_client_windows(
+      self.display.as_deref(),
+      |conn, window| get_window_pid(conn, window) == process_id,
+      &mut |_window_info| true,
+      &mut |_window_info| {},
+      &OpenWindowsOptions::default(),
+    )
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn close_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let Some((root_window, window)) = get_managed_window(&conn, window_id)? else {
+      return Ok(false);
+    };
+
+    let close_window_atom = get_atom(&conn, b"_NET_CLOSE_WINDOW", true);
+    let supported_atom = get_atom(&conn, b"_NET_SUPPORTED", true);
+    let delete_window_atom = get_atom(&conn, b"WM_DELETE_WINDOW", true);
+    let protocols_atom = get_atom(&conn, b"WM_PROTOCOLS", true);
+    if close_window_atom != x::ATOM_NONE
+      && get_atom_list(&conn, root_window, supported_atom).contains(&close_window_atom)
+    {
+      // Source indication 2 to be handled by the window manager as a user action
+      send_client_message(
+        &conn,
+        root_window,
+        window,
+        close_window_atom,
+        [x::CURRENT_TIME, 2, 0, 0, 0],
+        x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+      );
+    } else if delete_window_atom != x::ATOM_NONE
+      && get_atom_list(&conn, window, protocols_atom).contains(&delete_window_atom)
+    {
+      send_client_message(
+        &conn,
+        window,
+        window,
+        protocols_atom,
+        [delete_window_atom.resource_id(), x::CURRENT_TIME, 0, 0, 0],
+        x::EventMask::NO_EVENT,
+      );
+    } else {
+      return Ok(false);
+    }
+    conn
+      .flush()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    Ok(true)
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn set_window_position(
+    &self,
+    window_id: u32,
+    position: &WindowPosition,
+  ) -> Result<bool, XWinError> {
+    if position.width <= 0 || position.height <= 0 {
+      return Err(XWinError::PlatformError("invalid window size".to_owned()));
+    }
+    let conn = connection(self.display.as_deref())?;
+    let Some((root_window, window)) = get_managed_window(&conn, window_id)? else {
+      return Ok(false);
+    };
+
+    let supported = get_atom_list(&conn, root_window, get_atom(&conn, b"_NET_SUPPORTED", true));
+    let event_mask = x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT;
+    if is_full_screen_window(&conn, window) != position.is_full_screen {
+      let fullscreen_atom = get_atom(&conn, b"_NET_WM_STATE_FULLSCREEN", false);
+      // Action 1 to add and 0 to remove the state, source indication 2 for a user action
+      send_client_message(
+        &conn,
+        root_window,
+        window,
+        get_window_state_atom(&conn),
+        [
+          position.is_full_screen as u32,
+          fullscreen_atom.resource_id(),
+          0,
+          2,
+          0,
+        ],
+        event_mask,
+      );
+    }
+
+    if !position.is_full_screen {
+      conn.send_request(&x::ConfigureWindow {
+        window,
+        value_list: &[
+          x::ConfigWindow::X(position.x),
+          x::ConfigWindow::Y(position.y),
+          x::ConfigWindow::Width(position.width as u32),
+          x::ConfigWindow::Height(position.height as u32),
+        ],
+      });
+      let move_resize_atom = get_atom(&conn, b"_NET_MOVERESIZE_WINDOW", true);
+      if move_resize_atom != x::ATOM_NONE && supported.contains(&move_resize_atom) {
+        // Static gravity as positions are the ones of the client window, x, y, width and height flags
+        // and source indication 2 for a user action
+        let flags: u32 = 10 | (0b1111 << 8) | (2 << 12);
+        send_client_message(
+          &conn,
+          root_window,
+          window,
+          move_resize_atom,
+          [
+            flags,
+            position.x as u32,
+            position.y as u32,
+            position.width as u32,
+            position.height as u32,
+          ],
+          event_mask,
+        );
       }
     }
-    results
+    conn
+      .flush()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    Ok(true)
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn get_window_geometry(&self, window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let window: x::Window = XidNew::new(window_id);
+    if !window_exists(&conn, window) {
+      return Ok(None);
+    }
+    Ok(Some(get_window_position(&conn, window)))
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn get_window_property(
+    &self,
+    window_id: u32,
+    name: &str,
+  ) -> Result<Option<WindowProperty>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    if name.is_empty() {
+      return Ok(None);
+    }
+    // An atom which has never been interned can't be set on any window
+    let property = get_atom(&conn, name.as_bytes(), true);
+    if property == x::ATOM_NONE {
+      return Ok(None);
+    }
+    let Some((value_type, format, data)) =
+      get_raw_property(&conn, XidNew::new(window_id), property)
+    else {
+      return Ok(None);
+    };
+    Ok(Some(WindowProperty::new(
+      get_atom_name(&conn, value_type),
+      format,
+      data,
+    )))
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn minimize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let Some((root_window, window)) = get_managed_window(&conn, window_id)? else {
+      return Ok(false);
+    };
+    // Same request as XIconifyWindow, the window manager changes WM_STATE to iconic
+    send_client_message(
+      &conn,
+      root_window,
+      window,
+      get_atom(&conn, b"WM_CHANGE_STATE", false),
+      [ICONIC_STATE, 0, 0, 0, 0],
+      x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+    );
+    conn
+      .flush()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    Ok(true)
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn maximize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let Some((root_window, window)) = get_managed_window(&conn, window_id)? else {
+      return Ok(false);
+    };
+    send_maximized_state(&conn, root_window, window, true);
+    conn
+      .flush()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    Ok(true)
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn restore_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let Some((root_window, window)) = get_managed_window(&conn, window_id)? else {
+      return Ok(false);
+    };
+    // Iconic windows go back to the normal state when they are mapped again
+    if is_minimized_window(&conn, window) {
+      conn.send_request(&x::MapWindow { window });
+    }
+    send_maximized_state(&conn, root_window, window, false);
+    conn
+      .flush()
+      .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    Ok(true)
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, ret)
+  )]
+  fn get_window_under_cursor(&self) -> Result<Option<WindowInfo>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let pointer = conn.send_request(&x::QueryPointer {
+      window: get_root_window(&conn)?,
+    });
+    let Ok(pointer) = conn.wait_for_reply(pointer) else {
+      return Ok(None);
+    };
+    // Minimized windows keep their geometry, only mapped windows can be under the cursor.
+    // Coordinates are relative to the screen of the pointer, windows of other screens are ignored
+    let pointer_root_window = pointer.root();
+    let windows = get_client_windows(
+      self.display.as_deref(),
+      |conn, window| {
+        is_viewable_window(conn, window)
+          && get_window_root(conn, window) == Some(pointer_root_window)
+      },
+      &mut |_window_info| true,
+      &mut |_window_info| {},
+      &OpenWindowsOptions {
+        include_transient: true,
+        ..Default::default()
+      },
+    )?;
+    Ok(get_frontmost_window_at(
+      windows,
+      pointer.root_x() as i32,
+      pointer.root_y() as i32,
+    ))
+  }
+
+  fn capture_window(
+    &self,
+    window_id: u32,
+    max_dimension: Option<u32>,
+  ) -> Result<RawImage, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    capture_x11_window(&conn, XidNew::new(window_id), max_dimension)
+  }
+
+  fn capture_windows(
+    &self,
+    window_ids: &[u32],
+    max_dimension: Option<u32>,
+  ) -> Result<Vec<(u32, RawImage)>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    Ok(
+      window_ids
+        .iter()
+        .filter_map(|window_id| {
+          capture_x11_window(&conn, XidNew::new(*window_id), max_dimension)
+            .ok()
+            .map(|image| (*window_id, image))
+        })
+        .collect(),
+    )
   }
 
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo {
-    let conn = connection();
+    let Ok(conn) = connection(self.display.as_deref()) else {
+      return IconInfo {
+        data: "".to_owned(),
+        width: 0,
+        height: 0,
+      };
+    };
     let setup = conn.get_setup();
 
     let root_window = setup.roots().next();
     if root_window.is_some() {
-      let window = unsafe { XidNew::new(window_info.id) };
+      let window = XidNew::new(window_info.id);
       let icon_atom = get_window_icon_atom(&conn);
       if icon_atom != x::ATOM_NONE {
-        let icon_cookie = conn.send_request(&x::GetProperty {
-          delete: false,
-          window,
-          property: icon_atom,
-          r#type: x::ATOM_CARDINAL,
-          long_offset: 0,
-          long_length: u32::MAX,
-        });
-        if let Ok(icon_reply) = conn.wait_for_reply(icon_cookie) {
-          let icon_data: &[u32] = icon_reply.value::<u32>();
+        if let Some((_, icon_data)) =
+          get_property::<u32>(&conn, window, icon_atom, x::ATOM_CARDINAL)
+        {
           if !icon_data.is_empty() {
             let width = icon_data[0] as usize;
             let height = icon_data[1] as usize;
@@ -151,247 +552,2081 @@ impl Api for X11Api {
       height: 0,
     }
   }
-}
 
-fn connection() -> Connection {
-  let (conn, _) = xcb::Connection::connect(None).unwrap();
-  conn
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let root_window = get_root_window(&conn)?;
+    Ok(get_randr_monitors(&conn, root_window))
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(self), ret)
+  )]
+  fn get_workspaces(&self) -> Result<Vec<Workspace>, XWinError> {
+    let conn = connection(self.display.as_deref())?;
+    let root_window = get_root_window(&conn)?;
+    let desktop_names = get_desktop_names(&conn, root_window);
+    // Window managers without _NET_NUMBER_OF_DESKTOPS can still name their desktops
+    let desktop_count =
+      get_number_of_desktops(&conn, root_window).unwrap_or(desktop_names.len() as u32);
+    Ok(build_workspaces(
+      desktop_count,
+      &desktop_names,
+      get_current_desktop(&conn, root_window),
+    ))
+  }
+
+  fn change_source(&self) -> Option<Box<dyn ChangeSource>> {
+    X11ChangeSource::new(self.display.as_deref())
+      .ok()
+      .map(|source| Box::new(source) as Box<dyn ChangeSource>)
+  }
 }
 
 /**
- * Get window information
+ * Events of the X server selected on the root windows of all screens (windows created, destroyed, mapped, moved
+ * and root properties like `_NET_ACTIVE_WINDOW`) and on the active window (title, geometry)
  */
-fn get_window_information(conn: &xcb::Connection, window: &x::Window) -> WindowInfo {
-  let window_pid: u32 = get_window_pid(conn, *window);
-  let mut window_info: WindowInfo = init_entity();
+struct X11ChangeSource {
+  conn: X11Connection,
+  /**
+   * Properties which changes are notified, other properties of the root and active windows are ignored
+   */
+  watched_atoms: Vec<x::Atom>,
+  active_window: Option<x::Window>,
+}
 
-  if window_pid != 0 {
-    let (path, exec_name) = get_window_path_name(window_pid);
-    window_info.id = window.resource_id();
-    window_info.title = get_window_title(conn, *window);
-    window_info.info.process_id = window_pid;
-    window_info.info.path = path;
-    window_info.info.exec_name = exec_name;
-    window_info.info.name = get_window_class_name(conn, *window);
-    window_info.usage.memory = get_window_memory_usage(window_pid);
-    window_info.position = get_window_position(conn, *window);
+impl X11ChangeSource {
+  /**
+   * Select the events with a dedicated connection, an error is returned when the selection is refused
+   */
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+  )]
+  fn new(display: Option<&str>) -> Result<Self, XWinError> {
+    let conn = connection(display)?;
+    let root_windows = get_root_windows(&conn);
+    first_screen(root_windows.iter())?;
+    for root_window in root_windows {
+      let cookie = conn.send_request_checked(&x::ChangeWindowAttributes {
+        window: root_window,
+        value_list: &[x::Cw::EventMask(
+          x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::PROPERTY_CHANGE,
+        )],
+      });
+      conn
+        .check_request(cookie)
+        .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+    }
+    let watched_atoms = [
+      get_active_window_atom(&conn),
+      get_client_list_atom(&conn),
+      get_client_list_stacking_atom(&conn),
+      get_current_desktop_atom(&conn),
+      get_net_wm_name_atom(&conn),
+      x::ATOM_WM_NAME,
+    ]
+    .into_iter()
+    .filter(|atom| *atom != x::ATOM_NONE)
+    .collect();
+    let mut source = Self {
+      conn,
+      watched_atoms,
+      active_window: None,
+    };
+    source.watch_active_window();
+    Ok(source)
+  }
+
+  fn is_change_event(&self, event: &xcb::Event) -> bool {
+    match event {
+      xcb::Event::X(
+        x::Event::CreateNotify(_)
+        | x::Event::DestroyNotify(_)
+        | x::Event::MapNotify(_)
+        | x::Event::UnmapNotify(_)
+        | x::Event::ConfigureNotify(_),
+      ) => true,
+      xcb::Event::X(x::Event::PropertyNotify(event)) => self.watched_atoms.contains(&event.atom()),
+      _ => false,
+    }
+  }
+
+  /**
+   * Move the selection of the title and geometry events to the current active window
+   */
+  fn watch_active_window(&mut self) {
+    let active_window = get_focused_root_window(&self.conn)
+      .ok()
+      .and_then(|root_window| get_active_window_id(&self.conn, root_window));
+    if active_window == self.active_window {
+      return;
+    }
+    // Errors for destroyed windows are received with the events and ignored
+    if let Some(window) = self.active_window {
+      self.conn.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(x::EventMask::NO_EVENT)],
+      });
+    }
+    if let Some(window) = active_window {
+      self.conn.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(
+          x::EventMask::PROPERTY_CHANGE | x::EventMask::STRUCTURE_NOTIFY,
+        )],
+      });
+    }
+    self.active_window = active_window;
   }
-  window_info
 }
 
-/**
- * Get pid
- */
-fn get_window_pid(conn: &xcb::Connection, window: x::Window) -> u32 {
-  let window_pid_atom = get_window_pid_atom(conn);
-  if window_pid_atom != x::ATOM_NONE {
-    let window_pid = conn.send_request(&x::GetProperty {
-      delete: false,
-      window,
-      property: window_pid_atom,
-      r#type: x::ATOM_ANY,
-      long_offset: 0,
-      long_length: 1,
-    });
-    if let Ok(window_pid) = conn.wait_for_reply(window_pid) {
-      return window_pid.value::<u32>().first().unwrap().to_owned();
+impl ChangeSource for X11ChangeSource {
+  fn wait(&mut self, timeout: Duration) -> Result<bool, XWinError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      match self.conn.wait_for_event_timeout(remaining) {
+        Ok(Some(event)) if self.is_change_event(&event) => {
+          // Events already received are part of the same change
+          while let Ok(Some(_)) = self.conn.poll_for_queued_event() {}
+          self.watch_active_window();
+          return Ok(true);
+        }
+        Ok(Some(_)) | Err(xcb::Error::Protocol(_)) => {}
+        Ok(None) => return Ok(false),
+        Err(error) => return Err(XWinError::PlatformError(error.to_string())),
+      }
     }
   }
-  0
 }
 
 /**
- * Get window width, height, x and y
+ * Capture the RGBA pixels of the window with an existing connection, minimized and occluded windows are refused
  */
-fn get_window_position(conn: &xcb::Connection, window: x::Window) -> WindowPosition {
-  let mut position = WindowPosition {
+fn capture_x11_window(
+  conn: &X11Connection,
+  window: x::Window,
+  max_dimension: Option<u32>,
+) -> Result<RawImage, XWinError> {
+  if !is_viewable_window(conn, window) || has_window_state(conn, window, b"_NET_WM_STATE_HIDDEN") {
+    return Err(XWinError::PlatformError(
+      "window is minimized or not mapped".to_owned(),
+    ));
+  }
+  if is_occluded_window(conn, window) {
+    return Err(XWinError::PlatformError("window is occluded".to_owned()));
+  }
+
+  let geometry = conn.send_request(&x::GetGeometry {
+    drawable: x::Drawable::Window(window),
+  });
+  let geometry = conn
+    .wait_for_reply(geometry)
+    .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+  let (width, height) = (geometry.width(), geometry.height());
+
+  let image = conn.send_request(&x::GetImage {
+    format: x::ImageFormat::ZPixmap,
+    drawable: x::Drawable::Window(window),
     x: 0,
     y: 0,
-    width: 0,
-    height: 0,
-    is_full_screen: is_full_screen_window(conn, window),
-  };
-  let window_geometry = conn.send_request(&x::GetGeometry {
-    drawable: x::Drawable::Window(window),
+    width,
+    height,
+    plane_mask: u32::MAX,
   });
-  if let Ok(window_geometry) = conn.wait_for_reply(window_geometry) {
-    position.height = window_geometry.height() as i32;
-    position.width = window_geometry.width() as i32;
-    let window_geometry_x = window_geometry.x();
-    let window_geometry_y = window_geometry.y();
-    let translated_position = conn.send_request(&x::TranslateCoordinates {
-      dst_window: window_geometry.root(),
-      src_window: window,
-      src_x: window_geometry_x,
-      src_y: window_geometry_y,
-    });
-    if let Ok(translated_position) = conn.wait_for_reply(translated_position) {
-      position.x = (translated_position.dst_x() - window_geometry_x) as i32;
-      position.y = (translated_position.dst_y() - window_geometry_y) as i32;
-    }
+  let image = conn
+    .wait_for_reply(image)
+    .map_err(|error| XWinError::PlatformError(error.to_string()))?;
+  if image.depth() != 24 && image.depth() != 32 {
+    return Err(XWinError::PlatformError(format!(
+      "unsupported window depth {}",
+      image.depth()
+    )));
   }
 
-  position
+  // ZPixmap of depth 24 and 32 is stored as BGRX/BGRA
+  let rgba: Vec<u8> = image
+    .data()
+    .chunks_exact(4)
+    .flat_map(|bgra| {
+      let alpha = if image.depth() == 32 { bgra[3] } else { 255 };
+      [bgra[2], bgra[1], bgra[0], alpha]
+    })
+    .collect();
+  to_raw_image(width as u32, height as u32, rgba, max_dimension)
 }
 
 /**
- * Get window title
+ * Get information of windows from _NET_CLIENT_LIST_STACKING (or all client windows with `include_hidden`)
+ * accepted by the filter and the window types of the options, from front to back unless `raw_order` is set.
+ * `accept` is called with the information read from the X server before the process details are recovered
  */
-fn get_window_title(conn: &xcb::Connection, window: x::Window) -> String {
-  _get_string_response(conn, window, x::ATOM_WM_NAME)
+fn get_client_windows<F: Fn(&X11Connection, x::Window) -> bool>(
+  display: Option<&str>,
+  filter: F,
+  accept: &mut dyn FnMut(&WindowInfo) -> bool,
+  gathered: &mut dyn FnMut(&mut WindowInfo),
+  options: &OpenWindowsOptions,
+) -> Result<Vec<WindowInfo>, XWinError> {
+  let mut results: Vec<WindowInfo> = Vec::new();
+
+  let conn = connection_with_timeout(display, options.timeout_ms)?;
+  let focused_root_window = get_focused_root_window(&conn)?;
+  let focus_chain = get_focus_chain(&conn);
+  let window_types = get_window_type_atoms(&conn, options.window_types.as_deref());
+
+  // Windows of every screen are merged, the screen selected by the display name is stacked above the others
+  let mut z_order_offset: usize = 0;
+  for root_window in get_root_windows(&conn).into_iter().rev() {
+    let window_list: Vec<x::Window> = if options.include_hidden {
+      get_all_client_windows(&conn, root_window)
+    } else {
+      get_client_list_stacking(&conn, root_window)
+    };
+    if window_list.is_empty() {
+      continue;
+    }
+    let screen = get_screen_context(&conn, root_window);
+    let active_window = (root_window == focused_root_window)
+      .then(|| get_active_window_id(&conn, root_window))
+      .flatten();
+    for (z_order, window) in window_list.iter().enumerate() {
+      conn.check_timeout()?;
+      if !filter(&conn, *window) {
+        continue;
+      }
+      let mut result = get_window_x11_information(&conn, window, &screen);
+      if result.id.ne(&0)
+        && is_accepted_window_type(&conn, *window, &window_types, options.include_untyped)
+        && (!options.only_current_workspace
+          || is_on_desktop(result.workspace.as_ref(), screen.current_desktop))
+        && (options.include_transient || result.parent_id.is_none())
+        && (options.include_override_redirect || !is_override_redirect_window(&conn, *window))
+      {
+        result.z_order = (z_order_offset + z_order) as u32;
+        result.is_active = active_window == Some(*window);
+        result.is_focused = focus_chain.contains(window);
+        // Process details of rejected windows are never read
+        if accept(&result) {
+          set_process_information(&mut result, options.include_usage);
+          gathered(&mut result);
+          results.push(result);
+        }
+      }
+    }
+    z_order_offset += window_list.len();
+  }
+  conn.check_timeout()?;
+  if !options.raw_order {
+    sort_front_to_back(&mut results);
+  }
+  Ok(results)
 }
 
-fn _get_string_response(conn: &xcb::Connection, window: x::Window, property: x::Atom) -> String {
-  let window_title = conn.send_request(&x::GetProperty {
+/**
+ * Get the window set in _NET_ACTIVE_WINDOW
+ */
+#[cfg_attr(
+  feature = "tracing",
+  tracing::instrument(level = "debug", skip_all, ret)
+)]
+fn get_active_window_id(conn: &X11Connection, root_window: x::Window) -> Option<x::Window> {
+  let active_window_atom = get_active_window_atom(conn);
+  if active_window_atom == x::ATOM_NONE {
+    return None;
+  }
+  let active_window = conn.send_request(&x::GetProperty {
     delete: false,
-    window,
-    property,
-    r#type: x::ATOM_NONE,
+    window: root_window,
+    property: active_window_atom,
+    r#type: x::ATOM_WINDOW,
     long_offset: 0,
-    long_length: u32::MAX,
+    long_length: 1,
   });
-  if let Ok(window_title) = conn.wait_for_reply(window_title) {
-    let window_title: &[u8] = window_title.value();
-    unsafe { std::str::from_utf8_unchecked(window_title).to_string() }
-  } else {
-    "".to_owned()
-  }
+  conn
+    .wait_for_reply(active_window)
+    .ok()
+    .and_then(|reply| reply.value::<x::Window>().first().copied())
 }
 
 /**
- * Get process name
+ * Get the child window of the window manager set in _NET_SUPPORTING_WM_CHECK
  */
-fn get_window_class_name(conn: &xcb::Connection, window: x::Window) -> String {
-  let window_class = conn.send_request(&x::GetProperty {
+fn get_supporting_wm_check(conn: &X11Connection, root_window: x::Window) -> Option<x::Window> {
+  let supporting_wm_check_atom = get_supporting_wm_check_atom(conn);
+  if supporting_wm_check_atom == x::ATOM_NONE {
+    return None;
+  }
+  let supporting_wm_check = conn.send_request(&x::GetProperty {
     delete: false,
-    window,
-    property: x::ATOM_WM_CLASS,
-    r#type: x::ATOM_STRING,
+    window: root_window,
+    property: supporting_wm_check_atom,
+    r#type: x::ATOM_WINDOW,
     long_offset: 0,
-    long_length: u32::MAX,
+    long_length: 1,
   });
-  if let Ok(window_class) = conn.wait_for_reply(window_class) {
-    let window_class = window_class.value();
-    let window_class = std::str::from_utf8(window_class);
+  conn
+    .wait_for_reply(supporting_wm_check)
+    .ok()
+    .and_then(|reply| reply.value::<x::Window>().first().copied())
+    .filter(|window| window.resource_id() != 0)
+}
 
-    let mut process_name = window_class
-      .unwrap_or("")
-      .split('\u{0}')
-      .filter(|str| !str.is_empty())
-      .collect::<Vec<&str>>();
-    return process_name.pop().unwrap_or("").to_owned();
-  }
-  "".into()
+/**
+ * Get client windows from _NET_CLIENT_LIST_STACKING in bottom-to-top stacking order
+ */
+#[cfg_attr(
+  feature = "tracing",
+  tracing::instrument(level = "debug", skip_all, ret)
+)]
+fn get_client_list_stacking(conn: &X11Connection, root_window: x::Window) -> Vec<x::Window> {
+  get_window_list(conn, root_window, get_client_list_stacking_atom(conn))
 }
 
-fn get_window_pid_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_PID", true)
+/**
+ * Get client windows including the ones missing from _NET_CLIENT_LIST_STACKING: windows of _NET_CLIENT_LIST
+ * (minimized windows are omitted from the stacking list by some minimal WMs) and, when the WM maintains neither list,
+ * client windows found with QueryTree
+ */
+#[cfg_attr(
+  feature = "tracing",
+  tracing::instrument(level = "debug", skip_all, ret)
+)]
+fn get_all_client_windows(conn: &X11Connection, root_window: x::Window) -> Vec<x::Window> {
+  let windows = merge_client_lists(
+    &get_window_list(conn, root_window, get_client_list_atom(conn)),
+    &get_client_list_stacking(conn, root_window),
+  );
+  if windows.is_empty() {
+    find_client_windows(conn, root_window, 0)
+  } else {
+    windows
+  }
 }
 
 /**
- * Generate Atom of _NET_ACTIVE_WINDOW value
+ * Put windows of _NET_CLIENT_LIST missing from the stacking list below the stacked windows
  */
-fn get_active_window_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_ACTIVE_WINDOW", true)
+fn merge_client_lists(client_list: &[x::Window], stacking: &[x::Window]) -> Vec<x::Window> {
+  client_list
+    .iter()
+    .filter(|window| !stacking.contains(window))
+    .chain(stacking.iter())
+    .copied()
+    .collect()
 }
 
 /**
- * Generate Atom of _NET_CLIENT_LIST_STACKING value
+ * Find client windows (windows with WM_STATE) among the children of `window` in bottom-to-top stacking order,
+ * descendants are only searched for children which are not client windows themselves
  */
-fn get_client_list_stacking_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_CLIENT_LIST_STACKING", true)
+fn find_client_windows(conn: &X11Connection, window: x::Window, depth: u32) -> Vec<x::Window> {
+  if depth >= FIND_CLIENT_WINDOWS_MAX_DEPTH {
+    return Vec::new();
+  }
+  let tree = conn.send_request(&x::QueryTree { window });
+  let Ok(tree) = conn.wait_for_reply(tree) else {
+    return Vec::new();
+  };
+  tree
+    .children()
+    .iter()
+    .flat_map(|child| {
+      if get_wm_state(conn, *child).is_some() {
+        vec![*child]
+      } else {
+        find_client_windows(conn, *child, depth + 1)
+      }
+    })
+    .collect()
 }
 
 /**
- * Generate Atom of _NET_WM_WINDOW_TYPE value
+ * Get windows stored in a property of the root window (e.g. _NET_CLIENT_LIST)
  */
-fn get_window_type_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_WINDOW_TYPE", true)
+fn get_window_list(
+  conn: &X11Connection,
+  root_window: x::Window,
+  property: x::Atom,
+) -> Vec<x::Window> {
+  if property == x::ATOM_NONE {
+    return Vec::new();
+  }
+  get_property::<x::Window>(conn, root_window, property, x::ATOM_WINDOW)
+    .map(|(_, window_list)| window_list)
+    .unwrap_or_default()
 }
 
 /**
- * Generate Atom of _NET_WM_WINDOW_TYPE_NORMAL value
+ * Get the state of WM_STATE set by the WM on client windows (`1` normal, `3` iconic), `None` when not managed
  */
-fn get_window_type_normal_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_WINDOW_TYPE_NORMAL", true)
+fn get_wm_state(conn: &X11Connection, window: x::Window) -> Option<u32> {
+  let wm_state_atom = get_atom(conn, b"WM_STATE", true);
+  if wm_state_atom == x::ATOM_NONE {
+    return None;
+  }
+  let wm_state = conn.send_request(&x::GetProperty {
+    delete: false,
+    window,
+    property: wm_state_atom,
+    r#type: wm_state_atom,
+    long_offset: 0,
+    long_length: 2,
+  });
+  conn
+    .wait_for_reply(wm_state)
+    .ok()
+    .and_then(|reply| reply.value::<u32>().first().copied())
 }
 
 /**
- * Generate Atom of _NET_WM_STATE value
+ * Check if the window is minimized with _NET_WM_STATE_HIDDEN or the ICCCM iconic state
  */
-fn get_window_state_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_STATE", false)
+fn is_minimized_window(conn: &X11Connection, window: x::Window) -> bool {
+  has_window_state(conn, window, b"_NET_WM_STATE_HIDDEN")
+    || get_wm_state(conn, window) == Some(ICONIC_STATE)
 }
 
 /**
- * Generate Atom of _NET_WM_STATE_FULLSCREEN value
+ * Get the root window of the screen selected by the display name (the first one unless e.g. `:0.1`),
+ * `XWinError::NoScreen` when the X server has no screen
  */
-fn get_window_state_fullscreen_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_STATE_FULLSCREEN", false)
+fn get_root_window(conn: &X11Connection) -> Result<x::Window, XWinError> {
+  first_screen(get_root_windows(conn).into_iter())
+}
+
+fn first_screen<T>(mut screens: impl Iterator<Item = T>) -> Result<T, XWinError> {
+  screens.next().ok_or(XWinError::NoScreen)
 }
 
 /**
- * Generate Atom of _NET_WM_ICON value
+ * Get the root windows of all screens, the screen selected by the display name first
  */
-fn get_window_icon_atom(conn: &xcb::Connection) -> x::Atom {
-  get_atom(conn, b"_NET_WM_ICON", false)
+fn get_root_windows(conn: &X11Connection) -> Vec<x::Window> {
+  default_screen_first(
+    conn
+      .get_setup()
+      .roots()
+      .map(|screen| screen.root())
+      .collect(),
+    conn.default_screen(),
+  )
+}
+
+fn default_screen_first<T>(mut screens: Vec<T>, default_screen: usize) -> Vec<T> {
+  if default_screen < screens.len() {
+    let screen = screens.remove(default_screen);
+    screens.insert(0, screen);
+  }
+  screens
 }
 
 /**
- * Generate Atom of name parameter
+ * Get the root window of the screen showing the window
  */
-fn get_atom(conn: &xcb::Connection, name: &[u8], only_if_exists: bool) -> x::Atom {
-  let atom_name = conn.send_request(&x::InternAtom {
-    only_if_exists,
-    name,
+fn get_window_root(conn: &X11Connection, window: x::Window) -> Option<x::Window> {
+  let geometry = conn.send_request(&x::GetGeometry {
+    drawable: x::Drawable::Window(window),
   });
-  if let Ok(value) = conn.wait_for_reply(atom_name) {
-    value.atom()
-  } else {
-    x::ATOM_NONE
-  }
+  conn
+    .wait_for_reply(geometry)
+    .ok()
+    .map(|geometry| geometry.root())
 }
 
 /**
- * Check if the window is a normal type
+ * Get the window owning the input focus and its ancestors up to the root window, a client window is focused
+ * when it is one of them (the focus can be set on a child of the client). Empty when nothing is focused or
+ * the focus follows the pointer (`PointerRoot`)
  */
-fn is_normal_window(conn: &xcb::Connection, window: x::Window) -> bool {
-  let window_type_atom = get_window_type_atom(conn);
-  let type_normal_atom = get_window_type_normal_atom(conn);
-  if window_type_atom != x::ATOM_NONE && type_normal_atom != x::ATOM_NONE {
-    let window_state = conn.send_request(&x::GetProperty {
-      delete: false,
-      window,
-      property: window_type_atom,
-      r#type: x::ATOM_ATOM,
-      long_offset: 0,
-      long_length: u32::MAX,
-    });
-    if let Ok(window_state) = conn.wait_for_reply(window_state) {
-      return window_state.value().contains(&type_normal_atom);
+fn get_focus_chain(conn: &X11Connection) -> Vec<x::Window> {
+  let focus = conn.send_request(&x::GetInputFocus {});
+  let Ok(focus) = conn.wait_for_reply(focus) else {
+    return Vec::new();
+  };
+  let mut chain = Vec::new();
+  let mut window = focus.focus();
+  // None (0) and PointerRoot (1) are not windows
+  while window.resource_id() > 1 && chain.len() < FOCUS_CHAIN_MAX_DEPTH {
+    chain.push(window);
+    let tree = conn.send_request(&x::QueryTree { window });
+    let Ok(tree) = conn.wait_for_reply(tree) else {
+      break;
+    };
+    if tree.parent() == tree.root() {
+      break;
     }
+    window = tree.parent();
   }
-  false
+  chain
 }
 
 /**
- * Check if the window is full screened
+ * Get the root window of the screen owning the input focus (or the pointer when the focus follows it),
+ * the screen selected by the display name when it cannot be found
  */
-fn is_full_screen_window(conn: &xcb::Connection, window: x::Window) -> bool {
-  let state_window_atom = get_window_state_atom(conn);
-  let state_fullscreen_atom = get_window_state_fullscreen_atom(conn);
-  if state_window_atom != x::ATOM_NONE && state_fullscreen_atom != x::ATOM_NONE {
-    let window_state = conn.send_request(&x::GetProperty {
-      delete: false,
-      window,
-      property: state_window_atom,
-      r#type: x::ATOM_ATOM,
-      long_offset: 0,
-      long_length: u32::MAX,
-    });
-    if let Ok(window_state) = conn.wait_for_reply(window_state) {
-      return window_state.value().contains(&state_fullscreen_atom);
+fn get_focused_root_window(conn: &X11Connection) -> Result<x::Window, XWinError> {
+  let root_windows = get_root_windows(conn);
+  let default_root_window = first_screen(root_windows.iter().copied())?;
+  if root_windows.len() == 1 {
+    return Ok(default_root_window);
+  }
+  let focus = conn.send_request(&x::GetInputFocus {});
+  let Ok(focus) = conn.wait_for_reply(focus) else {
+    return Ok(default_root_window);
+  };
+  let focused_root_window = match focus.focus().resource_id() {
+    // No focus
+    0 => None,
+    // PointerRoot, the focus is on the screen of the pointer
+    1 => {
+      let pointer = conn.send_request(&x::QueryPointer {
+        window: default_root_window,
+      });
+      conn
+        .wait_for_reply(pointer)
+        .ok()
+        .map(|pointer| pointer.root())
     }
+    _ => get_window_root(conn, focus.focus()),
+  };
+  Ok(
+    focused_root_window
+      .filter(|root_window| root_windows.contains(root_window))
+      .unwrap_or(default_root_window),
+  )
+}
+
+/**
+ * Open a connection to the X server of `display`, `$DISPLAY` is used when not set
+ */
+#[cfg_attr(
+  feature = "tracing",
+  tracing::instrument(level = "debug", skip_all, err)
+)]
+fn connection(display: Option<&str>) -> Result<X11Connection, XWinError> {
+  X11Connection::connect(display, None)
+}
+
+/**
+ * Open a connection to the X server of `display` where replies are awaited at most `timeout_ms` milliseconds
+ */
+fn connection_with_timeout(
+  display: Option<&str>,
+  timeout_ms: Option<u32>,
+) -> Result<X11Connection, XWinError> {
+  X11Connection::connect(
+    display,
+    timeout_ms.map(|timeout_ms| Duration::from_millis(timeout_ms as u64)),
+  )
+}
+
+/**
+ * Get window information
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id())))]
+fn get_window_information(
+  conn: &X11Connection,
+  window: &x::Window,
+  screen: &ScreenContext,
+  include_usage: bool,
+) -> WindowInfo {
+  let mut window_info = get_window_x11_information(conn, window, screen);
+  set_process_information(&mut window_info, include_usage);
+  window_info
+}
+
+/**
+ * Read the process details of the window from /proc (executable, command line, user, start time and memory usage
+ * with `include_usage`), windows without process id are left untouched
+ */
+fn set_process_information(window_info: &mut WindowInfo, include_usage: bool) {
+  let window_pid = window_info.info.process_id;
+  if window_pid == 0 {
+    return;
+  }
+  let (path, exec_name) = get_window_path_name(window_pid);
+  window_info.info.path = path;
+  window_info.info.exec_name = exec_name;
+  window_info.info.command_line = get_process_command_line(window_pid);
+  window_info.info.user = get_process_user(window_pid);
+  window_info.info.start_time = get_process_start_time(window_pid);
+  if include_usage {
+    window_info.usage.memory = get_window_memory_usage(window_pid);
+  }
+}
+
+/**
+ * Get window information read from the X server, the process details are not recovered
+ */
+fn get_window_x11_information(
+  conn: &X11Connection,
+  window: &x::Window,
+  screen: &ScreenContext,
+) -> WindowInfo {
+  let window_pid: u32 = get_window_pid(conn, *window);
+  let mut window_info: WindowInfo = empty_entity();
+
+  // Windows without _NET_WM_PID are kept with empty process fields as long as they exist
+  if window_pid != 0 || window_exists(conn, *window) {
+    window_info.id = window.resource_id();
+    window_info.native_handle = window.resource_id() as u64;
+    window_info.title = get_window_title(conn, *window);
+    let window_class = get_window_class(conn, *window);
+    window_info.info.name = window_class.class;
+    window_info.info.instance_name = window_class.instance;
+    window_info.info.process_id = window_pid;
+    window_info.position = get_window_position(conn, *window);
+    window_info.frame = get_window_frame(conn, *window, &window_info.position);
+    window_info.on_external_display =
+      is_on_external_display(&window_info.position, &screen.monitors);
+    window_info.monitor = get_window_monitor(&window_info.position, &screen.monitors);
+    window_info.workspace = get_window_workspace(conn, *window, &screen.desktop_names);
+    window_info.opacity = get_window_opacity(conn, *window);
+    window_info.parent_id = get_transient_for(conn, *window, screen.root_window);
+    window_info.is_minimized = is_minimized_window(conn, *window);
+    window_info.is_visible = is_viewable_window(conn, *window);
+    window_info.role = get_window_role(conn, *window);
+    window_info.always_on_top = has_window_state(conn, *window, b"_NET_WM_STATE_ABOVE");
+    window_info.always_on_bottom = has_window_state(conn, *window, b"_NET_WM_STATE_BELOW");
+    // Window destroyed while its properties were read, its fields are partial
+    if conn.is_bad_window(*window) {
+      return empty_entity();
+    }
+  }
+  window_info
+}
+
+/**
+ * Get the window set in WM_TRANSIENT_FOR, windows transient for the root window (whole group) have no parent
+ */
+fn get_transient_for(
+  conn: &X11Connection,
+  window: x::Window,
+  root_window: x::Window,
+) -> Option<u32> {
+  let transient_for = conn.send_request(&x::GetProperty {
+    delete: false,
+    window,
+    property: x::ATOM_WM_TRANSIENT_FOR,
+    r#type: x::ATOM_WINDOW,
+    long_offset: 0,
+    long_length: 1,
+  });
+  conn
+    .wait_for_reply(transient_for)
+    .ok()
+    .and_then(|reply| reply.value::<x::Window>().first().copied())
+    .filter(|parent| parent.resource_id() != 0 && *parent != root_window)
+    .map(|parent| parent.resource_id())
+}
+
+/**
+ * Get opacity of the window from _NET_WM_WINDOW_OPACITY (0xFFFFFFFF for opaque windows)
+ */
+fn get_window_opacity(conn: &X11Connection, window: x::Window) -> f64 {
+  let opacity_atom = get_atom(conn, b"_NET_WM_WINDOW_OPACITY", true);
+  if opacity_atom == x::ATOM_NONE {
+    return 1.0;
+  }
+  let opacity = conn.send_request(&x::GetProperty {
+    delete: false,
+    window,
+    property: opacity_atom,
+    r#type: x::ATOM_CARDINAL,
+    long_offset: 0,
+    long_length: 1,
+  });
+  conn
+    .wait_for_reply(opacity)
+    .ok()
+    .and_then(|reply| reply.value::<u32>().first().copied())
+    .map(opacity_from_cardinal)
+    .unwrap_or(1.0)
+}
+
+fn opacity_from_cardinal(value: u32) -> f64 {
+  value as f64 / u32::MAX as f64
+}
+
+/**
+ * Get screen informations shared by all windows
+ */
+fn get_screen_context(conn: &X11Connection, root_window: x::Window) -> ScreenContext {
+  ScreenContext {
+    root_window,
+    monitors: get_randr_monitors(conn, root_window),
+    desktop_names: get_desktop_names(conn, root_window),
+    current_desktop: get_current_desktop(conn, root_window),
+  }
+}
+
+/**
+ * Get workspace of the window from _NET_WM_DESKTOP (0xFFFFFFFF for sticky windows)
+ */
+fn get_window_workspace(
+  conn: &X11Connection,
+  window: x::Window,
+  desktop_names: &[String],
+) -> Option<WorkspaceInfo> {
+  let desktop_atom = get_window_desktop_atom(conn);
+  if desktop_atom == x::ATOM_NONE {
+    return None;
+  }
+  let desktop = conn.send_request(&x::GetProperty {
+    delete: false,
+    window,
+    property: desktop_atom,
+    r#type: x::ATOM_CARDINAL,
+    long_offset: 0,
+    long_length: 1,
+  });
+  let desktop = conn.wait_for_reply(desktop).ok()?;
+  let desktop = *desktop.value::<u32>().first()?;
+  Some(workspace_from_desktop(desktop, desktop_names))
+}
+
+fn workspace_from_desktop(desktop: u32, desktop_names: &[String]) -> WorkspaceInfo {
+  if desktop == u32::MAX {
+    WorkspaceInfo { id: -1, name: None }
+  } else {
+    WorkspaceInfo {
+      id: desktop as i32,
+      name: desktop_names
+        .get(desktop as usize)
+        .filter(|name| !name.is_empty())
+        .cloned(),
+    }
+  }
+}
+
+/**
+ * Get the desktop shown on screen from _NET_CURRENT_DESKTOP
+ */
+fn get_current_desktop(conn: &X11Connection, root_window: x::Window) -> Option<u32> {
+  let current_desktop_atom = get_current_desktop_atom(conn);
+  if current_desktop_atom == x::ATOM_NONE {
+    return None;
+  }
+  let current_desktop = conn.send_request(&x::GetProperty {
+    delete: false,
+    window: root_window,
+    property: current_desktop_atom,
+    r#type: x::ATOM_CARDINAL,
+    long_offset: 0,
+    long_length: 1,
+  });
+  let current_desktop = conn.wait_for_reply(current_desktop).ok()?;
+  current_desktop.value::<u32>().first().copied()
+}
+
+/**
+ * Get the number of desktops from _NET_NUMBER_OF_DESKTOPS
+ */
+fn get_number_of_desktops(conn: &X11Connection, root_window: x::Window) -> Option<u32> {
+  let number_of_desktops_atom = get_atom(conn, b"_NET_NUMBER_OF_DESKTOPS", true);
+  if number_of_desktops_atom == x::ATOM_NONE {
+    return None;
+  }
+  let (_, value) =
+    get_property::<u32>(conn, root_window, number_of_desktops_atom, x::ATOM_CARDINAL)?;
+  value.first().copied()
+}
+
+/**
+ * Build the workspaces of `desktop_count` desktops, names are taken from _NET_DESKTOP_NAMES when set
+ */
+fn build_workspaces(
+  desktop_count: u32,
+  desktop_names: &[String],
+  current_desktop: Option<u32>,
+) -> Vec<Workspace> {
+  (0..desktop_count)
+    .map(|desktop| {
+      let workspace = workspace_from_desktop(desktop, desktop_names);
+      Workspace::new(
+        workspace.id,
+        workspace.name,
+        current_desktop == Some(desktop),
+      )
+    })
+    .collect()
+}
+
+/**
+ * To know if a window is shown on `desktop`, sticky windows and windows without desktop are shown on all of them
+ */
+fn is_on_desktop(workspace: Option<&WorkspaceInfo>, desktop: Option<u32>) -> bool {
+  match (workspace, desktop) {
+    (Some(workspace), Some(desktop)) => workspace.id == -1 || workspace.id as u32 == desktop,
+    _ => true,
+  }
+}
+
+/**
+ * Get labels of desktops from _NET_DESKTOP_NAMES (null separated UTF-8 strings)
+ */
+fn get_desktop_names(conn: &X11Connection, root_window: x::Window) -> Vec<String> {
+  let desktop_names_atom = get_desktop_names_atom(conn);
+  let utf8_string_atom = get_utf8_string_atom(conn);
+  if desktop_names_atom == x::ATOM_NONE || utf8_string_atom == x::ATOM_NONE {
+    return Vec::new();
+  }
+  if let Some((_, value)) =
+    get_property::<u8>(conn, root_window, desktop_names_atom, utf8_string_atom)
+  {
+    let mut names: Vec<String> = value
+      .split(|c| *c == 0)
+      .map(|name| String::from_utf8_lossy(name).to_string())
+      .collect();
+    // The list is terminated by a null byte
+    if value.last() == Some(&0) {
+      names.pop();
+    }
+    names
+  } else {
+    Vec::new()
+  }
+}
+
+/**
+ * Get monitors from RandR outputs that are connected to a crtc
+ */
+fn get_randr_monitors(conn: &X11Connection, root_window: x::Window) -> Vec<MonitorInfo> {
+  let mut monitors: Vec<MonitorInfo> = Vec::new();
+  let primary_output = conn
+    .wait_for_reply(conn.send_request(&randr::GetOutputPrimary {
+      window: root_window,
+    }))
+    .map(|reply| reply.output())
+    .unwrap_or(randr::Output::none());
+  let scale_factor = get_xft_scale_factor(conn, root_window).unwrap_or(1.0);
+  let resources = conn.send_request(&randr::GetScreenResourcesCurrent {
+    window: root_window,
+  });
+  if let Ok(resources) = conn.wait_for_reply(resources) {
+    for output in resources.outputs() {
+      let output_info = conn.send_request(&randr::GetOutputInfo {
+        output: *output,
+        config_timestamp: resources.config_timestamp(),
+      });
+      if let Ok(output_info) = conn.wait_for_reply(output_info) {
+        if output_info.crtc().is_none() {
+          continue;
+        }
+        let crtc_info = conn.send_request(&randr::GetCrtcInfo {
+          crtc: output_info.crtc(),
+          config_timestamp: resources.config_timestamp(),
+        });
+        if let Ok(crtc_info) = conn.wait_for_reply(crtc_info) {
+          monitors.push(MonitorInfo {
+            id: output.resource_id(),
+            is_primary: *output == primary_output,
+            name: String::from_utf8_lossy(output_info.name()).to_string(),
+            x: crtc_info.x() as i32,
+            y: crtc_info.y() as i32,
+            width: crtc_info.width() as i32,
+            height: crtc_info.height() as i32,
+            scale_factor,
+          });
+        }
+      }
+    }
+  }
+  monitors
+}
+
+/**
+ * Get the scale factor from `Xft.dpi` of the X resources (RESOURCE_MANAGER), it is shared by all monitors
+ */
+fn get_xft_scale_factor(conn: &X11Connection, root_window: x::Window) -> Option<f64> {
+  let resources = _get_string_response(conn, root_window, x::ATOM_RESOURCE_MANAGER, x::ATOM_STRING);
+  parse_xft_dpi(&resources).map(|dpi| dpi / 96.0)
+}
+
+/**
+ * Read the `Xft.dpi` value of X resources
+ */
+fn parse_xft_dpi(resources: &str) -> Option<f64> {
+  resources.lines().find_map(|line| {
+    let (name, value) = line.split_once(':')?;
+    if name.trim() != "Xft.dpi" {
+      return None;
+    }
+    value.trim().parse::<f64>().ok().filter(|dpi| *dpi > 0.0)
+  })
+}
+
+/**
+ * Check if the center of the window is on an external monitor.
+ * Return None when no monitor contains the window.
+ */
+fn is_on_external_display(position: &WindowPosition, monitors: &[MonitorInfo]) -> Option<bool> {
+  get_window_monitor(position, monitors).map(|monitor| !is_builtin_output(&monitor.name))
+}
+
+/**
+ * Built-in panels are exposed by RandR as eDP, LVDS or DSI outputs
+ */
+fn is_builtin_output(name: &str) -> bool {
+  let name = name.to_uppercase();
+  ["EDP", "LVDS", "DSI"]
+    .iter()
+    .any(|prefix| name.starts_with(prefix))
+}
+
+/**
+ * Get pid
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id()), ret))]
+fn get_window_pid(conn: &X11Connection, window: x::Window) -> u32 {
+  let window_pid_atom = get_window_pid_atom(conn);
+  if window_pid_atom != x::ATOM_NONE {
+    let window_pid = conn.send_request(&x::GetProperty {
+      delete: false,
+      window,
+      property: window_pid_atom,
+      r#type: x::ATOM_ANY,
+      long_offset: 0,
+      long_length: 1,
+    });
+    if let Ok(window_pid) = conn.wait_for_reply(window_pid) {
+      return window_pid.value::<u32>().first().copied().unwrap_or(0);
+    }
+  }
+  0
+}
+
+/**
+ * To know if the window still exists on the X server
+ */
+fn window_exists(conn: &X11Connection, window: x::Window) -> bool {
+  let attributes = conn.send_request(&x::GetWindowAttributes { window });
+  conn.wait_for_reply(attributes).is_ok()
+}
+
+/**
+ * Get window width, height, x and y
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id()), ret))]
+fn get_window_position(conn: &X11Connection, window: x::Window) -> WindowPosition {
+  let mut position = WindowPosition {
+    x: 0,
+    y: 0,
+    width: 0,
+    height: 0,
+    is_full_screen: is_full_screen_window(conn, window),
+  };
+  let window_geometry = conn.send_request(&x::GetGeometry {
+    drawable: x::Drawable::Window(window),
+  });
+  if let Ok(window_geometry) = conn.wait_for_reply(window_geometry) {
+    position.height = window_geometry.height() as i32;
+    position.width = window_geometry.width() as i32;
+    let window_geometry_x = window_geometry.x();
+    let window_geometry_y = window_geometry.y();
+    let translated_position = conn.send_request(&x::TranslateCoordinates {
+      dst_window: window_geometry.root(),
+      src_window: window,
+      src_x: window_geometry_x,
+      src_y: window_geometry_y,
+    });
+    if let Ok(translated_position) = conn.wait_for_reply(translated_position) {
+      position.x = (translated_position.dst_x() - window_geometry_x) as i32;
+      position.y = (translated_position.dst_y() - window_geometry_y) as i32;
+    }
+  }
+
+  position
+}
+
+/**
+ * Get the bounds of the window with the decorations of the window manager from `_NET_FRAME_EXTENTS`
+ * (left, right, top and bottom borders), `None` when the window manager does not set them
+ */
+fn get_window_frame(
+  conn: &X11Connection,
+  window: x::Window,
+  position: &WindowPosition,
+) -> Option<WindowPosition> {
+  let frame_extents_atom = get_atom(conn, b"_NET_FRAME_EXTENTS", true);
+  if frame_extents_atom == x::ATOM_NONE {
+    return None;
+  }
+  let (_, extents) = get_property::<u32>(conn, window, frame_extents_atom, x::ATOM_CARDINAL)?;
+  let [left, right, top, bottom] = extents.get(..4)?.try_into().ok()?;
+  let [left, right, top, bottom] = [left, right, top, bottom].map(|extent| extent as i32);
+  Some(WindowPosition::new(
+    position.x - left,
+    position.y - top,
+    position.width + left + right,
+    position.height + top + bottom,
+    position.is_full_screen,
+  ))
+}
+
+/**
+ * Get window title from _NET_WM_NAME (UTF-8) and fallback to legacy WM_NAME
+ */
+fn get_window_title(conn: &X11Connection, window: x::Window) -> String {
+  let net_wm_name_atom = get_net_wm_name_atom(conn);
+  let utf8_string_atom = get_utf8_string_atom(conn);
+  if net_wm_name_atom != x::ATOM_NONE && utf8_string_atom != x::ATOM_NONE {
+    let title = _get_string_response(conn, window, net_wm_name_atom, utf8_string_atom);
+    if !title.is_empty() {
+      return title;
+    }
+  }
+  _get_string_response(conn, window, x::ATOM_WM_NAME, x::ATOM_ANY)
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id(), property = %get_atom_name(conn, property)), ret))]
+fn _get_string_response(
+  conn: &X11Connection,
+  window: x::Window,
+  property: x::Atom,
+  r#type: x::Atom,
+) -> String {
+  if let Some((value_type, value)) = get_property::<u8>(conn, window, property, r#type) {
+    decode_text(&value, value_type == x::ATOM_STRING)
+  } else {
+    "".to_owned()
+  }
+}
+
+/**
+ * Decode a text property, STRING properties are encoded as ISO-8859-1 and other types (UTF8_STRING) as UTF-8
+ */
+fn decode_text(value: &[u8], is_latin1: bool) -> String {
+  if is_latin1 {
+    value.iter().map(|&c| c as char).collect()
+  } else {
+    String::from_utf8_lossy(value).to_string()
+  }
+}
+
+/**
+ * Get the role of the window from WM_WINDOW_ROLE, empty when not set
+ */
+fn get_window_role(conn: &X11Connection, window: x::Window) -> String {
+  let window_role_atom = get_atom(conn, b"WM_WINDOW_ROLE", true);
+  if window_role_atom == x::ATOM_NONE {
+    return "".to_owned();
+  }
+  _get_string_response(conn, window, window_role_atom, x::ATOM_STRING)
+}
+
+/**
+ * Instance and class names of a window from WM_CLASS (e.g. `Navigator` and `firefox`)
+ */
+#[derive(Debug, Default, PartialEq)]
+struct WmClass {
+  instance: String,
+  class: String,
+}
+
+/**
+ * Get instance and class names of a window from WM_CLASS
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id()), ret))]
+fn get_window_class(conn: &X11Connection, window: x::Window) -> WmClass {
+  // Some toolkits store WM_CLASS as UTF8_STRING instead of STRING, any type is accepted
+  get_property::<u8>(conn, window, x::ATOM_WM_CLASS, x::ATOM_ANY)
+    .map(|(value_type, window_class)| parse_wm_class(&window_class, value_type == x::ATOM_STRING))
+    .unwrap_or_default()
+}
+
+/**
+ * Parse the NUL separated instance and class names of WM_CLASS (ISO-8859-1 for STRING, UTF-8 otherwise),
+ * a single name is used for both and missing names are empty
+ */
+fn parse_wm_class(value: &[u8], is_latin1: bool) -> WmClass {
+  let names: Vec<String> = value
+    .split(|&c| c == 0)
+    .filter(|name| !name.is_empty())
+    .map(|name| decode_text(name, is_latin1))
+    .collect();
+  WmClass {
+    instance: names.first().cloned().unwrap_or_default(),
+    class: names.last().cloned().unwrap_or_default(),
+  }
+}
+
+fn get_window_pid_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_PID", true)
+}
+
+/**
+ * Generate Atom of _NET_WM_NAME value
+ */
+fn get_net_wm_name_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_NAME", false)
+}
+
+/**
+ * Generate Atom of UTF8_STRING value
+ */
+fn get_utf8_string_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"UTF8_STRING", false)
+}
+
+/**
+ * Generate Atom of _NET_WM_DESKTOP value
+ */
+fn get_window_desktop_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_DESKTOP", true)
+}
+
+/**
+ * Generate Atom of _NET_CURRENT_DESKTOP value
+ */
+fn get_current_desktop_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_CURRENT_DESKTOP", true)
+}
+
+/**
+ * Generate Atom of _NET_DESKTOP_NAMES value
+ */
+fn get_desktop_names_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_DESKTOP_NAMES", true)
+}
+
+/**
+ * Generate Atom of _NET_ACTIVE_WINDOW value
+ */
+fn get_active_window_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_ACTIVE_WINDOW", true)
+}
+
+/**
+ * Generate Atom of _NET_SUPPORTING_WM_CHECK value
+ */
+fn get_supporting_wm_check_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_SUPPORTING_WM_CHECK", true)
+}
+
+/**
+ * Generate Atom of _NET_CLIENT_LIST value
+ */
+fn get_client_list_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_CLIENT_LIST", true)
+}
+
+/**
+ * Generate Atom of _NET_CLIENT_LIST_STACKING value
+ */
+fn get_client_list_stacking_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_CLIENT_LIST_STACKING", true)
+}
+
+/**
+ * Generate Atom of _NET_WM_WINDOW_TYPE value
+ */
+fn get_window_type_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_WINDOW_TYPE", true)
+}
+
+/**
+ * Generate Atoms of accepted _NET_WM_WINDOW_TYPE_<TYPE> values, `DEFAULT_WINDOW_TYPES` when not set
+ */
+fn get_window_type_atoms(conn: &X11Connection, window_types: Option<&[String]>) -> Vec<x::Atom> {
+  let default_window_types = DEFAULT_WINDOW_TYPES.map(String::from);
+  window_types
+    .unwrap_or(&default_window_types)
+    .iter()
+    .map(|window_type| get_atom(conn, window_type_atom_name(window_type).as_bytes(), true))
+    .filter(|atom| *atom != x::ATOM_NONE)
+    .collect()
+}
+
+/**
+ * Name of the _NET_WM_WINDOW_TYPE value of a window type (e.g. `dialog` for _NET_WM_WINDOW_TYPE_DIALOG)
+ */
+fn window_type_atom_name(window_type: &str) -> String {
+  format!("_NET_WM_WINDOW_TYPE_{}", window_type.to_uppercase())
+}
+
+/**
+ * Generate Atom of _NET_WM_STATE value
+ */
+fn get_window_state_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_STATE", false)
+}
+
+/**
+ * Generate Atom of _NET_WM_ICON value
+ */
+fn get_window_icon_atom(conn: &X11Connection) -> x::Atom {
+  get_atom(conn, b"_NET_WM_ICON", false)
+}
+
+/**
+ * Generate Atom of name parameter
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(name = %String::from_utf8_lossy(name), only_if_exists), ret))]
+fn get_atom(conn: &X11Connection, name: &[u8], only_if_exists: bool) -> x::Atom {
+  let atom_name = conn.send_request(&x::InternAtom {
+    only_if_exists,
+    name,
+  });
+  if let Ok(value) = conn.wait_for_reply(atom_name) {
+    value.atom()
+  } else {
+    x::ATOM_NONE
+  }
+}
+
+/**
+ * Get name of the atom, empty when the atom does not exist
+ */
+fn get_atom_name(conn: &X11Connection, atom: x::Atom) -> String {
+  let atom_name = conn.send_request(&x::GetAtomName { atom });
+  conn
+    .wait_for_reply(atom_name)
+    .map(|reply| reply.name().to_string())
+    .unwrap_or_default()
+}
+
+/**
+ * Check if the window has one of the accepted types (or has no type when `include_untyped` is set)
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(conn, window_types), fields(window = window.resource_id()), ret))]
+fn is_accepted_window_type(
+  conn: &X11Connection,
+  window: x::Window,
+  window_types: &[x::Atom],
+  include_untyped: bool,
+) -> bool {
+  let types = get_atom_list(conn, window, get_window_type_atom(conn));
+  types
+    .iter()
+    .any(|window_type| window_types.contains(window_type))
+    || (include_untyped && types.is_empty())
+}
+
+/**
+ * Check if the window is full screened with _NET_WM_STATE_FULLSCREEN,
+ * geometry is not used as borderless maximized windows can cover the whole screen
+ */
+fn is_full_screen_window(conn: &X11Connection, window: x::Window) -> bool {
+  has_window_state(conn, window, b"_NET_WM_STATE_FULLSCREEN")
+}
+
+/**
+ * Check if _NET_WM_STATE of the window contains the state
+ */
+fn has_window_state(conn: &X11Connection, window: x::Window, state: &[u8]) -> bool {
+  let state_atom = get_atom(conn, state, true);
+  state_atom != x::ATOM_NONE
+    && get_atom_list(conn, window, get_window_state_atom(conn)).contains(&state_atom)
+}
+
+/**
+ * Get atoms stored in a property of the window (e.g. _NET_WM_STATE, WM_PROTOCOLS)
+ */
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = window.resource_id(), property = %get_atom_name(conn, property)), ret))]
+fn get_atom_list(conn: &X11Connection, window: x::Window, property: x::Atom) -> Vec<x::Atom> {
+  if property == x::ATOM_NONE {
+    return Vec::new();
+  }
+  get_property::<x::Atom>(conn, window, property, x::ATOM_ATOM)
+    .map(|(_, atom_list)| atom_list)
+    .unwrap_or_default()
+}
+
+/**
+ * Get the value of a window property with its actual type, `None` when the request fails or the value is stored
+ * with another format than `P` (e.g. format 32 for a property read with `AnyPropertyType` as bytes).
+ * The value is read in chunks of `PROPERTY_CHUNK_LENGTH` until no bytes are left, a single
+ * reply could be truncated for long titles or client lists
+ */
+fn get_property<P: x::PropEl + Clone>(
+  conn: &X11Connection,
+  window: x::Window,
+  property: x::Atom,
+  r#type: x::Atom,
+) -> Option<(x::Atom, Vec<P>)> {
+  get_property_chunked(conn, window, property, r#type, PROPERTY_CHUNK_LENGTH)
+}
+
+fn get_property_chunked<P: x::PropEl + Clone>(
+  conn: &X11Connection,
+  window: x::Window,
+  property: x::Atom,
+  r#type: x::Atom,
+  chunk_length: u32,
+) -> Option<(x::Atom, Vec<P>)> {
+  let mut value: Vec<P> = Vec::new();
+  let mut long_offset: u32 = 0;
+  loop {
+    let cookie = conn.send_request(&x::GetProperty {
+      delete: false,
+      window,
+      property,
+      r#type,
+      long_offset,
+      long_length: chunk_length,
+    });
+    let reply = conn.wait_for_reply(cookie).ok()?;
+    // A value is returned for a matching type or `AnyPropertyType`, `value` panics on another format than `P`
+    let has_value =
+      reply.r#type() != x::ATOM_NONE && (r#type == x::ATOM_ANY || reply.r#type() == r#type);
+    if has_value && reply.format() != P::FORMAT {
+      return None;
+    }
+    let chunk = reply.value::<P>();
+    value.extend_from_slice(chunk);
+    // Nothing is returned when the type doesn't match, the remaining bytes are never read
+    if reply.bytes_after() == 0 || chunk.is_empty() {
+      return Some((reply.r#type(), value));
+    }
+    long_offset += chunk_length;
+  }
+}
+
+/**
+ * Get the value of a window property as bytes with its type and format (bits per item), `None` when the property
+ * is not set or the request fails. The value is read in chunks like `get_property`, each chunk with items of the
+ * size of its format
+ */
+fn get_raw_property(
+  conn: &X11Connection,
+  window: x::Window,
+  property: x::Atom,
+) -> Option<(x::Atom, u8, Vec<u8>)> {
+  let mut data: Vec<u8> = Vec::new();
+  let mut value_type = x::ATOM_ANY;
+  let mut format: u8 = 0;
+  let mut long_offset: u32 = 0;
+  loop {
+    let cookie = conn.send_request(&x::GetProperty {
+      delete: false,
+      window,
+      property,
+      r#type: value_type,
+      long_offset,
+      long_length: PROPERTY_CHUNK_LENGTH,
+    });
+    let reply = conn.wait_for_reply(cookie).ok()?;
+    if long_offset == 0 {
+      value_type = reply.r#type();
+      format = reply.format();
+    } else if reply.r#type() != value_type || reply.format() != format {
+      // Value replaced while it is read
+      return None;
+    }
+    if value_type == x::ATOM_NONE {
+      return None;
+    }
+    let chunk_len = data.len();
+    match reply.format() {
+      8 => data.extend_from_slice(reply.value::<u8>()),
+      16 => data.extend(
+        reply
+          .value::<u16>()
+          .iter()
+          .flat_map(|item| item.to_ne_bytes()),
+      ),
+      32 => data.extend(
+        reply
+          .value::<u32>()
+          .iter()
+          .flat_map(|item| item.to_ne_bytes()),
+      ),
+      _ => return None,
+    }
+    if reply.bytes_after() == 0 || data.len() == chunk_len {
+      return Some((value_type, format, data));
+    }
+    long_offset += PROPERTY_CHUNK_LENGTH;
+  }
+}
+
+/**
+ * Get the root window of its screen and the window of `window_id` when it is managed by the window manager
+ */
+fn get_managed_window(
+  conn: &X11Connection,
+  window_id: u32,
+) -> Result<Option<(x::Window, x::Window)>, XWinError> {
+  let window: x::Window = XidNew::new(window_id);
+  let root_windows = get_root_windows(conn);
+  first_screen(root_windows.iter())?;
+  Ok(
+    root_windows
+      .into_iter()
+      .find(|root_window| get_client_list_stacking(conn, *root_window).contains(&window))
+      .map(|root_window| (root_window, window)),
+  )
+}
+
+/**
+ * Ask the window manager to add or remove both maximized states of the window
+ */
+fn send_maximized_state(
+  conn: &X11Connection,
+  root_window: x::Window,
+  window: x::Window,
+  is_maximized: bool,
+) {
+  let maximized_vert_atom = get_atom(conn, b"_NET_WM_STATE_MAXIMIZED_VERT", false);
+  let maximized_horz_atom = get_atom(conn, b"_NET_WM_STATE_MAXIMIZED_HORZ", false);
+  // Action 1 to add and 0 to remove the states, source indication 2 for a user action
+  send_client_message(
+    conn,
+    root_window,
+    window,
+    get_window_state_atom(conn),
+    [
+      is_maximized as u32,
+      maximized_vert_atom.resource_id(),
+      maximized_horz_atom.resource_id(),
+      2,
+      0,
+    ],
+    x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+  );
+}
+
+/**
+ * Send a 32 bits client message about the window to the destination
+ */
+fn send_client_message(
+  conn: &X11Connection,
+  destination: x::Window,
+  window: x::Window,
+  message_type: x::Atom,
+  data: [u32; 5],
+  event_mask: x::EventMask,
+) {
+  let event = x::ClientMessageEvent::new(window, message_type, x::ClientMessageData::Data32(data));
+  conn.send_request(&x::SendEvent {
+    propagate: false,
+    destination: x::SendEventDest::Window(destination),
+    event_mask,
+    event: &event,
+  });
+}
+
+/**
+ * Check if the window is mapped and all its ancestors are mapped
+ */
+fn is_viewable_window(conn: &X11Connection, window: x::Window) -> bool {
+  let attributes = conn.send_request(&x::GetWindowAttributes { window });
+  conn
+    .wait_for_reply(attributes)
+    .map(|attributes| attributes.map_state() == x::MapState::Viewable)
+    .unwrap_or(false)
+}
+
+/**
+ * Check if the window is placed without the window manager (menus, tooltips, drag icons...),
+ * such windows are not managed even when they set no `_NET_WM_WINDOW_TYPE`
+ */
+fn is_override_redirect_window(conn: &X11Connection, window: x::Window) -> bool {
+  let attributes = conn.send_request(&x::GetWindowAttributes { window });
+  conn
+    .wait_for_reply(attributes)
+    .map(|attributes| attributes.override_redirect())
+    .unwrap_or(false)
+}
+
+/**
+ * Check if the window is fully covered by a viewable client window stacked above it
+ */
+fn is_occluded_window(conn: &X11Connection, window: x::Window) -> bool {
+  let Ok(Some((root_window, _))) = get_managed_window(conn, window.resource_id()) else {
+    return false;
+  };
+  let window_list = get_client_list_stacking(conn, root_window);
+  let Some(index) = window_list.iter().position(|client| *client == window) else {
+    return false;
+  };
+  let position = get_window_position(conn, window);
+  window_list[index + 1..].iter().any(|above| {
+    is_viewable_window(conn, *above)
+      && !has_window_state(conn, *above, b"_NET_WM_STATE_HIDDEN")
+      && contains_position(&get_window_position(conn, *above), &position)
+  })
+}
+
+fn contains_position(outer: &WindowPosition, inner: &WindowPosition) -> bool {
+  outer.x <= inner.x
+    && outer.y <= inner.y
+    && outer.x + outer.width >= inner.x + inner.width
+    && outer.y + outer.height >= inner.y + inner.height
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_test_window(conn: &X11Connection) -> x::Window {
+    let screen = conn.get_setup().roots().next().unwrap();
+    let window: x::Window = conn.generate_id();
+    conn.send_request(&x::CreateWindow {
+      depth: x::COPY_FROM_PARENT as u8,
+      wid: window,
+      parent: screen.root(),
+      x: 0,
+      y: 0,
+      width: 100,
+      height: 100,
+      border_width: 0,
+      class: x::WindowClass::InputOutput,
+      visual: screen.root_visual(),
+      value_list: &[],
+    });
+    conn.flush().unwrap();
+    window
+  }
+
+  fn destroy_test_window(conn: &X11Connection, window: x::Window) {
+    conn.send_request(&x::DestroyWindow { window });
+    conn.flush().unwrap();
+  }
+
+  #[test]
+  fn test_get_window_title_utf8() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let title = "x-win ✓ émoji 🚀 テスト";
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_net_wm_name_atom(&conn),
+      r#type: get_utf8_string_atom(&conn),
+      data: title.as_bytes(),
+    });
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_NAME,
+      r#type: x::ATOM_STRING,
+      data: b"legacy",
+    });
+    conn.flush().unwrap();
+    assert_eq!(get_window_title(&conn, window), title);
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_property_chunked() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let title = "x-win ".repeat(1000);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_net_wm_name_atom(&conn),
+      r#type: get_utf8_string_atom(&conn),
+      data: title.as_bytes(),
+    });
+    conn.flush().unwrap();
+    let (value_type, value) = get_property_chunked::<u8>(
+      &conn,
+      window,
+      get_net_wm_name_atom(&conn),
+      get_utf8_string_atom(&conn),
+      7,
+    )
+    .unwrap();
+    assert_eq!(value_type, get_utf8_string_atom(&conn));
+    assert_eq!(value, title.as_bytes());
+    assert_eq!(get_window_title(&conn, window), title);
+    // A mismatched type returns the actual type without value
+    let (value_type, value) =
+      get_property::<u8>(&conn, window, get_net_wm_name_atom(&conn), x::ATOM_STRING).unwrap();
+    assert_eq!(value_type, get_utf8_string_atom(&conn));
+    assert!(value.is_empty());
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_title_fallback_wm_name() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_NAME,
+      r#type: x::ATOM_STRING,
+      data: b"caf\xe9",
+    });
+    conn.flush().unwrap();
+    assert_eq!(get_window_title(&conn, window), "café");
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_role() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    assert_eq!(get_window_role(&conn, window), "");
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_atom(&conn, b"WM_WINDOW_ROLE", false),
+      r#type: x::ATOM_STRING,
+      data: b"browser",
+    });
+    conn.flush().unwrap();
+    assert_eq!(get_window_role(&conn, window), "browser");
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_frame() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let position = WindowPosition::new(10, 40, 100, 100, false);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_atom(&conn, b"_NET_FRAME_EXTENTS", false),
+      r#type: x::ATOM_CARDINAL,
+      data: &[2u32, 3, 30, 4],
+    });
+    conn.flush().unwrap();
+    let frame = get_window_frame(&conn, window, &position).unwrap();
+    assert_eq!(
+      (frame.x, frame.y, frame.width, frame.height),
+      (8, 10, 105, 134)
+    );
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_merge_client_lists() {
+    let [a, b, c]: [x::Window; 3] = [1, 2, 3].map(XidNew::new);
+    assert_eq!(merge_client_lists(&[a, b, c], &[c, a]), vec![b, c, a]);
+    assert_eq!(merge_client_lists(&[a], &[]), vec![a]);
+    assert_eq!(merge_client_lists(&[], &[b]), vec![b]);
+  }
+
+  #[test]
+  fn test_parse_wm_class() {
+    let wm_class = parse_wm_class(b"Navigator\0firefox\0", true);
+    assert_eq!(wm_class.instance, "Navigator");
+    assert_eq!(wm_class.class, "firefox");
+    let wm_class = parse_wm_class(b"xterm", true);
+    assert_eq!(wm_class.instance, "xterm");
+    assert_eq!(wm_class.class, "xterm");
+    assert_eq!(parse_wm_class(b"", true), WmClass::default());
+    assert_eq!(parse_wm_class(b"\0\0", true), WmClass::default());
+    assert_eq!(parse_wm_class(b"caf\xe9\0Caf\xe9", true).class, "Café");
+    let wm_class = parse_wm_class("café\0Café ✓\0".as_bytes(), false);
+    assert_eq!(wm_class.instance, "café");
+    assert_eq!(wm_class.class, "Café ✓");
+  }
+
+  #[test]
+  fn test_get_window_class_utf8() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_CLASS,
+      r#type: get_utf8_string_atom(&conn),
+      data: "code\0Code ✓\0".as_bytes(),
+    });
+    conn.flush().unwrap();
+    let wm_class = get_window_class(&conn, window);
+    assert_eq!(wm_class.instance, "code");
+    assert_eq!(wm_class.class, "Code ✓");
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_class_format_32() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_CLASS,
+      r#type: x::ATOM_CARDINAL,
+      data: &[1u32, 2u32],
+    });
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_NAME,
+      r#type: x::ATOM_CARDINAL,
+      data: &[1u32],
+    });
+    conn.flush().unwrap();
+    let wm_class = get_window_class(&conn, window);
+    assert_eq!(wm_class.instance, "");
+    assert_eq!(wm_class.class, "");
+    assert_eq!(get_window_title(&conn, window), "");
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_raw_property() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let property = get_atom(&conn, b"_X_WIN_TEST_PROPERTY", false);
+    assert_eq!(get_raw_property(&conn, window, property), None);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property,
+      r#type: x::ATOM_CARDINAL,
+      data: &[1u32, 2u32],
+    });
+    conn.flush().unwrap();
+    let (value_type, format, data) = get_raw_property(&conn, window, property).unwrap();
+    assert_eq!(get_atom_name(&conn, value_type), "CARDINAL");
+    assert_eq!(format, 32);
+    assert_eq!(data, [1u32.to_ne_bytes(), 2u32.to_ne_bytes()].concat());
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_opacity_from_cardinal() {
+    assert_eq!(opacity_from_cardinal(u32::MAX), 1.0);
+    assert_eq!(opacity_from_cardinal(0), 0.0);
+    assert!((opacity_from_cardinal(0x7FFFFFFF) - 0.5).abs() < 0.0001);
+  }
+
+  #[test]
+  fn test_is_full_screen_window() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let screen = conn.get_setup().roots().next().unwrap();
+    conn.send_request(&x::ConfigureWindow {
+      window,
+      value_list: &[
+        x::ConfigWindow::Width(screen.width_in_pixels() as u32),
+        x::ConfigWindow::Height(screen.height_in_pixels() as u32),
+      ],
+    });
+    conn.flush().unwrap();
+    assert!(!get_window_position(&conn, window).is_full_screen);
+
+    let fullscreen_atom = get_atom(&conn, b"_NET_WM_STATE_FULLSCREEN", false);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_window_state_atom(&conn),
+      r#type: x::ATOM_ATOM,
+      data: &[fullscreen_atom],
+    });
+    conn.flush().unwrap();
+    assert!(get_window_position(&conn, window).is_full_screen);
+
+    conn.send_request(&x::ChangeProperty::<x::Atom> {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_window_state_atom(&conn),
+      r#type: x::ATOM_ATOM,
+      data: &[],
+    });
+    conn.flush().unwrap();
+    assert!(!get_window_position(&conn, window).is_full_screen);
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_information_without_pid() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_NAME,
+      r#type: x::ATOM_STRING,
+      data: b"no pid",
+    });
+    conn.flush().unwrap();
+    let root_window = conn.get_setup().roots().next().unwrap().root();
+    let screen = get_screen_context(&conn, root_window);
+    let window_info = get_window_information(&conn, &window, &screen, true);
+    assert_eq!(window_info.id, window.resource_id());
+    assert_eq!(window_info.native_handle, window.resource_id() as u64);
+    assert_eq!(window_info.title, "no pid");
+    assert_eq!(window_info.info.process_id, 0);
+    assert_eq!(window_info.position.width, 100);
+    destroy_test_window(&conn, window);
+    let window_info = get_window_information(&conn, &window, &screen, true);
+    assert_eq!(window_info.id, 0);
+  }
+
+  #[test]
+  fn test_get_window_information_stacking_layer() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let root_window = conn.get_setup().roots().next().unwrap().root();
+    let screen = get_screen_context(&conn, root_window);
+    let window_info = get_window_information(&conn, &window, &screen, false);
+    assert!(!window_info.always_on_top && !window_info.always_on_bottom);
+
+    let above_atom = get_atom(&conn, b"_NET_WM_STATE_ABOVE", false);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_window_state_atom(&conn),
+      r#type: x::ATOM_ATOM,
+      data: &[above_atom],
+    });
+    conn.flush().unwrap();
+    let window_info = get_window_information(&conn, &window, &screen, false);
+    assert!(window_info.always_on_top && !window_info.always_on_bottom);
+
+    let below_atom = get_atom(&conn, b"_NET_WM_STATE_BELOW", false);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: get_window_state_atom(&conn),
+      r#type: x::ATOM_ATOM,
+      data: &[below_atom],
+    });
+    conn.flush().unwrap();
+    let window_info = get_window_information(&conn, &window, &screen, false);
+    assert!(!window_info.always_on_top && window_info.always_on_bottom);
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_is_override_redirect_window() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    assert!(!is_override_redirect_window(&conn, window));
+    conn.send_request(&x::ChangeWindowAttributes {
+      window,
+      value_list: &[x::Cw::OverrideRedirect(true)],
+    });
+    conn.flush().unwrap();
+    assert!(is_override_redirect_window(&conn, window));
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_focus_chain() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    conn.send_request(&x::MapWindow { window });
+    let cookie = conn.send_request_checked(&x::SetInputFocus {
+      revert_to: x::InputFocus::PointerRoot,
+      focus: window,
+      time: x::CURRENT_TIME,
+    });
+    conn.check_request(cookie).unwrap();
+    assert_eq!(get_focus_chain(&conn), vec![window]);
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_transient_for() {
+    let conn = connection(None).unwrap();
+    let parent = create_test_window(&conn);
+    let window = create_test_window(&conn);
+    let root_window = conn.get_setup().roots().next().unwrap().root();
+    assert_eq!(get_transient_for(&conn, window, root_window), None);
+    conn.send_request(&x::ChangeProperty {
+      mode: x::PropMode::Replace,
+      window,
+      property: x::ATOM_WM_TRANSIENT_FOR,
+      r#type: x::ATOM_WINDOW,
+      data: &[parent],
+    });
+    conn.flush().unwrap();
+    assert_eq!(
+      get_transient_for(&conn, window, root_window),
+      Some(parent.resource_id())
+    );
+    destroy_test_window(&conn, window);
+    destroy_test_window(&conn, parent);
+  }
+
+  #[test]
+  fn test_get_window_manager_name_unknown_display() {
+    assert_eq!(
+      X11Api::new(Some(":4242".to_owned())).get_window_manager_name(),
+      ""
+    );
+  }
+
+  #[test]
+  fn test_close_window_not_managed() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    assert_eq!(
+      X11Api::default().close_window(window.resource_id()),
+      Ok(false)
+    );
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_get_window_geometry() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let api = X11Api::default();
+    let position = api
+      .get_window_geometry(window.resource_id())
+      .unwrap()
+      .unwrap();
+    assert_eq!((position.width, position.height), (100, 100));
+    destroy_test_window(&conn, window);
+    assert!(api
+      .get_window_geometry(window.resource_id())
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn test_window_state_not_managed() {
+    let conn = connection(None).unwrap();
+    let window = create_test_window(&conn);
+    let api = X11Api::default();
+    assert_eq!(api.minimize_window(window.resource_id()), Ok(false));
+    assert_eq!(api.maximize_window(window.resource_id()), Ok(false));
+    assert_eq!(api.restore_window(window.resource_id()), Ok(false));
+    destroy_test_window(&conn, window);
+  }
+
+  #[test]
+  fn test_default_screen_first() {
+    // Windows of all screens are listed, the screen of the display name (`:0.2`) comes first
+    assert_eq!(default_screen_first(vec![0, 1, 2], 2), vec![2, 0, 1]);
+    assert_eq!(default_screen_first(vec![0, 1, 2], 0), vec![0, 1, 2]);
+    // A screen missing from the server keeps the order of the server
+    assert_eq!(default_screen_first(vec![0, 1], 4), vec![0, 1]);
+    assert!(default_screen_first(Vec::<u32>::new(), 0).is_empty());
+  }
+
+  #[test]
+  fn test_first_screen() {
+    assert_eq!(
+      first_screen(std::iter::empty::<x::Window>()),
+      Err(XWinError::NoScreen)
+    );
+    let window: x::Window = XidNew::new(1);
+    assert_eq!(first_screen([window].into_iter()), Ok(window));
+  }
+
+  #[test]
+  fn test_window_type_atom_name() {
+    assert_eq!(
+      window_type_atom_name("dialog"),
+      "_NET_WM_WINDOW_TYPE_DIALOG"
+    );
+    assert_eq!(
+      window_type_atom_name("Utility"),
+      "_NET_WM_WINDOW_TYPE_UTILITY"
+    );
+  }
+
+  #[test]
+  fn test_workspace_from_desktop() {
+    let desktop_names = vec!["Main".to_owned(), "".to_owned()];
+    let workspace = workspace_from_desktop(0, &desktop_names);
+    assert_eq!(workspace.id, 0);
+    assert_eq!(workspace.name, Some("Main".to_owned()));
+    let workspace = workspace_from_desktop(1, &desktop_names);
+    assert_eq!(workspace.id, 1);
+    assert_eq!(workspace.name, None);
+    let workspace = workspace_from_desktop(u32::MAX, &desktop_names);
+    assert_eq!(workspace.id, -1);
+    assert_eq!(workspace.name, None);
+  }
+
+  #[test]
+  fn test_build_workspaces() {
+    let workspaces = build_workspaces(3, &["Main".to_owned()], Some(1));
+    assert_eq!(workspaces.len(), 3);
+    assert_eq!(workspaces[0].id, 0);
+    assert_eq!(workspaces[0].name, Some("Main".to_owned()));
+    assert!(!workspaces[0].is_current);
+    assert_eq!(workspaces[1].name, None);
+    assert!(workspaces[1].is_current);
+    assert!(build_workspaces(0, &[], None).is_empty());
+  }
+
+  #[test]
+  fn test_is_on_desktop() {
+    let workspace = WorkspaceInfo { id: 1, name: None };
+    let sticky = WorkspaceInfo { id: -1, name: None };
+    assert!(is_on_desktop(Some(&workspace), Some(1)));
+    assert!(!is_on_desktop(Some(&workspace), Some(0)));
+    assert!(is_on_desktop(Some(&sticky), Some(0)));
+    assert!(is_on_desktop(None, Some(0)));
+    assert!(is_on_desktop(Some(&workspace), None));
+  }
+
+  #[test]
+  fn test_contains_position() {
+    let outer = WindowPosition {
+      x: 0,
+      y: 0,
+      width: 100,
+      height: 100,
+      is_full_screen: false,
+    };
+    let mut inner = WindowPosition {
+      x: 10,
+      y: 10,
+      width: 50,
+      height: 50,
+      is_full_screen: false,
+    };
+    assert!(contains_position(&outer, &inner));
+    inner.x = 60;
+    assert!(!contains_position(&outer, &inner));
+  }
+
+  #[test]
+  fn test_parse_xft_dpi() {
+    assert_eq!(
+      parse_xft_dpi("Xft.antialias:\t1\nXft.dpi:\t192\nXft.hinting:\t1"),
+      Some(192.0)
+    );
+    assert_eq!(parse_xft_dpi("Xcursor.size:\t24"), None);
+    assert_eq!(parse_xft_dpi("Xft.dpi:\tabc"), None);
+  }
+
+  #[test]
+  fn test_is_on_external_display_internal_panel() {
+    let monitors = vec![
+      MonitorInfo {
+        id: 1,
+        is_primary: true,
+        name: "eDP-1".to_owned(),
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+        scale_factor: 1.0,
+      },
+      MonitorInfo {
+        id: 2,
+        is_primary: false,
+        name: "HDMI-1".to_owned(),
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+        scale_factor: 1.0,
+      },
+    ];
+    let mut position = WindowPosition {
+      x: 100,
+      y: 100,
+      width: 800,
+      height: 600,
+      is_full_screen: false,
+    };
+    assert_eq!(is_on_external_display(&position, &monitors), Some(false));
+    position.x = 2000;
+    assert_eq!(is_on_external_display(&position, &monitors), Some(true));
+    position.x = 10000;
+    assert_eq!(is_on_external_display(&position, &monitors), None);
   }
-  false
 }
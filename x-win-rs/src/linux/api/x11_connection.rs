@@ -0,0 +1,301 @@
+#![deny(unused_imports)]
+
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashSet,
+  ops::Deref,
+  os::fd::AsRawFd,
+  sync::mpsc,
+  thread,
+  time::{Duration, Instant},
+};
+
+use xcb::{x, CookieWithReplyChecked, Xid};
+
+use crate::XWinError;
+
+/**
+ * Connection to the X server where replies are awaited until an optional deadline.
+ * Errors of replies are never fatal, windows reported as bad (e.g. destroyed while they are walked) are recorded
+ */
+pub struct X11Connection {
+  conn: xcb::Connection,
+  default_screen: usize,
+  deadline: Option<Instant>,
+  timed_out: Cell<bool>,
+  bad_windows: RefCell<HashSet<u32>>,
+}
+
+impl X11Connection {
+  /**
+   * Open a connection to the X server of `display` (`$DISPLAY` when not set), the connection and the replies
+   * are awaited without limit when `timeout` is not set
+   */
+  pub fn connect(display: Option<&str>, timeout: Option<Duration>) -> Result<Self, XWinError> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let (conn, default_screen) = match timeout {
+      Some(timeout) => connect_with_timeout(display, timeout)?,
+      None => xcb::Connection::connect(display).map_err(|_| XWinError::NoDisplay)?,
+    };
+    Ok(Self {
+      conn,
+      default_screen: default_screen.max(0) as usize,
+      deadline,
+      timed_out: Cell::new(false),
+      bad_windows: RefCell::new(HashSet::new()),
+    })
+  }
+
+  /**
+   * Index of the screen selected by the display name (`1` for `:0.1`), `0` when no screen is given
+   */
+  pub fn default_screen(&self) -> usize {
+    self.default_screen
+  }
+
+  /**
+   * Wait for the reply of a request, once the deadline is passed every wait fails immediately
+   */
+  pub fn wait_for_reply<C>(&self, cookie: C) -> xcb::Result<C::Reply>
+  where
+    C: CookieWithReplyChecked,
+  {
+    let reply = self.wait_for_reply_until_deadline(cookie);
+    if let Err(xcb::Error::Protocol(xcb::ProtocolError::X(
+      x::Error::Window(error) | x::Error::Drawable(error),
+      _,
+    ))) = &reply
+    {
+      self.bad_windows.borrow_mut().insert(error.bad_value());
+    }
+    reply
+  }
+
+  /**
+   * To know if a reply failed because the window does not exist anymore
+   */
+  pub fn is_bad_window(&self, window: x::Window) -> bool {
+    self.bad_windows.borrow().contains(&window.resource_id())
+  }
+
+  fn wait_for_reply_until_deadline<C>(&self, cookie: C) -> xcb::Result<C::Reply>
+  where
+    C: CookieWithReplyChecked,
+  {
+    let Some(deadline) = self.deadline else {
+      return self.conn.wait_for_reply(cookie);
+    };
+    if !self.timed_out.get() {
+      self.conn.flush()?;
+      loop {
+        if let Some(reply) = self.conn.poll_for_reply(&cookie) {
+          return reply;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !wait_readable(self.conn.as_raw_fd(), remaining) {
+          break;
+        }
+      }
+    }
+    self.timed_out.set(true);
+    Err(xcb::Error::Connection(xcb::ConnError::Connection))
+  }
+
+  /**
+   * Wait at most `timeout` for the next event, `None` when no event has been received in time.
+   * Errors of requests without reply are received as `xcb::Error::Protocol` like events
+   */
+  pub fn wait_for_event_timeout(&self, timeout: Duration) -> xcb::Result<Option<xcb::Event>> {
+    self.conn.flush()?;
+    let deadline = Instant::now() + timeout;
+    loop {
+      if let Some(event) = self.conn.poll_for_event()? {
+        return Ok(Some(event));
+      }
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() || !wait_readable(self.conn.as_raw_fd(), remaining) {
+        return Ok(None);
+      }
+    }
+  }
+
+  /**
+   * Return `XWinError::Timeout` when a reply has not been received before the deadline
+   */
+  pub fn check_timeout(&self) -> Result<(), XWinError> {
+    if self.timed_out.get() {
+      Err(XWinError::Timeout)
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/**
+ * Connect on a helper thread to stop waiting after `timeout` (e.g. unresponsive remote display or SSH forwarding),
+ * a connection established after the timeout is closed by the thread
+ */
+fn connect_with_timeout(
+  display: Option<&str>,
+  timeout: Duration,
+) -> Result<(xcb::Connection, i32), XWinError> {
+  let (sender, receiver) = mpsc::channel();
+  let display = display.map(str::to_owned);
+  thread::spawn(move || {
+    let _ = sender.send(xcb::Connection::connect(display.as_deref()));
+  });
+  match receiver.recv_timeout(timeout) {
+    Ok(connection) => connection.map_err(|_| XWinError::NoDisplay),
+    Err(mpsc::RecvTimeoutError::Timeout) => Err(XWinError::Timeout),
+    Err(mpsc::RecvTimeoutError::Disconnected) => Err(XWinError::NoDisplay),
+  }
+}
+
+impl Deref for X11Connection {
+  type Target = xcb::Connection;
+
+  fn deref(&self) -> &Self::Target {
+    &self.conn
+  }
+}
+
+/**
+ * Wait until the file descriptor is readable, return `false` when `timeout` elapsed first
+ */
+fn wait_readable(fd: i32, timeout: Duration) -> bool {
+  let mut poll_fd = libc::pollfd {
+    fd,
+    events: libc::POLLIN,
+    revents: 0,
+  };
+  let timeout_ms = timeout.as_millis().clamp(1, i32::MAX as u128) as i32;
+  let result = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+  // Interrupted polls are retried by the caller with the remaining time
+  result > 0
+    || (result < 0 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_connect_unknown_display() {
+    assert!(matches!(
+      X11Connection::connect(Some(":4242"), None),
+      Err(XWinError::NoDisplay)
+    ));
+  }
+
+  #[test]
+  fn test_connect_timeout() {
+    // Accepted connections are never answered, the setup of the X server is awaited forever
+    let (listener, display_number) = (50..100)
+      .find_map(|display_number| {
+        std::net::TcpListener::bind(("127.0.0.1", 6000 + display_number))
+          .ok()
+          .map(|listener| (listener, display_number))
+      })
+      .unwrap();
+    let start = Instant::now();
+    assert!(matches!(
+      X11Connection::connect(
+        Some(&format!("127.0.0.1:{}", display_number)),
+        Some(Duration::from_millis(100))
+      ),
+      Err(XWinError::Timeout)
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(matches!(
+      X11Connection::connect(Some(":4242"), Some(Duration::from_secs(5))),
+      Err(XWinError::NoDisplay)
+    ));
+    drop(listener);
+  }
+
+  #[test]
+  fn test_bad_window_reply() {
+    let conn = X11Connection::connect(None, None).unwrap();
+    let screen = conn.get_setup().roots().next().unwrap();
+    let window: x::Window = conn.generate_id();
+    conn.send_request(&x::CreateWindow {
+      depth: x::COPY_FROM_PARENT as u8,
+      wid: window,
+      parent: screen.root(),
+      x: 0,
+      y: 0,
+      width: 100,
+      height: 100,
+      border_width: 0,
+      class: x::WindowClass::InputOutput,
+      visual: screen.root_visual(),
+      value_list: &[],
+    });
+    conn.send_request(&x::DestroyWindow { window });
+    let cookie = conn.send_request(&x::GetWindowAttributes { window });
+    assert!(conn.wait_for_reply(cookie).is_err());
+    assert!(conn.is_bad_window(window));
+    // Following requests are not affected by the error
+    let root = screen.root();
+    let cookie = conn.send_request(&x::GetWindowAttributes { window: root });
+    assert!(conn.wait_for_reply(cookie).is_ok());
+    assert!(!conn.is_bad_window(root));
+  }
+
+  #[test]
+  fn test_wait_for_event_timeout() {
+    let conn = X11Connection::connect(None, None).unwrap();
+    let root = conn.get_setup().roots().next().unwrap().root();
+    let cookie = conn.send_request_checked(&x::ChangeWindowAttributes {
+      window: root,
+      value_list: &[x::Cw::EventMask(x::EventMask::SUBSTRUCTURE_NOTIFY)],
+    });
+    conn.check_request(cookie).unwrap();
+    assert!(conn
+      .wait_for_event_timeout(Duration::from_millis(20))
+      .unwrap()
+      .is_none());
+
+    let other = X11Connection::connect(None, None).unwrap();
+    let screen = other.get_setup().roots().next().unwrap();
+    let window: x::Window = other.generate_id();
+    other.send_request(&x::CreateWindow {
+      depth: x::COPY_FROM_PARENT as u8,
+      wid: window,
+      parent: screen.root(),
+      x: 0,
+      y: 0,
+      width: 100,
+      height: 100,
+      border_width: 0,
+      class: x::WindowClass::InputOutput,
+      visual: screen.root_visual(),
+      value_list: &[],
+    });
+    other.flush().unwrap();
+    assert!(matches!(
+      conn.wait_for_event_timeout(Duration::from_secs(1)),
+      Ok(Some(xcb::Event::X(x::Event::CreateNotify(_))))
+    ));
+    other.send_request(&x::DestroyWindow { window });
+    other.flush().unwrap();
+  }
+
+  #[test]
+  fn test_wait_readable_timeout() {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let start = Instant::now();
+    assert!(!wait_readable(fds[0], Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert_eq!(
+      unsafe { libc::write(fds[1], b"x".as_ptr() as *const _, 1) },
+      1
+    );
+    assert!(wait_readable(fds[0], Duration::from_millis(20)));
+    unsafe {
+      libc::close(fds[0]);
+      libc::close(fds[1]);
+    }
+  }
+}
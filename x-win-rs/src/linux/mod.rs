@@ -1,18 +1,38 @@
 #![deny(unused_imports)]
 
 mod api;
-use crate::common::api::Api;
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+  common::{
+    api::Api,
+    x_win_struct::{
+      desktop_entry::DesktopEntry, extension_install_info::ExtensionInstallInfo,
+      extension_install_options::ExtensionInstallOptions, process_info::ProcessInfo,
+    },
+  },
+  XWinError,
+};
 
 use api::LinuxAPI;
 
 use self::api::APIGnome;
 
 pub fn init_platform_api() -> impl Api {
-  LinuxAPI {}
+  init_platform_api_with_display(None)
+}
+
+/**
+ * Init the API for the X display `display` (e.g. `:1`), `$DISPLAY` is used when not set
+ */
+pub fn init_platform_api_with_display(display: Option<String>) -> impl Api {
+  LinuxAPI::new(display)
 }
 
-pub fn gnome_install_extension() -> bool {
-  LinuxAPI::install_extension()
+pub fn gnome_install_extension(
+  options: &ExtensionInstallOptions,
+) -> Result<ExtensionInstallInfo, XWinError> {
+  LinuxAPI::install_extension(options)
 }
 
 pub fn gnome_uninstall_extension() -> bool {
@@ -26,3 +46,34 @@ pub fn gnome_enable_extension() -> bool {
 pub fn gnome_disable_extension() -> bool {
   LinuxAPI::disable_extension()
 }
+
+pub fn os_version() -> String {
+  api::os_version()
+}
+
+pub fn session_type() -> String {
+  api::session_type()
+}
+
+pub fn window_manager_name() -> String {
+  api::window_manager_name()
+}
+
+pub fn get_process_cpu_time(pid: u32) -> Option<Duration> {
+  api::get_process_cpu_time(pid)
+}
+
+pub fn get_process_env(pid: u32) -> HashMap<String, String> {
+  api::get_process_env(pid)
+}
+
+/**
+ * Resolve the desktop entry from the WM_CLASS class (`name`) and the executable name of the process
+ */
+pub fn get_desktop_entry(process_info: &ProcessInfo) -> DesktopEntry {
+  api::get_desktop_entry(&process_info.name, &process_info.exec_name)
+}
+
+pub fn get_permissions() -> Vec<(String, bool)> {
+  api::get_permissions()
+}
@@ -1,37 +1,49 @@
 #![deny(unused_imports)]
 
+use std::collections::HashMap;
 use std::process::Command;
+use std::time::Duration;
 
 use base64::Engine;
 use cocoa::appkit::NSScreen;
 use cocoa::base::{id, nil};
-use cocoa::foundation::{NSRect, NSString, NSURL};
-use core_foundation::array::{CFArrayGetCount, CFArrayGetValueAtIndex};
-use core_foundation::base::{CFType, TCFType};
+use cocoa::foundation::{NSPoint, NSRect, NSString, NSURL};
+use core_foundation::array::{CFArray, CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayRef};
+use core_foundation::base::{CFType, CFTypeRef, TCFType};
 use core_foundation::boolean::CFBoolean;
 
 use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
 
 use core_foundation::number::CFNumber;
-use core_foundation::string::CFString;
+use core_foundation::string::{CFString, CFStringRef};
 use core_graphics::display::{
   kCGWindowListExcludeDesktopElements, kCGWindowListOptionIncludingWindow,
-  kCGWindowListOptionOnScreenOnly, CGWindowListCopyWindowInfo,
+  kCGWindowListOptionOnScreenOnly, CGDirectDisplayID, CGDisplay, CGGetDisplaysWithRect, CGRectNull,
+  CGWindowListCopyWindowInfo,
 };
-use core_graphics::geometry::CGRect;
+use core_graphics::geometry::{CGPoint, CGRect, CGSize};
 use core_graphics::window::{
-  kCGWindowBounds, kCGWindowIsOnscreen, kCGWindowLayer, kCGWindowMemoryUsage, kCGWindowName,
-  kCGWindowNumber, kCGWindowOwnerName, kCGWindowOwnerPID,
+  create_image, kCGWindowAlpha, kCGWindowBounds, kCGWindowImageBoundsIgnoreFraming,
+  kCGWindowImageNominalResolution, kCGWindowIsOnscreen, kCGWindowLayer, kCGWindowMemoryUsage,
+  kCGWindowName, kCGWindowNumber, kCGWindowOwnerName, kCGWindowOwnerPID, kCGWindowWorkspace,
 };
 
 use crate::common::x_win_struct::icon_info::IconInfo;
 use crate::common::{
-  api::{empty_entity, os_name, Api},
+  api::{
+    empty_entity, get_frontmost_window_at, get_window_monitor, os_name, parse_environ,
+    sort_front_to_back, Api,
+  },
+  capture::to_raw_image,
   x_win_struct::{
-    process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
-    window_position::WindowPosition,
+    active_window_options::ActiveWindowOptions, focused_element::FocusedElement,
+    monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions,
+    permission_status::PermissionStatus, process_info::ProcessInfo, raw_image::RawImage,
+    usage_info::UsageInfo, window_info::WindowInfo, window_position::WindowPosition,
+    workspace_info::WorkspaceInfo,
   },
 };
+use crate::XWinError;
 
 use objc::runtime::{BOOL, NO};
 
@@ -41,18 +53,235 @@ pub struct MacosAPI {}
  * Impl. for Darwin system
  */
 impl Api for MacosAPI {
-  fn get_active_window(&self) -> WindowInfo {
-    let windows: Vec<WindowInfo> = get_windows_informations(true);
+  fn get_active_window(&self, options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError> {
+    let windows: Vec<WindowInfo> =
+      get_windows_informations(true, options.include_url, false, &mut |_window_info| true)?;
     if !windows.is_empty() {
-      let t: &WindowInfo = windows.first().unwrap();
-      t.clone() as WindowInfo
+      let mut t: WindowInfo = windows.first().unwrap().clone();
+      if options.include_content_language && unsafe { AXIsProcessTrusted() } {
+        t.content_language = get_content_language(t.info.process_id);
+      }
+      if options.include_focused_element && unsafe { AXIsProcessTrusted() } {
+        t.focused_element = get_focused_element(t.info.process_id);
+      }
+      Ok(t)
     } else {
-      empty_entity()
+      Ok(empty_entity())
+    }
+  }
+
+  fn get_active_window_id(&self) -> Result<u32, XWinError> {
+    get_frontmost_window_id()
+  }
+
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> {
+    self.get_open_windows_filtered(options, &mut |_window_info| true)
+  }
+
+  fn get_open_windows_filtered(
+    &self,
+    options: &OpenWindowsOptions,
+    filter: &mut dyn FnMut(&WindowInfo) -> bool,
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut windows = get_windows_informations(
+      false,
+      options.include_url,
+      options.exclude_desktop_shell,
+      filter,
+    )?;
+    // Memory is part of the window list returned by CoreGraphics, there is no read to skip
+    if !options.include_usage {
+      for window_info in windows.iter_mut() {
+        window_info.usage.memory = 0;
+      }
+    }
+    if !options.raw_order {
+      sort_front_to_back(&mut windows);
+    }
+    Ok(windows)
+  }
+
+  fn find_windows_by_ax_identifier(&self, identifier: &str) -> Result<Vec<WindowInfo>, XWinError> {
+    if !unsafe { AXIsProcessTrusted() } {
+      return Err(XWinError::PermissionDenied);
+    }
+    let windows = get_windows_informations(false, false, false, &mut |_window_info| true)?;
+    let mut process_ids: Vec<u32> = windows
+      .iter()
+      .map(|window_info| window_info.info.process_id)
+      .collect();
+    process_ids.sort_unstable();
+    process_ids.dedup();
+    let window_ids: Vec<u32> = process_ids
+      .into_iter()
+      .flat_map(|process_id| get_ax_window_ids(process_id, identifier))
+      .collect();
+    Ok(
+      windows
+        .into_iter()
+        .filter(|window_info| window_ids.contains(&window_info.id))
+        .collect(),
+    )
+  }
+
+  fn capture_window(
+    &self,
+    window_id: u32,
+    max_dimension: Option<u32>,
+  ) -> Result<RawImage, XWinError> {
+    if !unsafe { CGPreflightScreenCaptureAccess() } {
+      return Err(XWinError::PermissionDenied);
     }
+    // Minimized windows are not on screen and would be captured as a blank image
+    if !is_window_on_screen(window_id) {
+      return Err(XWinError::PlatformError(
+        "window is minimized or not on screen".to_owned(),
+      ));
+    }
+    capture_cg_window(window_id, max_dimension)
+  }
+
+  fn capture_windows(
+    &self,
+    window_ids: &[u32],
+    max_dimension: Option<u32>,
+  ) -> Result<Vec<(u32, RawImage)>, XWinError> {
+    if !unsafe { CGPreflightScreenCaptureAccess() } {
+      return Err(XWinError::PermissionDenied);
+    }
+    // On screen windows are listed once for the whole batch
+    let on_screen_window_ids = get_on_screen_window_ids();
+    Ok(
+      window_ids
+        .iter()
+        .filter(|window_id| on_screen_window_ids.contains(window_id))
+        .filter_map(|window_id| {
+          capture_cg_window(*window_id, max_dimension)
+            .ok()
+            .map(|image| (*window_id, image))
+        })
+        .collect(),
+    )
+  }
+
+  fn close_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if !unsafe { AXIsProcessTrusted() } {
+      return Err(XWinError::PermissionDenied);
+    }
+    let Some(ax_window) = get_ax_window(window_id) else {
+      return Ok(false);
+    };
+    // Press the close button like the user would do, the application can still ask to save changes
+    let Some(close_button) = copy_ax_attribute(ax_window.as_CFTypeRef(), "AXCloseButton") else {
+      return Ok(false);
+    };
+    let action = CFString::new("AXPress");
+    let result = unsafe {
+      AXUIElementPerformAction(close_button.as_CFTypeRef(), action.as_concrete_TypeRef())
+    };
+    Ok(result == 0)
   }
 
-  fn get_open_windows(&self) -> Vec<WindowInfo> {
-    get_windows_informations(false)
+  fn set_window_position(
+    &self,
+    window_id: u32,
+    position: &WindowPosition,
+  ) -> Result<bool, XWinError> {
+    if !unsafe { AXIsProcessTrusted() } {
+      return Err(XWinError::PermissionDenied);
+    }
+    let Some(ax_window) = get_ax_window(window_id) else {
+      return Ok(false);
+    };
+    let is_full_screen = copy_ax_attribute(ax_window.as_CFTypeRef(), "AXFullScreen")
+      .and_then(|value| value.downcast::<CFBoolean>())
+      .map(|value| value == CFBoolean::true_value())
+      .unwrap_or(false);
+    if is_full_screen != position.is_full_screen {
+      let value = if position.is_full_screen {
+        CFBoolean::true_value()
+      } else {
+        CFBoolean::false_value()
+      };
+      if !set_ax_attribute(
+        ax_window.as_CFTypeRef(),
+        "AXFullScreen",
+        value.as_CFTypeRef(),
+      ) {
+        return Ok(false);
+      }
+    }
+    if position.is_full_screen {
+      return Ok(true);
+    }
+
+    let point = CGPoint::new(position.x as f64, position.y as f64);
+    let size = CGSize::new(position.width as f64, position.height as f64);
+    let point = unsafe { AXValueCreate(AX_VALUE_CG_POINT_TYPE, (&point as *const CGPoint).cast()) };
+    let size = unsafe { AXValueCreate(AX_VALUE_CG_SIZE_TYPE, (&size as *const CGSize).cast()) };
+    if point.is_null() || size.is_null() {
+      return Ok(false);
+    }
+    let point = unsafe { CFType::wrap_under_create_rule(point) };
+    let size = unsafe { CFType::wrap_under_create_rule(size) };
+    Ok(
+      set_ax_attribute(ax_window.as_CFTypeRef(), "AXPosition", point.as_CFTypeRef())
+        && set_ax_attribute(ax_window.as_CFTypeRef(), "AXSize", size.as_CFTypeRef()),
+    )
+  }
+
+  fn get_window_geometry(&self, window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+    // Same bounds as the window list, accessibility permission is not required
+    let bounds = get_window_description(window_id)
+      .and_then(|window| {
+        window
+          .find(unsafe { kCGWindowBounds })
+          .map(|bounds| bounds.clone())
+      })
+      .and_then(|bounds| bounds.downcast::<CFDictionary>())
+      .and_then(|bounds| CGRect::from_dict_representation(&bounds.to_untyped()));
+    Ok(bounds.map(|bounds| WindowPosition {
+      x: bounds.origin.x as i32,
+      y: bounds.origin.y as i32,
+      width: bounds.size.width as i32,
+      height: bounds.size.height as i32,
+      is_full_screen: is_full_screen(bounds, get_screen_rect()),
+    }))
+  }
+
+  fn minimize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    set_ax_window_minimized(window_id, true)
+  }
+
+  fn maximize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    if !unsafe { AXIsProcessTrusted() } {
+      return Err(XWinError::PermissionDenied);
+    }
+    let Some(ax_window) = get_ax_window(window_id) else {
+      return Ok(false);
+    };
+    // The zoom button fills the screen without entering a fullscreen space
+    let Some(zoom_button) = copy_ax_attribute(ax_window.as_CFTypeRef(), "AXZoomButton") else {
+      return Ok(false);
+    };
+    let action = CFString::new("AXPress");
+    let result =
+      unsafe { AXUIElementPerformAction(zoom_button.as_CFTypeRef(), action.as_concrete_TypeRef()) };
+    Ok(result == 0)
+  }
+
+  fn restore_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    set_ax_window_minimized(window_id, false)
+  }
+
+  fn get_window_under_cursor(&self) -> Result<Option<WindowInfo>, XWinError> {
+    // Cocoa coordinates start from the bottom of the main display, windows bounds from the top
+    let location: NSPoint = unsafe { msg_send![class!(NSEvent), mouseLocation] };
+    let x = location.x.floor() as i32;
+    let y = (CGDisplay::main().bounds().size.height - location.y).floor() as i32;
+    // Menu bar and dock are above the normal window layer
+    let windows = get_windows_informations(false, false, true, &mut |_window_info| true)?;
+    Ok(get_frontmost_window_at(windows, x, y))
   }
 
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo {
@@ -92,18 +321,38 @@ impl Api for MacosAPI {
       width: 0,
     }
   }
+
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError> {
+    Ok(get_cg_monitors())
+  }
 }
 
-fn get_windows_informations(only_active: bool) -> Vec<WindowInfo> {
+/**
+ * Windows listed by CoreGraphics from front to back and accepted by `accept`, it is called before the process
+ * details (command line, user, start time) and the url are read, with all other fields set
+ */
+fn get_windows_informations(
+  only_active: bool,
+  include_url: bool,
+  exclude_desktop_shell: bool,
+  accept: &mut dyn FnMut(&WindowInfo) -> bool,
+) -> Result<Vec<WindowInfo>, XWinError> {
   let mut windows: Vec<WindowInfo> = Vec::new();
 
   let options = kCGWindowListOptionOnScreenOnly
     | kCGWindowListExcludeDesktopElements
     | kCGWindowListOptionIncludingWindow;
   let window_list_info = unsafe { CGWindowListCopyWindowInfo(options, 0) };
+  if window_list_info.is_null() {
+    return Err(XWinError::PlatformError(
+      "CGWindowListCopyWindowInfo returned no window list".to_owned(),
+    ));
+  }
   let windows_count: isize = unsafe { CFArrayGetCount(window_list_info) };
 
   let screen_rect = get_screen_rect();
+  let monitors = get_cg_monitors();
+  let mut has_focused_window = false;
 
   for idx in 0..windows_count {
     let dref: CFDictionaryRef =
@@ -126,6 +375,10 @@ fn get_windows_informations(only_active: bool) -> Vec<WindowInfo> {
     if window_layer.lt(&CFNumber::from(0)) || window_layer.gt(&CFNumber::from(100)) {
       continue;
     }
+    // Menu bar, status items and floating shell panels are above the normal window layer
+    if exclude_desktop_shell && window_layer.ne(&CFNumber::from(0)) {
+      continue;
+    }
 
     let bounds = cfd.get(unsafe { kCGWindowBounds });
     let bounds: CFDictionary = bounds.downcast::<CFDictionary>().unwrap();
@@ -164,7 +417,9 @@ fn get_windows_informations(only_active: bool) -> Vec<WindowInfo> {
       std::str::from_utf8(unsafe { std::ffi::CStr::from_ptr(bundle_identifier).to_bytes() })
         .unwrap();
 
-    if bundle_identifier.eq("com.apple.dock") {
+    if bundle_identifier.eq("com.apple.dock")
+      || (exclude_desktop_shell && is_desktop_shell_bundle_id(bundle_identifier))
+    {
       continue;
     }
 
@@ -187,61 +442,195 @@ fn get_windows_informations(only_active: bool) -> Vec<WindowInfo> {
       .to_str()
       .unwrap();
 
+    // Spaces are only exposed by kCGWindowWorkspace on older macOS versions
+    let workspace = cfd
+      .find(unsafe { kCGWindowWorkspace })
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_i32())
+      .map(|id| WorkspaceInfo { id, name: None });
+
     let memory = cfd.get(unsafe { kCGWindowMemoryUsage });
     let memory = memory.downcast::<CFNumber>().unwrap().to_i64().unwrap();
 
+    let opacity = cfd
+      .find(unsafe { kCGWindowAlpha })
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_f64())
+      .unwrap_or(1.0);
+
     let id = cfd.get(unsafe { kCGWindowNumber });
     let id = id.downcast::<CFNumber>().unwrap().to_i64().unwrap();
 
-    let mut url: String = String::new();
+    // Window list is ordered from front to back, the first window of the active app is its key window
+    let is_focused = !is_not_active && !has_focused_window;
+    has_focused_window |= is_focused;
 
-    if is_browser_bundle_id(bundle_identifier) {
-      let mut command = format!(
-        "tell app id \"{}\" to get URL of active tab of front window",
-        bundle_identifier
-      );
-      if is_from_document(bundle_identifier) {
-        command = format!(
-          "tell app id \"{}\" to get URL of front document",
-          bundle_identifier
-        );
-      }
-      // else if is_firefox_browser(&bundle_identifier)
-      // {
-      //   command = format!("tell app id \"{}\" to get URL of active tab of front window", bundle_identifier);
-      // }
-      url = execute_applescript(&command);
-    }
+    let position = WindowPosition {
+      x: bounds.origin.x as i32,
+      y: bounds.origin.y as i32,
+      width: bounds.size.width as i32,
+      height: bounds.size.height as i32,
+      is_full_screen: is_full_screen(bounds, screen_rect),
+    };
+    let monitor = get_window_monitor(&position, &monitors);
 
-    windows.push(WindowInfo {
+    let mut window_info = WindowInfo {
       id: id as u32,
       os: os_name(),
       title,
-      position: WindowPosition {
-        x: bounds.origin.x as i32,
-        y: bounds.origin.y as i32,
-        width: bounds.size.width as i32,
-        height: bounds.size.height as i32,
-        is_full_screen: is_full_screen(bounds, screen_rect),
-      },
+      // CoreGraphics bounds already include the title bar
+      frame: Some(position.clone()),
+      position,
       info: ProcessInfo {
         process_id: process_id as u32,
         path: path.to_owned(),
         name: app_name.to_owned(),
         exec_name: exec_name.to_owned(),
+        command_line: "".to_owned(),
+        user: "".to_owned(),
+        start_time: 0,
+        bundle_id: bundle_identifier.to_owned(),
+        app_name: get_localized_name(app),
+        instance_name: "".to_owned(),
       },
       usage: UsageInfo {
         memory: memory as u32,
+        cpu: 0.0,
       },
-      url,
-    });
+      url: "".to_owned(),
+      on_external_display: is_on_external_display(bounds),
+      // Window list is ordered from front to back
+      z_order: (windows_count - 1 - idx) as u32,
+      workspace,
+      content_language: None,
+      monitor,
+      is_focused,
+      is_active: is_focused,
+      opacity,
+      // CoreGraphics does not expose the parent of a window
+      parent_id: None,
+      // Only windows on screen are listed
+      is_minimized: false,
+      // Windows that are not on screen are skipped
+      is_visible: true,
+      role: "".to_owned(),
+      focused_element: None,
+      // Floating and utility windows use a level above the normal window layer
+      always_on_top: window_layer.gt(&CFNumber::from(0)),
+      // Windows below the normal window layer are skipped
+      always_on_bottom: false,
+      native_handle: id as u64,
+    };
+
+    // Process details and url of rejected windows are never read
+    if accept(&window_info) {
+      window_info.info.command_line = get_process_command_line(process_id as i32);
+      window_info.info.user = get_process_user(process_id as i32);
+      window_info.info.start_time = get_process_start_time(process_id as i32);
+      if include_url && is_browser_bundle_id(bundle_identifier) {
+        let mut command = format!(
+          "tell app id \"{}\" to get URL of active tab of front window",
+          bundle_identifier
+        );
+        if is_from_document(bundle_identifier) {
+          command = format!(
+            "tell app id \"{}\" to get URL of front document",
+            bundle_identifier
+          );
+        }
+        // else if is_firefox_browser(&bundle_identifier)
+        // {
+        //   command = format!("tell app id \"{}\" to get URL of active tab of front window", bundle_identifier);
+        // }
+        window_info.url = execute_applescript(&command);
+      }
+      windows.push(window_info);
+    }
 
     if only_active && is_not_active {
       break;
     }
   }
 
-  windows
+  Ok(windows)
+}
+
+/**
+ * Id of the front window of the frontmost application with the same filters as `get_windows_informations`,
+ * without reading the details of the windows. `0` when the frontmost application has no window on screen
+ */
+fn get_frontmost_window_id() -> Result<u32, XWinError> {
+  let process_id: i32 = unsafe {
+    let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let app: id = msg_send![workspace, frontmostApplication];
+    if app == nil {
+      return Ok(0);
+    }
+    msg_send![app, processIdentifier]
+  };
+
+  let options = kCGWindowListOptionOnScreenOnly
+    | kCGWindowListExcludeDesktopElements
+    | kCGWindowListOptionIncludingWindow;
+  let window_list_info = unsafe { CGWindowListCopyWindowInfo(options, 0) };
+  if window_list_info.is_null() {
+    return Err(XWinError::PlatformError(
+      "CGWindowListCopyWindowInfo returned no window list".to_owned(),
+    ));
+  }
+  let window_list: CFArray<CFDictionary<CFString, CFType>> =
+    unsafe { CFArray::wrap_under_create_rule(window_list_info) };
+
+  // Window list is ordered from front to back
+  for cfd in window_list.iter() {
+    let owner_pid = cfd
+      .find(unsafe { kCGWindowOwnerPID })
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_i32());
+    if owner_pid != Some(process_id) {
+      continue;
+    }
+    let is_on_screen = cfd
+      .find(unsafe { kCGWindowIsOnscreen })
+      .and_then(|value| value.downcast::<CFBoolean>())
+      .is_some_and(|value| value == CFBoolean::true_value());
+    let layer = cfd
+      .find(unsafe { kCGWindowLayer })
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_i32())
+      .unwrap_or(-1);
+    if !is_on_screen || !(0..=100).contains(&layer) {
+      continue;
+    }
+    let bounds = cfd
+      .find(unsafe { kCGWindowBounds })
+      .and_then(|value| value.downcast::<CFDictionary>())
+      .and_then(|value| CGRect::from_dict_representation(&value.to_untyped()));
+    if !bounds.is_some_and(|bounds| bounds.size.height >= 50.0 && bounds.size.width >= 50.0) {
+      continue;
+    }
+    if let Some(window_id) = cfd
+      .find(unsafe { kCGWindowNumber })
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_i64())
+    {
+      return Ok(window_id as u32);
+    }
+  }
+  Ok(0)
+}
+
+fn is_desktop_shell_bundle_id(bundle_id: &str) -> bool {
+  matches!(
+    bundle_id,
+    "com.apple.controlcenter"
+      | "com.apple.notificationcenterui"
+      | "com.apple.systemuiserver"
+      | "com.apple.WindowManager"
+      | "com.apple.Spotlight"
+      | "com.apple.loginwindow"
+      | "com.apple.screencaptureui"
+  )
 }
 
 fn is_browser_bundle_id(bundle_id: &str) -> bool {
@@ -293,6 +682,541 @@ fn is_from_document(bundle_id: &str) -> bool {
 //   }
 // }
 
+/**
+ * Ratio to convert Mach absolute time units into nanoseconds
+ */
+#[repr(C)]
+struct MachTimebaseInfo {
+  numer: u32,
+  denom: u32,
+}
+
+extern "C" {
+  fn mach_timebase_info(info: *mut MachTimebaseInfo) -> libc::c_int;
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+  fn CGPreflightScreenCaptureAccess() -> bool;
+  fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+  fn AXIsProcessTrusted() -> bool;
+  fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+  static kAXTrustedCheckOptionPrompt: CFStringRef;
+  fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+  fn AXUIElementCopyAttributeValue(
+    element: CFTypeRef,
+    attribute: CFStringRef,
+    value: *mut CFTypeRef,
+  ) -> i32;
+  fn AXUIElementSetAttributeValue(
+    element: CFTypeRef,
+    attribute: CFStringRef,
+    value: CFTypeRef,
+  ) -> i32;
+  fn AXUIElementPerformAction(element: CFTypeRef, action: CFStringRef) -> i32;
+  fn AXValueCreate(value_type: u32, value: *const std::ffi::c_void) -> CFTypeRef;
+  fn AXValueGetValue(value: CFTypeRef, value_type: u32, value_ptr: *mut std::ffi::c_void) -> bool;
+  fn _AXUIElementGetWindow(element: CFTypeRef, window_id: *mut u32) -> i32;
+}
+
+/**
+ * Change the AXMinimized attribute of the window with accessibility
+ */
+fn set_ax_window_minimized(window_id: u32, is_minimized: bool) -> Result<bool, XWinError> {
+  if !unsafe { AXIsProcessTrusted() } {
+    return Err(XWinError::PermissionDenied);
+  }
+  let Some(ax_window) = get_ax_window(window_id) else {
+    return Ok(false);
+  };
+  let value = if is_minimized {
+    CFBoolean::true_value()
+  } else {
+    CFBoolean::false_value()
+  };
+  Ok(set_ax_attribute(
+    ax_window.as_CFTypeRef(),
+    "AXMinimized",
+    value.as_CFTypeRef(),
+  ))
+}
+
+/**
+ * Copy an attribute value of an accessibility element
+ */
+fn copy_ax_attribute(element: CFTypeRef, attribute: &str) -> Option<CFType> {
+  let attribute = CFString::new(attribute);
+  let mut value: CFTypeRef = std::ptr::null();
+  let result =
+    unsafe { AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value) };
+  if result == 0 && !value.is_null() {
+    Some(unsafe { CFType::wrap_under_create_rule(value) })
+  } else {
+    None
+  }
+}
+
+/**
+ * kAXValueCGPointType and kAXValueCGSizeType of AXValueType
+ */
+const AX_VALUE_CG_POINT_TYPE: u32 = 1;
+const AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+
+/**
+ * Set an attribute value of an accessibility element
+ */
+fn set_ax_attribute(element: CFTypeRef, attribute: &str, value: CFTypeRef) -> bool {
+  let attribute = CFString::new(attribute);
+  unsafe { AXUIElementSetAttributeValue(element, attribute.as_concrete_TypeRef(), value) == 0 }
+}
+
+/**
+ * Get accessibility element of the window from its window number
+ */
+fn get_ax_window(window_id: u32) -> Option<CFType> {
+  let process_id = get_window_description(window_id)?
+    .find(unsafe { kCGWindowOwnerPID })
+    .map(|value| value.clone())
+    .and_then(|value| value.downcast::<CFNumber>())
+    .and_then(|value| value.to_i32())?;
+  get_ax_windows(process_id as u32)
+    .into_iter()
+    .find(|ax_window| get_ax_window_id(ax_window) == Some(window_id))
+}
+
+/**
+ * Get accessibility elements of the application windows
+ */
+fn get_ax_windows(process_id: u32) -> Vec<CFType> {
+  let application = unsafe { AXUIElementCreateApplication(process_id as i32) };
+  if application.is_null() {
+    return Vec::new();
+  }
+  let application = unsafe { CFType::wrap_under_create_rule(application) };
+  let Some(ax_windows) = copy_ax_attribute(application.as_CFTypeRef(), "AXWindows") else {
+    return Vec::new();
+  };
+  let ax_windows = ax_windows.as_CFTypeRef() as CFArrayRef;
+  (0..unsafe { CFArrayGetCount(ax_windows) })
+    .map(|idx| unsafe {
+      CFType::wrap_under_get_rule(CFArrayGetValueAtIndex(ax_windows, idx) as CFTypeRef)
+    })
+    .collect()
+}
+
+/**
+ * Get window number of an accessibility window element
+ */
+fn get_ax_window_id(ax_window: &CFType) -> Option<u32> {
+  let mut window_id: u32 = 0;
+  if unsafe { _AXUIElementGetWindow(ax_window.as_CFTypeRef(), &mut window_id) } == 0 {
+    Some(window_id)
+  } else {
+    None
+  }
+}
+
+/**
+ * Get window numbers of the application windows having the AX identifier
+ */
+fn get_ax_window_ids(process_id: u32, identifier: &str) -> Vec<u32> {
+  get_ax_windows(process_id)
+    .iter()
+    .filter(|ax_window| {
+      copy_ax_attribute(ax_window.as_CFTypeRef(), "AXIdentifier")
+        .and_then(|value| value.downcast::<CFString>())
+        .map(|value| value.to_string())
+        .as_deref()
+        == Some(identifier)
+    })
+    .filter_map(get_ax_window_id)
+    .collect()
+}
+
+/**
+ * Maximum number of ancestors visited to find the language of the focused element
+ */
+const AX_MAX_PARENTS: usize = 32;
+
+/**
+ * Get language of the focused element of the application from its AXLanguage attribute (or the one of its ancestors)
+ */
+fn get_content_language(process_id: u32) -> Option<String> {
+  let application = unsafe { AXUIElementCreateApplication(process_id as i32) };
+  if application.is_null() {
+    return None;
+  }
+  let application = unsafe { CFType::wrap_under_create_rule(application) };
+  let mut element = copy_ax_attribute(application.as_CFTypeRef(), "AXFocusedUIElement");
+  for _ in 0..AX_MAX_PARENTS {
+    let current = element?;
+    let language = copy_ax_attribute(current.as_CFTypeRef(), "AXLanguage")
+      .and_then(|value| value.downcast::<CFString>())
+      .map(|value| value.to_string())
+      .filter(|value| !value.is_empty());
+    if language.is_some() {
+      return language;
+    }
+    element = copy_ax_attribute(current.as_CFTypeRef(), "AXParent");
+  }
+  None
+}
+
+/**
+ * Get the focused element of the application with its AXRole, AXTitle (or AXDescription) and frame
+ */
+fn get_focused_element(process_id: u32) -> Option<FocusedElement> {
+  let application = unsafe { AXUIElementCreateApplication(process_id as i32) };
+  if application.is_null() {
+    return None;
+  }
+  let application = unsafe { CFType::wrap_under_create_rule(application) };
+  let element = copy_ax_attribute(application.as_CFTypeRef(), "AXFocusedUIElement")?;
+  let element = element.as_CFTypeRef();
+  let string_attribute = |attribute: &str| {
+    copy_ax_attribute(element, attribute)
+      .and_then(|value| value.downcast::<CFString>())
+      .map(|value| value.to_string())
+      .filter(|value| !value.is_empty())
+  };
+  let mut origin = CGPoint::new(0.0, 0.0);
+  let mut size = CGSize::new(0.0, 0.0);
+  if let Some(position) = copy_ax_attribute(element, "AXPosition") {
+    unsafe {
+      AXValueGetValue(
+        position.as_CFTypeRef(),
+        AX_VALUE_CG_POINT_TYPE,
+        (&mut origin as *mut CGPoint).cast(),
+      )
+    };
+  }
+  if let Some(ax_size) = copy_ax_attribute(element, "AXSize") {
+    unsafe {
+      AXValueGetValue(
+        ax_size.as_CFTypeRef(),
+        AX_VALUE_CG_SIZE_TYPE,
+        (&mut size as *mut CGSize).cast(),
+      )
+    };
+  }
+  Some(FocusedElement::new(
+    string_attribute("AXRole").unwrap_or_default(),
+    string_attribute("AXTitle")
+      .or_else(|| string_attribute("AXDescription"))
+      .unwrap_or_default(),
+    WindowPosition {
+      x: origin.x as i32,
+      y: origin.y as i32,
+      width: size.width as i32,
+      height: size.height as i32,
+      is_full_screen: false,
+    },
+  ))
+}
+
+/**
+ * Get description of the window from its window number
+ */
+fn get_window_description(window_id: u32) -> Option<CFDictionary<CFString, CFType>> {
+  let window_list_info =
+    unsafe { CGWindowListCopyWindowInfo(kCGWindowListOptionIncludingWindow, window_id) };
+  if window_list_info.is_null() {
+    return None;
+  }
+  let window_list: CFArray<CFDictionary<CFString, CFType>> =
+    unsafe { CFArray::wrap_under_create_rule(window_list_info) };
+  let window = window_list.get(0)?;
+  Some(unsafe { CFDictionary::wrap_under_get_rule(window.as_concrete_TypeRef()) })
+}
+
+/**
+ * Capture the RGBA pixels of the window, the window has to be on screen
+ */
+fn capture_cg_window(window_id: u32, max_dimension: Option<u32>) -> Result<RawImage, XWinError> {
+  let image = create_image(
+    unsafe { CGRectNull },
+    kCGWindowListOptionIncludingWindow,
+    window_id,
+    kCGWindowImageBoundsIgnoreFraming | kCGWindowImageNominalResolution,
+  )
+  .ok_or_else(|| XWinError::PlatformError("failed to capture the window".to_owned()))?;
+  if image.bits_per_pixel() != 32 || image.bits_per_component() != 8 {
+    return Err(XWinError::PlatformError(
+      "unsupported window image format".to_owned(),
+    ));
+  }
+
+  let (width, height, bytes_per_row) = (image.width(), image.height(), image.bytes_per_row());
+  let data = image.data();
+  let bytes = data.bytes();
+  let mut rgba: Vec<u8> = Vec::with_capacity(width * height * 4);
+  // Window images are stored as BGRA with premultiplied alpha first in little endian
+  for y in 0..height {
+    let row = &bytes[y * bytes_per_row..y * bytes_per_row + width * 4];
+    for bgra in row.chunks_exact(4) {
+      rgba.extend_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+    }
+  }
+  to_raw_image(width as u32, height as u32, rgba, max_dimension)
+}
+
+/**
+ * Check if the window is currently on screen (not minimized or on another space)
+ */
+fn is_window_on_screen(window_id: u32) -> bool {
+  get_window_description(window_id)
+    .and_then(|window| {
+      window
+        .find(unsafe { kCGWindowIsOnscreen })
+        .and_then(|value| value.downcast::<CFBoolean>())
+    })
+    .map(|value| value == CFBoolean::true_value())
+    .unwrap_or(false)
+}
+
+/**
+ * Get ids of windows currently on screen
+ */
+fn get_on_screen_window_ids() -> Vec<u32> {
+  let window_list_info = unsafe { CGWindowListCopyWindowInfo(kCGWindowListOptionOnScreenOnly, 0) };
+  if window_list_info.is_null() {
+    return Vec::new();
+  }
+  let window_list: CFArray<CFDictionary<CFString, CFType>> =
+    unsafe { CFArray::wrap_under_create_rule(window_list_info) };
+  window_list
+    .iter()
+    .filter_map(|window| {
+      window
+        .find(unsafe { kCGWindowNumber })
+        .and_then(|value| value.downcast::<CFNumber>())
+        .and_then(|value| value.to_i64())
+        .map(|window_id| window_id as u32)
+    })
+    .collect()
+}
+
+/**
+ * Recover BSD informations of the process with proc_pidinfo
+ */
+fn get_bsd_info(process_id: i32) -> Option<libc::proc_bsdinfo> {
+  let mut bsd_info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+  let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+  let read = unsafe {
+    libc::proc_pidinfo(
+      process_id,
+      libc::PROC_PIDTBSDINFO,
+      0,
+      (&mut bsd_info as *mut libc::proc_bsdinfo).cast(),
+      size,
+    )
+  };
+  (read == size).then_some(bsd_info)
+}
+
+/**
+ * Recover CPU time (user and system) used by the process with proc_pidinfo, `None` when unavailable
+ */
+pub fn get_process_cpu_time(process_id: u32) -> Option<Duration> {
+  let mut task_info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+  let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+  let read = unsafe {
+    libc::proc_pidinfo(
+      process_id as i32,
+      libc::PROC_PIDTASKINFO,
+      0,
+      (&mut task_info as *mut libc::proc_taskinfo).cast(),
+      size,
+    )
+  };
+  if read != size {
+    return None;
+  }
+  // Task times are in Mach absolute time units which are not nanoseconds on Apple silicon
+  let mut timebase = MachTimebaseInfo { numer: 0, denom: 0 };
+  if unsafe { mach_timebase_info(&mut timebase) } != 0 || timebase.denom == 0 {
+    return None;
+  }
+  let ticks = task_info.pti_total_user + task_info.pti_total_system;
+  Some(Duration::from_nanos(
+    (ticks as u128 * timebase.numer as u128 / timebase.denom as u128) as u64,
+  ))
+}
+
+/**
+ * Recover start time of the process as a Unix timestamp in milliseconds, `0` when unavailable
+ */
+fn get_process_start_time(process_id: i32) -> u64 {
+  get_bsd_info(process_id)
+    .map(|bsd_info| bsd_info.pbi_start_tvsec * 1000 + bsd_info.pbi_start_tvusec / 1000)
+    .unwrap_or(0)
+}
+
+/**
+ * Recover name of the user owning the process, empty when the process cannot be inspected
+ */
+fn get_process_user(process_id: i32) -> String {
+  let Some(bsd_info) = get_bsd_info(process_id) else {
+    return String::new();
+  };
+
+  let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+  let mut result: *mut libc::passwd = std::ptr::null_mut();
+  let mut buffer: Vec<libc::c_char> = vec![0; 4096];
+  let status = unsafe {
+    libc::getpwuid_r(
+      bsd_info.pbi_uid,
+      &mut passwd,
+      buffer.as_mut_ptr(),
+      buffer.len(),
+      &mut result,
+    )
+  };
+  if status != 0 || result.is_null() || passwd.pw_name.is_null() {
+    return String::new();
+  }
+  unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) }
+    .to_string_lossy()
+    .to_string()
+}
+
+/**
+ * Recover arguments of the process with sysctl(KERN_PROCARGS2), empty when the process is not readable
+ */
+fn get_process_command_line(process_id: i32) -> String {
+  let Some(buffer) = get_process_arguments(process_id) else {
+    return String::new();
+  };
+  // Buffer contains argc, executable path, null padding and arguments
+  let argc = i32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]).max(0) as usize;
+  buffer[4..]
+    .split(|byte| *byte == 0)
+    .skip(1)
+    .filter(|arg| !arg.is_empty())
+    .take(argc)
+    .map(String::from_utf8_lossy)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/**
+ * Recover environment variables of the process with sysctl(KERN_PROCARGS2), empty when the process
+ * is not readable (processes of other users)
+ */
+pub fn get_process_env(process_id: u32) -> HashMap<String, String> {
+  let Some(buffer) = get_process_arguments(process_id as i32) else {
+    return HashMap::new();
+  };
+  // Variables follow the arguments and end with an empty entry
+  let argc = i32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]).max(0) as usize;
+  let environ = buffer[4..]
+    .split(|byte| *byte == 0)
+    .skip(1)
+    .skip_while(|entry| entry.is_empty())
+    .skip(argc)
+    .take_while(|entry| !entry.is_empty())
+    .collect::<Vec<_>>()
+    .join(&0);
+  parse_environ(&environ)
+}
+
+/**
+ * Read the raw sysctl(KERN_PROCARGS2) buffer of the process, `None` when it is not readable
+ */
+fn get_process_arguments(process_id: i32) -> Option<Vec<u8>> {
+  let mut arg_max: libc::c_int = 0;
+  let mut size = std::mem::size_of::<libc::c_int>();
+  let mut mib = [libc::CTL_KERN, libc::KERN_ARGMAX];
+  if unsafe {
+    libc::sysctl(
+      mib.as_mut_ptr(),
+      2,
+      (&mut arg_max as *mut libc::c_int).cast(),
+      &mut size,
+      std::ptr::null_mut(),
+      0,
+    )
+  } != 0
+    || arg_max <= 0
+  {
+    return None;
+  }
+
+  let mut buffer: Vec<u8> = vec![0; arg_max as usize];
+  let mut size = buffer.len();
+  let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, process_id];
+  if unsafe {
+    libc::sysctl(
+      mib.as_mut_ptr(),
+      3,
+      buffer.as_mut_ptr().cast(),
+      &mut size,
+      std::ptr::null_mut(),
+      0,
+    )
+  } != 0
+  {
+    return None;
+  }
+  buffer.truncate(size);
+  if buffer.len() < 4 {
+    return None;
+  }
+  Some(buffer)
+}
+
+/**
+ * Recover macOS product version
+ */
+pub fn os_version() -> String {
+  let output = Command::new("sw_vers").arg("-productVersion").output();
+  if let Ok(output) = output {
+    return String::from_utf8_lossy(&output.stdout).trim().to_owned();
+  }
+  "".into()
+}
+
+/**
+ * Permissions states required to recover titles and urls without prompting the user
+ */
+pub fn get_permissions() -> Vec<(String, bool)> {
+  unsafe {
+    vec![
+      (
+        "screen_recording".to_owned(),
+        CGPreflightScreenCaptureAccess(),
+      ),
+      ("accessibility".to_owned(), AXIsProcessTrusted()),
+    ]
+  }
+}
+
+/**
+ * Get the states of Screen Recording and Accessibility permissions without prompting the user
+ */
+pub fn check_permissions() -> PermissionStatus {
+  unsafe { PermissionStatus::new(CGPreflightScreenCaptureAccess(), AXIsProcessTrusted()) }
+}
+
+/**
+ * Show the system prompts of the permissions not granted yet and return their current states,
+ * a permission granted from the prompt is only effective after restarting the application
+ */
+pub fn request_permissions() -> PermissionStatus {
+  let screen_recording =
+    unsafe { CGPreflightScreenCaptureAccess() || CGRequestScreenCaptureAccess() };
+  let options = CFDictionary::from_CFType_pairs(&[(
+    unsafe { CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt) },
+    CFBoolean::true_value(),
+  )]);
+  let accessibility = unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) };
+  PermissionStatus::new(screen_recording, accessibility)
+}
+
 fn execute_applescript(script: &str) -> String {
   let output = Command::new("osascript").args(["-e", script]).output();
   if let Ok(output) = output {
@@ -301,11 +1225,117 @@ fn execute_applescript(script: &str) -> String {
   "".into()
 }
 
+/**
+ * Get the NSScreen matching the display id
+ */
+fn get_ns_screen(display_id: CGDirectDisplayID) -> Option<id> {
+  // CFString is toll-free bridged with NSString and released when dropped
+  let screen_number_key = CFString::from_static_string("NSScreenNumber");
+  let key = screen_number_key.as_concrete_TypeRef() as id;
+  unsafe {
+    let screens: id = NSScreen::screens(nil);
+    let count: u64 = msg_send![screens, count];
+    for idx in 0..count {
+      let screen: id = msg_send![screens, objectAtIndex: idx];
+      let description: id = msg_send![screen, deviceDescription];
+      let number: id = msg_send![description, objectForKey: key];
+      let screen_id: u32 = msg_send![number, unsignedIntValue];
+      if screen_id == display_id {
+        return Some(screen);
+      }
+    }
+  }
+  None
+}
+
+/**
+ * Get localized name of the running application, empty when it has none
+ */
+fn get_localized_name(app: id) -> String {
+  unsafe {
+    let name: id = msg_send![app, localizedName];
+    if name == nil {
+      return "".into();
+    }
+    let name = NSString::UTF8String(name);
+    std::ffi::CStr::from_ptr(name).to_string_lossy().to_string()
+  }
+}
+
+/**
+ * Get localized name of the NSScreen matching the display id
+ */
+fn get_display_name(display_id: CGDirectDisplayID) -> String {
+  if let Some(screen) = get_ns_screen(display_id) {
+    unsafe {
+      let responds: BOOL = msg_send![screen, respondsToSelector: sel!(localizedName)];
+      if responds != NO {
+        let name: id = msg_send![screen, localizedName];
+        let name = NSString::UTF8String(name);
+        return std::ffi::CStr::from_ptr(name).to_string_lossy().to_string();
+      }
+    }
+  }
+  "".into()
+}
+
+/**
+ * Get active displays with the backing scale factor of their screen
+ */
+fn get_cg_monitors() -> Vec<MonitorInfo> {
+  CGDisplay::active_displays()
+    .unwrap_or_default()
+    .into_iter()
+    .map(|display_id| {
+      let display = CGDisplay::new(display_id);
+      let bounds = display.bounds();
+      MonitorInfo {
+        id: display_id,
+        name: get_display_name(display_id),
+        x: bounds.origin.x as i32,
+        y: bounds.origin.y as i32,
+        width: bounds.size.width as i32,
+        height: bounds.size.height as i32,
+        is_primary: display.is_main(),
+        scale_factor: get_backing_scale_factor(display_id).unwrap_or(1.0),
+      }
+    })
+    .collect()
+}
+
+/**
+ * Get `backingScaleFactor` of the NSScreen matching the display id
+ */
+fn get_backing_scale_factor(display_id: CGDirectDisplayID) -> Option<f64> {
+  get_ns_screen(display_id).map(|screen| unsafe { msg_send![screen, backingScaleFactor] })
+}
+
 fn get_screen_rect() -> NSRect {
   let screen = unsafe { NSScreen::mainScreen(nil) };
   unsafe { NSScreen::frame(screen) }
 }
 
+/**
+ * Check if the center of the window is on an external display (not built-in display)
+ */
+fn is_on_external_display(window_rect: CGRect) -> Option<bool> {
+  let center = CGRect::new(
+    &CGPoint::new(
+      window_rect.origin.x + window_rect.size.width / 2.0,
+      window_rect.origin.y + window_rect.size.height / 2.0,
+    ),
+    &CGSize::new(1.0, 1.0),
+  );
+  let mut display_id: CGDirectDisplayID = 0;
+  let mut display_count: u32 = 0;
+  let result = unsafe { CGGetDisplaysWithRect(center, 1, &mut display_id, &mut display_count) };
+  if result == 0 && display_count > 0 {
+    Some(!CGDisplay::new(display_id).is_builtin())
+  } else {
+    None
+  }
+}
+
 fn is_full_screen(window_rect: CGRect, screen_rect: NSRect) -> bool {
   window_rect.size.height.eq(&screen_rect.size.height)
     && window_rect.size.width.eq(&screen_rect.size.width)
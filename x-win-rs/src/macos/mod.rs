@@ -2,9 +2,43 @@
 
 mod api;
 
-use crate::common::api::Api;
+use std::{collections::HashMap, time::Duration};
+
+use crate::common::{api::Api, x_win_struct::permission_status::PermissionStatus};
 use api::MacosAPI;
 
 pub fn init_platform_api() -> impl Api {
   MacosAPI {}
 }
+
+pub fn os_version() -> String {
+  api::os_version()
+}
+
+pub fn session_type() -> String {
+  "quartz".to_owned()
+}
+
+pub fn window_manager_name() -> String {
+  "WindowServer".to_owned()
+}
+
+pub fn get_process_cpu_time(process_id: u32) -> Option<Duration> {
+  api::get_process_cpu_time(process_id)
+}
+
+pub fn get_process_env(process_id: u32) -> HashMap<String, String> {
+  api::get_process_env(process_id)
+}
+
+pub fn get_permissions() -> Vec<(String, bool)> {
+  api::get_permissions()
+}
+
+pub fn check_permissions() -> PermissionStatus {
+  api::check_permissions()
+}
+
+pub fn request_permissions() -> PermissionStatus {
+  api::request_permissions()
+}
@@ -4,37 +4,76 @@ use base64::Engine;
 use regex::Regex;
 
 use windows::{
-  core::{w, VARIANT},
+  core::{w, GUID, VARIANT},
+  Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation},
   Win32::{
-    Foundation::{FALSE, TRUE},
+    Devices::Display::{
+      DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+      DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_MODE_INFO,
+      DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED,
+      DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_UDI_EMBEDDED,
+      DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SOURCE_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
+    },
+    Foundation::{FALSE, TRUE, UNICODE_STRING},
+    Globalization::LCIDToLocaleName,
     Graphics::Gdi::{
-      DeleteDC, DeleteObject, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
-      DIB_RGB_COLORS,
+      BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject,
+      EnumDisplayMonitors, GetDIBits, GetMonitorInfoW, GetObjectW, GetWindowDC, MonitorFromWindow,
+      ReleaseDC, SelectObject, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HDC,
+      HMONITOR, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONULL, SRCCOPY,
+    },
+    Security::{
+      GetLengthSid, GetTokenInformation, LookupAccountSidW, TokenUser, SID_NAME_USE, TOKEN_QUERY,
+      TOKEN_USER,
+    },
+    Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS},
+    System::Registry::{
+      RegGetValueW, HKEY_CURRENT_USER, REG_ROUTINE_FLAGS, RRF_RT_REG_BINARY, RRF_RT_REG_SZ,
     },
+    System::Threading::OpenProcessToken,
     UI::{
-      Shell::ExtractIconExW,
+      HiDpi::{GetDpiForMonitor, GetDpiForWindow, MDT_EFFECTIVE_DPI},
+      Shell::{ExtractIconExW, IVirtualDesktopManager, VirtualDesktopManager},
       WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO},
     },
   },
 };
 
-use crate::common::{
-  api::{empty_entity, os_name, Api},
-  x_win_struct::{
-    icon_info::IconInfo, process_info::ProcessInfo, usage_info::UsageInfo, window_info::WindowInfo,
-    window_position::WindowPosition,
+use crate::{
+  common::{
+    api::{empty_entity, os_name, sort_front_to_back, Api},
+    capture::to_raw_image,
+    x_win_struct::{
+      active_window_options::ActiveWindowOptions, focused_element::FocusedElement,
+      icon_info::IconInfo, monitor_info::MonitorInfo, open_windows_options::OpenWindowsOptions,
+      process_info::ProcessInfo, raw_image::RawImage, usage_info::UsageInfo,
+      window_info::WindowInfo, window_position::WindowPosition, workspace::Workspace,
+      workspace_info::WorkspaceInfo,
+    },
   },
+  lock_unpoisoned, XWinError,
 };
+use once_cell::sync::Lazy;
 use std::path::{Path, PathBuf};
-use std::{ffi::c_void, os::windows::ffi::OsStrExt};
+use std::{
+  collections::HashMap,
+  ffi::c_void,
+  os::windows::{ffi::OsStrExt, process::CommandExt},
+  sync::Mutex,
+  time::Duration,
+};
 use windows::Win32::{
-  Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED},
+  Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS},
   System::{ProcessStatus::GetProcessMemoryInfo, StationsAndDesktops::EnumDesktopWindows},
   UI::{
     Accessibility::CUIAutomation,
     WindowsAndMessaging::{
-      GetWindowInfo, GetWindowPlacement, IsWindow, IsWindowVisible, SW_SHOWMAXIMIZED, WINDOWINFO,
-      WINDOWPLACEMENT, WS_ACTIVECAPTION, WS_CAPTION, WS_CHILD, WS_EX_TOOLWINDOW,
+      GetClassNameW, GetLayeredWindowAttributes, GetSystemMetrics, GetWindow, GetWindowInfo,
+      GetWindowPlacement, IsIconic, IsWindow, IsWindowVisible, GW_OWNER,
+      LAYERED_WINDOW_ATTRIBUTES_FLAGS, LWA_ALPHA, MONITORINFOF_PRIMARY, SM_CXVIRTUALSCREEN,
+      SM_CYVIRTUALSCREEN, SM_REMOTESESSION, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SW_SHOWMAXIMIZED,
+      WINDOWINFO, WINDOWPLACEMENT, WS_ACTIVECAPTION, WS_CAPTION, WS_CHILD, WS_EX_LAYERED,
+      WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
     },
   },
 };
@@ -42,25 +81,32 @@ use windows::{
   core::{PCWSTR, PWSTR},
   Win32::{
     Foundation::HWND,
-    Foundation::{CloseHandle, BOOL, LPARAM, RECT},
-    Foundation::{HANDLE, MAX_PATH},
+    Foundation::{CloseHandle, BOOL, LPARAM, POINT, RECT, WPARAM},
+    Foundation::{FILETIME, HANDLE, MAX_PATH},
     Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW},
     System::{
       Com::*,
       ProcessStatus::PROCESS_MEMORY_COUNTERS,
       Threading::{
-        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
-        PROCESS_QUERY_LIMITED_INFORMATION,
+        GetProcessTimes, OpenProcess, QueryFullProcessImageNameW, CREATE_NO_WINDOW,
+        PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
       },
     },
     UI::Accessibility::*,
     UI::WindowsAndMessaging::{
-      EnumChildWindows, GetForegroundWindow, GetWindowRect, GetWindowTextW,
-      GetWindowThreadProcessId,
+      EnumChildWindows, GetAncestor, GetCursorPos, GetForegroundWindow, GetWindowRect,
+      GetWindowTextW, GetWindowThreadProcessId, PostMessageW, SetWindowPos, ShowWindow,
+      ShowWindowAsync, WindowFromPoint, GA_ROOT, SHOW_WINDOW_CMD, SWP_NOACTIVATE, SWP_NOZORDER,
+      SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, WM_CLOSE,
     },
   },
 };
 
+/**
+ * PrintWindow flag to render windows using DirectComposition (not exposed by the windows crate)
+ */
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
 #[derive(Debug)]
 struct LangCodePage {
   pub w_language: u16,
@@ -73,23 +119,250 @@ pub struct WindowsAPI {}
  * Impl. for windows system
  */
 impl Api for WindowsAPI {
-  fn get_active_window(&self) -> WindowInfo {
+  fn get_active_window(&self, options: &ActiveWindowOptions) -> Result<WindowInfo, XWinError> {
     let hwnd = unsafe { GetForegroundWindow() };
-    get_window_information(hwnd)
+    let mut window_info = get_window_information(
+      hwnd,
+      options.include_url,
+      true,
+      &VirtualDesktops::new(),
+      // The stacking index would need a desktop enumeration on each poll of the active window
+      0,
+    );
+    window_info.is_focused = window_info.id.ne(&0);
+    window_info.is_active = window_info.is_focused;
+    if options.include_content_language && window_info.id.ne(&0) {
+      window_info.content_language = get_content_language(hwnd);
+    }
+    if options.include_focused_element && window_info.id.ne(&0) {
+      window_info.focused_element = get_focused_element(window_info.info.process_id);
+    }
+    Ok(window_info)
   }
 
-  fn get_open_windows(&self) -> Vec<WindowInfo> {
+  fn get_active_window_id(&self) -> Result<u32, XWinError> {
+    // Same id as the one parsed from the handle by `get_window_information`
+    Ok(unsafe { GetForegroundWindow() }.0 as usize as u32)
+  }
+
+  fn is_fullscreen_active(&self) -> Result<bool, XWinError> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_invalid() || !covers_monitor_without_caption(hwnd) {
+      return Ok(false);
+    }
+    // The desktop and the start menu also cover the monitor without caption
+    let window_info = get_window_information(hwnd, false, false, &VirtualDesktops::new(), 0);
+    Ok(!is_desktop_shell_window(hwnd, &window_info))
+  }
+
+  fn get_open_windows(&self, options: &OpenWindowsOptions) -> Result<Vec<WindowInfo>, XWinError> {
+    Ok(get_desktop_windows(
+      options,
+      &mut |_window_info| true,
+      &mut |_window_info| {},
+    ))
+  }
+
+  fn get_open_windows_filtered(
+    &self,
+    options: &OpenWindowsOptions,
+    filter: &mut dyn FnMut(&WindowInfo) -> bool,
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    Ok(get_desktop_windows(options, filter, &mut |_window_info| {}))
+  }
+
+  fn get_open_windows_with_progress(
+    &self,
+    options: &OpenWindowsOptions,
+    on_window: &mut dyn FnMut(&mut WindowInfo),
+  ) -> Result<Vec<WindowInfo>, XWinError> {
+    Ok(get_desktop_windows(
+      options,
+      &mut |_window_info| true,
+      on_window,
+    ))
+  }
+
+  fn get_windows_by_pid(&self, process_id: u32) -> Result<Vec<WindowInfo>, XWinError> {
     let mut results: Vec<WindowInfo> = Vec::new();
+    let virtual_desktops = VirtualDesktops::new();
+    let handles = get_desktop_window_handles();
+
+    for (index, &hwnd) in handles.iter().enumerate() {
+      let mut lpdwprocessid: u32 = 0;
+      unsafe { GetWindowThreadProcessId(hwnd, Some(&mut lpdwprocessid)) };
+      if lpdwprocessid == process_id {
+        let z_order = (handles.len() - 1 - index) as u32;
+        let window_info = get_window_information(hwnd, false, true, &virtual_desktops, z_order);
+        if window_info.id.ne(&0) && !is_untitled_explorer_window(&window_info) {
+          results.push(window_info);
+        }
+      }
+    }
 
-    enum_desktop_windows(|hwnd| {
-      let window_info = get_window_information(hwnd);
-      if !(window_info.title.eq(&"") && window_info.info.exec_name.to_lowercase().eq(&"explorer")) {
-        results.push(window_info);
+    sort_front_to_back(&mut results);
+    Ok(results)
+  }
+
+  fn find_windows_by_ax_identifier(&self, identifier: &str) -> Result<Vec<WindowInfo>, XWinError> {
+    let mut results: Vec<WindowInfo> = Vec::new();
+    let virtual_desktops = VirtualDesktops::new();
+    let handles = get_desktop_window_handles();
+    unsafe {
+      if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() {
+        let automation: Result<IUIAutomation, _> =
+          CoCreateInstance(&CUIAutomation, None, CLSCTX_ALL);
+        if let Ok(automation) = automation {
+          if let Ok(root) = automation.GetRootElement() {
+            let variant = VARIANT::from(::windows::core::BSTR::from(identifier));
+            if let Ok(condition) =
+              automation.CreatePropertyCondition(UIA_AutomationIdPropertyId, &variant)
+            {
+              if let Ok(elements) = root.FindAll(TreeScope_Children, &condition) {
+                for index in 0..elements.Length().unwrap_or(0) {
+                  if let Ok(hwnd) = elements
+                    .GetElement(index)
+                    .and_then(|element| element.CurrentNativeWindowHandle())
+                  {
+                    let window_info = get_window_information(
+                      hwnd,
+                      false,
+                      true,
+                      &virtual_desktops,
+                      get_z_order(&handles, hwnd),
+                    );
+                    if window_info.id.ne(&0) {
+                      results.push(window_info);
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
       }
-      true
-    });
+    }
+    Ok(results)
+  }
+
+  fn capture_window(
+    &self,
+    window_id: u32,
+    max_dimension: Option<u32>,
+  ) -> Result<RawImage, XWinError> {
+    let hdc_memory = unsafe { CreateCompatibleDC(HDC::default()) };
+    let capture = capture_hwnd(
+      HWND(window_id as usize as *mut c_void),
+      hdc_memory,
+      max_dimension,
+    );
+    let _ = unsafe { DeleteDC(hdc_memory) };
+    capture
+  }
+
+  fn capture_windows(
+    &self,
+    window_ids: &[u32],
+    max_dimension: Option<u32>,
+  ) -> Result<Vec<(u32, RawImage)>, XWinError> {
+    // The memory device context is shared by all captures
+    let hdc_memory = unsafe { CreateCompatibleDC(HDC::default()) };
+    let captures = window_ids
+      .iter()
+      .filter_map(|window_id| {
+        capture_hwnd(
+          HWND(*window_id as usize as *mut c_void),
+          hdc_memory,
+          max_dimension,
+        )
+        .ok()
+        .map(|image| (*window_id, image))
+      })
+      .collect();
+    let _ = unsafe { DeleteDC(hdc_memory) };
+    Ok(captures)
+  }
 
-    results
+  fn close_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    let hwnd = HWND(window_id as usize as *mut c_void);
+    unsafe {
+      if !IsWindow(hwnd).as_bool() {
+        return Ok(false);
+      }
+      Ok(PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)).is_ok())
+    }
+  }
+
+  fn set_window_position(
+    &self,
+    window_id: u32,
+    position: &WindowPosition,
+  ) -> Result<bool, XWinError> {
+    let hwnd = HWND(window_id as usize as *mut c_void);
+    unsafe {
+      if !IsWindow(hwnd).as_bool() {
+        return Ok(false);
+      }
+      // Full screen windows are reported as maximized windows
+      if position.is_full_screen {
+        if !is_fullscreen(hwnd).as_bool() {
+          let _ = ShowWindow(hwnd, SW_MAXIMIZE);
+        }
+        return Ok(true);
+      }
+      if is_fullscreen(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+        let _ = ShowWindow(hwnd, SW_RESTORE);
+      }
+      Ok(
+        SetWindowPos(
+          hwnd,
+          None,
+          position.x,
+          position.y,
+          position.width,
+          position.height,
+          SWP_NOZORDER | SWP_NOACTIVATE,
+        )
+        .is_ok(),
+      )
+    }
+  }
+
+  fn get_window_geometry(&self, window_id: u32) -> Result<Option<WindowPosition>, XWinError> {
+    let hwnd = HWND(window_id as usize as *mut c_void);
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+      return Ok(None);
+    }
+    Ok(Some(get_rect_window(hwnd)))
+  }
+
+  fn minimize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    show_window(window_id, SW_MINIMIZE)
+  }
+
+  fn maximize_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    show_window(window_id, SW_MAXIMIZE)
+  }
+
+  fn restore_window(&self, window_id: u32) -> Result<bool, XWinError> {
+    show_window(window_id, SW_RESTORE)
+  }
+
+  fn get_window_under_cursor(&self) -> Result<Option<WindowInfo>, XWinError> {
+    let mut point = POINT::default();
+    if unsafe { GetCursorPos(&mut point) }.is_err() {
+      return Ok(None);
+    }
+    // WindowFromPoint returns the control under the cursor, the top-level window is its root ancestor
+    let hwnd = unsafe { GetAncestor(WindowFromPoint(point), GA_ROOT) };
+    if hwnd.is_invalid() {
+      return Ok(None);
+    }
+    let window_info = get_window_information(hwnd, false, true, &VirtualDesktops::new(), 0);
+    if window_info.id.eq(&0) || is_desktop_shell_window(hwnd, &window_info) {
+      return Ok(None);
+    }
+    Ok(Some(window_info))
   }
 
   fn get_app_icon(&self, window_info: &WindowInfo) -> IconInfo {
@@ -209,9 +482,131 @@ impl Api for WindowsAPI {
       width: 0,
     }
   }
+
+  fn get_monitors(&self) -> Result<Vec<MonitorInfo>, XWinError> {
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+    let lparam = LPARAM(&mut monitors as *mut Vec<MonitorInfo> as isize);
+    let _ = unsafe { EnumDisplayMonitors(None, None, Some(enum_display_monitors_proc), lparam) };
+    Ok(monitors)
+  }
+
+  fn get_workspaces(&self) -> Result<Vec<Workspace>, XWinError> {
+    let virtual_desktops = VirtualDesktops::new();
+    let current_desktop_id = virtual_desktops.get_current_desktop_id();
+    Ok(
+      virtual_desktops
+        .desktops
+        .into_iter()
+        .enumerate()
+        .map(|(index, (desktop_id, name))| {
+          Workspace::new(index as i32, name, current_desktop_id == Some(desktop_id))
+        })
+        .collect(),
+    )
+  }
+
+  fn get_desktop_bounds(&self) -> Result<WindowPosition, XWinError> {
+    let (x, y, width, height) = unsafe {
+      (
+        GetSystemMetrics(SM_XVIRTUALSCREEN),
+        GetSystemMetrics(SM_YVIRTUALSCREEN),
+        GetSystemMetrics(SM_CXVIRTUALSCREEN),
+        GetSystemMetrics(SM_CYVIRTUALSCREEN),
+      )
+    };
+    if width == 0 || height == 0 {
+      return Err(XWinError::PlatformError(
+        "unable to get virtual screen metrics".to_owned(),
+      ));
+    }
+    Ok(WindowPosition::new(x, y, width, height, false))
+  }
 }
 
 /** Functions for callback */
+unsafe extern "system" fn enum_display_monitors_proc(
+  hmonitor: HMONITOR,
+  _hdc: HDC,
+  _rect: *mut RECT,
+  lparam: LPARAM,
+) -> BOOL {
+  let monitors = lparam.0 as *mut Vec<MonitorInfo>;
+  if let Some(monitor) = get_monitor_info(hmonitor, unsafe { (*monitors).len() } as u32) {
+    unsafe { (*monitors).push(monitor) };
+  }
+  TRUE
+}
+
+unsafe extern "system" fn enum_monitor_handles_proc(
+  hmonitor: HMONITOR,
+  _hdc: HDC,
+  _rect: *mut RECT,
+  lparam: LPARAM,
+) -> BOOL {
+  let handles = lparam.0 as *mut Vec<HMONITOR>;
+  unsafe { (*handles).push(hmonitor) };
+  TRUE
+}
+
+/**
+ * Get position, name and scale factor (effective DPI) of the monitor, `id` is its index in the enumeration of monitors
+ */
+fn get_monitor_info(hmonitor: HMONITOR, id: u32) -> Option<MonitorInfo> {
+  let mut monitor_info = MONITORINFOEXW::default();
+  monitor_info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+  unsafe {
+    if !GetMonitorInfoW(hmonitor, &mut monitor_info as *mut _ as *mut MONITORINFO).as_bool() {
+      return None;
+    }
+  }
+  let rect = monitor_info.monitorInfo.rcMonitor;
+  let name_len = monitor_info
+    .szDevice
+    .iter()
+    .position(|&c| c == 0)
+    .unwrap_or(monitor_info.szDevice.len());
+  let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+  let scale_factor =
+    if unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) }.is_ok()
+      && dpi_x > 0
+    {
+      dpi_x as f64 / 96.0
+    } else {
+      1.0
+    };
+  Some(MonitorInfo {
+    id,
+    name: String::from_utf16_lossy(&monitor_info.szDevice[..name_len]),
+    x: rect.left,
+    y: rect.top,
+    width: rect.right - rect.left,
+    height: rect.bottom - rect.top,
+    is_primary: monitor_info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+    scale_factor,
+  })
+}
+
+/**
+ * Get the monitor of the window with the scale factor from the DPI of the window
+ */
+fn get_window_monitor(hwnd: HWND) -> Option<MonitorInfo> {
+  let hmonitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) };
+  if hmonitor.is_invalid() {
+    return None;
+  }
+  // Same id as the monitor returned by `get_monitors`
+  let mut handles: Vec<HMONITOR> = Vec::new();
+  let lparam = LPARAM(&mut handles as *mut Vec<HMONITOR> as isize);
+  let _ = unsafe { EnumDisplayMonitors(None, None, Some(enum_monitor_handles_proc), lparam) };
+  let id = handles.iter().position(|handle| *handle == hmonitor)?;
+  let mut monitor = get_monitor_info(hmonitor, id as u32)?;
+  let dpi = unsafe { GetDpiForWindow(hwnd) };
+  if dpi > 0 {
+    monitor.scale_factor = dpi as f64 / 96.0;
+  }
+  Some(monitor)
+}
+
 unsafe extern "system" fn enum_desktop_windows_proc<Callback: FnMut(HWND) -> bool>(
   hwnd: HWND,
   lparam: LPARAM,
@@ -228,19 +623,9 @@ unsafe extern "system" fn enum_desktop_windows_proc<Callback: FnMut(HWND) -> boo
         || is_fullscreen(hwnd).as_bool())
         && pwi.dwStyle & WS_CHILD == windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE(0)
       {
-        let mut clocked_val: i32 = 0;
-        let cbattribute = std::mem::size_of::<i32>() as u32;
-        let result = DwmGetWindowAttribute(
-          hwnd,
-          DWMWA_CLOAKED,
-          &mut clocked_val as *mut i32 as *mut _,
-          cbattribute,
-        );
-        if result.is_ok() && clocked_val == 0 {
-          // If problem with callback stop loop
-          if !((*callback)(hwnd)) {
-            return FALSE;
-          }
+        // If problem with callback stop loop
+        if !is_cloaked(hwnd) && !((*callback)(hwnd)) {
+          return FALSE;
         }
       }
     }
@@ -272,6 +657,255 @@ extern "system" fn enum_child_windows_func(hwnd: HWND, lparam: LPARAM) -> BOOL {
   }
 }
 
+/**
+ * Capture the RGBA pixels of the window by rendering it in the memory device context, minimized and hidden windows are refused
+ */
+fn capture_hwnd(
+  hwnd: HWND,
+  hdc_memory: HDC,
+  max_dimension: Option<u32>,
+) -> Result<RawImage, XWinError> {
+  unsafe {
+    if !IsWindow(hwnd).as_bool() {
+      return Err(XWinError::PlatformError("window not found".to_owned()));
+    }
+    if IsIconic(hwnd).as_bool() || !IsWindowVisible(hwnd).as_bool() || is_cloaked(hwnd) {
+      return Err(XWinError::PlatformError(
+        "window is minimized or hidden".to_owned(),
+      ));
+    }
+  }
+  let position = get_rect_window(hwnd);
+  let (width, height) = (position.width, position.height);
+  if width <= 0 || height <= 0 {
+    return Err(XWinError::PlatformError("window has no size".to_owned()));
+  }
+
+  let mut buffer: Vec<u8> = vec![0u8; (width * height * 4) as usize];
+  let captured_height = unsafe {
+    let hdc_window = GetWindowDC(hwnd);
+    let hbitmap = CreateCompatibleBitmap(hdc_window, width, height);
+    let previous = SelectObject(hdc_memory, hbitmap);
+    // PrintWindow renders occluded windows, BitBlt is used as fallback
+    if !PrintWindow(hwnd, hdc_memory, PW_RENDERFULLCONTENT).as_bool() {
+      let _ = BitBlt(hdc_memory, 0, 0, width, height, hdc_window, 0, 0, SRCCOPY);
+    }
+    SelectObject(hdc_memory, previous);
+
+    let mut bitmap_info = BITMAPINFO::default();
+    bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    bitmap_info.bmiHeader.biWidth = width;
+    bitmap_info.bmiHeader.biHeight = -height;
+    bitmap_info.bmiHeader.biPlanes = 1;
+    bitmap_info.bmiHeader.biBitCount = 32;
+    bitmap_info.bmiHeader.biCompression = BI_RGB.0;
+    let captured_height = GetDIBits(
+      hdc_memory,
+      hbitmap,
+      0,
+      height as u32,
+      Some(buffer.as_mut_ptr().cast()),
+      &mut bitmap_info,
+      DIB_RGB_COLORS,
+    );
+
+    let _ = DeleteObject(hbitmap);
+    ReleaseDC(hwnd, hdc_window);
+    captured_height
+  };
+  if captured_height != height {
+    return Err(XWinError::PlatformError(
+      "failed to capture the window".to_owned(),
+    ));
+  }
+
+  // GDI buffer is BGRA without alpha
+  for chunk in buffer.chunks_mut(4) {
+    chunk.swap(0, 2);
+    chunk[3] = 255;
+  }
+  to_raw_image(width as u32, height as u32, buffer, max_dimension)
+}
+
+/**
+ * Is the window part of the desktop shell (taskbar, desktop, start menu, tooltips...)
+ */
+fn is_desktop_shell_window(hwnd: HWND, window_info: &WindowInfo) -> bool {
+  let mut class_name: [u16; 256] = [0; 256];
+  let length = unsafe { GetClassNameW(hwnd, &mut class_name) };
+  let class_name = String::from_utf16_lossy(&class_name[..length.max(0) as usize]);
+  matches!(
+    class_name.as_str(),
+    "Shell_TrayWnd"
+      | "Shell_SecondaryTrayWnd"
+      | "Progman"
+      | "WorkerW"
+      | "tooltips_class32"
+      | "NotifyIconOverflowWindow"
+      | "Windows.UI.Core.CoreWindow"
+  ) || matches!(
+    window_info.info.exec_name.to_lowercase().as_str(),
+    "searchhost"
+      | "searchapp"
+      | "shellexperiencehost"
+      | "startmenuexperiencehost"
+      | "textinputhost"
+      | "lockapp"
+  )
+}
+
+/**
+ * Check if the window is a tool window or a popup without caption (menus, tooltips, drag images...),
+ * full screen popups of games and video players are not counted
+ */
+fn is_popup_window(hwnd: HWND) -> bool {
+  let mut pwi = WINDOWINFO {
+    cbSize: std::mem::size_of::<WINDOWINFO>() as u32,
+    ..Default::default()
+  };
+  if unsafe { GetWindowInfo(hwnd, &mut pwi) }.is_err() {
+    return false;
+  }
+  let is_tool_window = pwi.dwExStyle & WS_EX_TOOLWINDOW == WS_EX_TOOLWINDOW;
+  let is_captionless_popup =
+    pwi.dwStyle & WS_POPUP == WS_POPUP && pwi.dwStyle & WS_CAPTION != WS_CAPTION;
+  (is_tool_window || is_captionless_popup) && !covers_monitor_without_caption(hwnd)
+}
+
+/**
+ * Check if the window covers its whole monitor without caption like full screen games and video players,
+ * unlike `is_fullscreen` maximized windows keep their caption
+ */
+fn covers_monitor_without_caption(hwnd: HWND) -> bool {
+  let mut pwi = WINDOWINFO {
+    cbSize: std::mem::size_of::<WINDOWINFO>() as u32,
+    ..Default::default()
+  };
+  if unsafe { GetWindowInfo(hwnd, &mut pwi) }.is_err() || pwi.dwStyle & WS_CAPTION == WS_CAPTION {
+    return false;
+  }
+  let hmonitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) };
+  if hmonitor.is_invalid() {
+    return false;
+  }
+  let mut monitor_info = MONITORINFO {
+    cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+    ..Default::default()
+  };
+  if !unsafe { GetMonitorInfoW(hmonitor, &mut monitor_info) }.as_bool() {
+    return false;
+  }
+  let (window, monitor) = (pwi.rcWindow, monitor_info.rcMonitor);
+  window.left <= monitor.left
+    && window.top <= monitor.top
+    && window.right >= monitor.right
+    && window.bottom >= monitor.bottom
+}
+
+/**
+ * Is the window cloaked by DWM (e.g. on another virtual desktop)
+ */
+fn is_cloaked(hwnd: HWND) -> bool {
+  let mut clocked_val: i32 = 0;
+  let cbattribute = std::mem::size_of::<i32>() as u32;
+  let result = unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      DWMWA_CLOAKED,
+      &mut clocked_val as *mut i32 as *mut _,
+      cbattribute,
+    )
+  };
+  result.is_err() || clocked_val != 0
+}
+
+/**
+ * Get the visible bounds of the window from DWM, unlike `GetWindowRect` the invisible resize borders are excluded
+ */
+fn get_window_frame(hwnd: HWND, is_full_screen: bool) -> Option<WindowPosition> {
+  let mut rect = RECT::default();
+  unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      DWMWA_EXTENDED_FRAME_BOUNDS,
+      &mut rect as *mut RECT as *mut _,
+      std::mem::size_of::<RECT>() as u32,
+    )
+  }
+  .ok()?;
+  Some(WindowPosition::new(
+    rect.left,
+    rect.top,
+    rect.right - rect.left,
+    rect.bottom - rect.top,
+    is_full_screen,
+  ))
+}
+
+/**
+ * Untitled windows of explorer (desktop, taskbar...) which are never listed
+ */
+fn is_untitled_explorer_window(window_info: &WindowInfo) -> bool {
+  window_info.title.eq(&"") && window_info.info.exec_name.to_lowercase().eq(&"explorer")
+}
+
+/**
+ * Windows of the desktop kept by `options` and accepted by `accept`, it is called before the memory usage
+ * and the url are read, with all other fields set. `gathered` is called with each accepted window in enumeration order
+ */
+fn get_desktop_windows(
+  options: &OpenWindowsOptions,
+  accept: &mut dyn FnMut(&WindowInfo) -> bool,
+  gathered: &mut dyn FnMut(&mut WindowInfo),
+) -> Vec<WindowInfo> {
+  let mut results: Vec<WindowInfo> = Vec::new();
+  let foreground_hwnd = unsafe { GetForegroundWindow() };
+  let virtual_desktops = VirtualDesktops::new();
+
+  let handles = get_desktop_window_handles();
+
+  for (index, &hwnd) in handles.iter().enumerate() {
+    // Windows are enumerated from front to back
+    let z_order = (handles.len() - 1 - index) as u32;
+    let mut window_info = get_window_information(hwnd, false, false, &virtual_desktops, z_order);
+    window_info.is_focused = hwnd == foreground_hwnd;
+    window_info.is_active = window_info.is_focused;
+    if is_untitled_explorer_window(&window_info) {
+      continue;
+    }
+    if options.exclude_desktop_shell && is_desktop_shell_window(hwnd, &window_info) {
+      continue;
+    }
+    if options.only_current_workspace && !virtual_desktops.is_on_current(hwnd) {
+      continue;
+    }
+    if !options.include_transient && window_info.parent_id.is_some() {
+      continue;
+    }
+    // Captionless foreground windows (e.g. splash screens, borderless apps) are kept like the active window
+    if !options.include_override_redirect && !window_info.is_active && is_popup_window(hwnd) {
+      continue;
+    }
+    // Memory and url of rejected windows are never read
+    if !accept(&window_info) {
+      continue;
+    }
+    set_window_details(
+      hwnd,
+      &mut window_info,
+      options.include_url,
+      options.include_usage,
+    );
+    gathered(&mut window_info);
+    results.push(window_info);
+  }
+
+  if !options.raw_order {
+    sort_front_to_back(&mut results);
+  }
+  results
+}
+
 /** Function with callback as parameter to get open windows */
 fn enum_desktop_windows<Callback: FnMut(HWND) -> bool>(callback: Callback) {
   unsafe {
@@ -280,6 +914,18 @@ fn enum_desktop_windows<Callback: FnMut(HWND) -> bool>(callback: Callback) {
   }
 }
 
+/**
+ * Handles of the windows enumerated by `enum_desktop_windows`, from front to back
+ */
+fn get_desktop_window_handles() -> Vec<HWND> {
+  let mut handles: Vec<HWND> = Vec::new();
+  enum_desktop_windows(|hwnd| {
+    handles.push(hwnd);
+    true
+  });
+  handles
+}
+
 /**
  * Is the window show as maximized
  */
@@ -296,6 +942,19 @@ fn is_fullscreen(hwnd: HWND) -> BOOL {
   FALSE
 }
 
+/**
+ * Change the show state of the window, asynchronously as the window is owned by another thread
+ */
+fn show_window(window_id: u32, command: SHOW_WINDOW_CMD) -> Result<bool, XWinError> {
+  let hwnd = HWND(window_id as usize as *mut c_void);
+  unsafe {
+    if !IsWindow(hwnd).as_bool() {
+      return Ok(false);
+    }
+    Ok(ShowWindowAsync(hwnd, command).as_bool())
+  }
+}
+
 /**
  * Method to open hnadle
  */
@@ -348,6 +1007,181 @@ fn get_window_title(hwnd: HWND) -> String {
   String::from_utf16_lossy(&v[0..(title_len as usize)])
 }
 
+/**
+ * Get command line of the process from handle, empty when not readable (require Windows 8.1+)
+ */
+fn get_process_command_line(phlde: HANDLE) -> String {
+  let mut return_length: u32 = 0;
+  let _ = unsafe {
+    NtQueryInformationProcess(
+      phlde,
+      ProcessCommandLineInformation,
+      std::ptr::null_mut(),
+      0,
+      &mut return_length,
+    )
+  };
+  if return_length == 0 {
+    return String::new();
+  }
+
+  // u64 buffer to keep UNICODE_STRING aligned
+  let mut buffer: Vec<u64> = vec![0; (return_length as usize).div_ceil(8)];
+  let status = unsafe {
+    NtQueryInformationProcess(
+      phlde,
+      ProcessCommandLineInformation,
+      buffer.as_mut_ptr().cast(),
+      return_length,
+      &mut return_length,
+    )
+  };
+  if status.is_err() {
+    return String::new();
+  }
+
+  let command_line = unsafe { &*(buffer.as_ptr() as *const UNICODE_STRING) };
+  if command_line.Buffer.is_null() {
+    return String::new();
+  }
+  let command_line = unsafe {
+    std::slice::from_raw_parts(command_line.Buffer.0, (command_line.Length / 2) as usize)
+  };
+  String::from_utf16_lossy(command_line)
+}
+
+/**
+ * Get creation time of the process as a Unix timestamp in milliseconds, `0` when unavailable
+ */
+fn get_process_start_time(phlde: HANDLE) -> u64 {
+  let mut creation_time = FILETIME::default();
+  let mut exit_time = FILETIME::default();
+  let mut kernel_time = FILETIME::default();
+  let mut user_time = FILETIME::default();
+  if unsafe {
+    GetProcessTimes(
+      phlde,
+      &mut creation_time,
+      &mut exit_time,
+      &mut kernel_time,
+      &mut user_time,
+    )
+  }
+  .is_err()
+  {
+    return 0;
+  }
+  filetime_to_unix_ms(&creation_time)
+}
+
+/**
+ * Recover CPU time (kernel and user) used by the process with GetProcessTimes, `None` when unavailable
+ */
+pub fn get_process_cpu_time(process_id: u32) -> Option<Duration> {
+  let phlde = open_process_handle(process_id).ok()?;
+  let mut creation_time = FILETIME::default();
+  let mut exit_time = FILETIME::default();
+  let mut kernel_time = FILETIME::default();
+  let mut user_time = FILETIME::default();
+  let result = unsafe {
+    GetProcessTimes(
+      phlde,
+      &mut creation_time,
+      &mut exit_time,
+      &mut kernel_time,
+      &mut user_time,
+    )
+  };
+  close_process_handle(phlde);
+  result.ok()?;
+  let intervals = filetime_to_intervals(&kernel_time) + filetime_to_intervals(&user_time);
+  Some(Duration::from_nanos(intervals * 100))
+}
+
+/**
+ * Get the number of 100 nanoseconds intervals of a FILETIME
+ */
+fn filetime_to_intervals(filetime: &FILETIME) -> u64 {
+  ((filetime.dwHighDateTime as u64) << 32) | filetime.dwLowDateTime as u64
+}
+
+/**
+ * Convert a FILETIME (100 nanoseconds intervals since 1601) into a Unix timestamp in milliseconds
+ */
+fn filetime_to_unix_ms(filetime: &FILETIME) -> u64 {
+  (filetime_to_intervals(filetime) / 10_000).saturating_sub(11_644_473_600_000)
+}
+
+/**
+ * Account names by SID, `LookupAccountSidW` may query a domain controller over the network for domain accounts
+ */
+static ACCOUNT_NAMES: Lazy<Mutex<HashMap<Vec<u8>, String>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+/**
+ * Get name of the user owning the process from its token, empty when the token cannot be opened.
+ * The name of each SID is looked up once
+ */
+fn get_process_user(phlde: HANDLE) -> String {
+  let mut token = HANDLE::default();
+  if unsafe { OpenProcessToken(phlde, TOKEN_QUERY, &mut token) }.is_err() {
+    return String::new();
+  }
+
+  let mut return_length: u32 = 0;
+  let _ = unsafe { GetTokenInformation(token, TokenUser, None, 0, &mut return_length) };
+  // u64 buffer to keep TOKEN_USER aligned
+  let mut buffer: Vec<u64> = vec![0; (return_length as usize).div_ceil(8)];
+  let token_information = unsafe {
+    GetTokenInformation(
+      token,
+      TokenUser,
+      Some(buffer.as_mut_ptr().cast()),
+      return_length,
+      &mut return_length,
+    )
+  };
+  close_process_handle(token);
+  if return_length == 0 || token_information.is_err() {
+    return String::new();
+  }
+
+  let token_user = unsafe { &*(buffer.as_ptr() as *const TOKEN_USER) };
+  let sid = unsafe {
+    std::slice::from_raw_parts(
+      token_user.User.Sid.0 as *const u8,
+      GetLengthSid(token_user.User.Sid) as usize,
+    )
+  }
+  .to_vec();
+  if let Some(user) = lock_unpoisoned(&ACCOUNT_NAMES).get(&sid) {
+    return user.clone();
+  }
+
+  let mut name: Vec<u16> = vec![0; 256];
+  let mut name_len = name.len() as u32;
+  let mut domain: Vec<u16> = vec![0; 256];
+  let mut domain_len = domain.len() as u32;
+  let mut sid_name_use = SID_NAME_USE::default();
+  let lookup = unsafe {
+    LookupAccountSidW(
+      PCWSTR::null(),
+      token_user.User.Sid,
+      PWSTR(name.as_mut_ptr()),
+      &mut name_len,
+      PWSTR(domain.as_mut_ptr()),
+      &mut domain_len,
+      &mut sid_name_use,
+    )
+  };
+  if lookup.is_err() {
+    return String::new();
+  }
+  let user = String::from_utf16_lossy(&name[..name_len as usize]);
+  lock_unpoisoned(&ACCOUNT_NAMES).insert(sid, user.clone());
+  user
+}
+
 /**
  * Get process path from handle
  */
@@ -453,6 +1287,12 @@ fn get_process_path_and_name(phlde: HANDLE, hwnd: HWND, process_id: u32) -> Proc
     name: "".to_string(),
     path: "".to_string(),
     exec_name: "".to_string(),
+    command_line: get_process_command_line(phlde),
+    user: get_process_user(phlde),
+    start_time: get_process_start_time(phlde),
+    bundle_id: "".to_string(),
+    app_name: "".to_string(),
+    instance_name: "".to_string(),
   };
 
   if let Ok(process_path) = get_process_path(phlde) {
@@ -488,7 +1328,59 @@ fn get_process_path_and_name(phlde: HANDLE, hwnd: HWND, process_id: u32) -> Proc
 /**
  * Function that construct windowInfo
  */
-fn get_window_information(hwnd: HWND) -> WindowInfo {
+fn get_window_information(
+  hwnd: HWND,
+  include_url: bool,
+  include_usage: bool,
+  virtual_desktops: &VirtualDesktops,
+  z_order: u32,
+) -> WindowInfo {
+  let mut window_info = get_window_handle_information(hwnd, virtual_desktops, z_order);
+  set_window_details(hwnd, &mut window_info, include_url, include_usage);
+  window_info
+}
+
+/**
+ * Read the memory usage of the process (with `include_usage`) and the url of browsers (with `include_url`),
+ * empty entities are left untouched
+ */
+fn set_window_details(
+  hwnd: HWND,
+  window_info: &mut WindowInfo,
+  include_url: bool,
+  include_usage: bool,
+) {
+  if window_info.id == 0 {
+    return;
+  }
+  if include_usage {
+    if let Ok(handle) = open_process_handle(window_info.info.process_id) {
+      let mut process_memory_counters = PROCESS_MEMORY_COUNTERS::default();
+      unsafe {
+        let _ = GetProcessMemoryInfo(
+          handle,
+          &mut process_memory_counters as *mut _,
+          std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        );
+      };
+      close_process_handle(handle);
+      window_info.usage.memory = process_memory_counters.WorkingSetSize as u32;
+    }
+  }
+  let exec_name = window_info.info.exec_name.to_lowercase();
+  if include_url && is_browser(exec_name.as_str()) {
+    window_info.url = get_browser_url(hwnd, exec_name);
+  }
+}
+
+/**
+ * Get window information without the memory usage and the url, `z_order` is the stacking index from `get_z_order`
+ */
+fn get_window_handle_information(
+  hwnd: HWND,
+  virtual_desktops: &VirtualDesktops,
+  z_order: u32,
+) -> WindowInfo {
   let mut window_info: WindowInfo = empty_entity();
   let mut lpdwprocessid: u32 = 0;
   unsafe { GetWindowThreadProcessId(hwnd, Some(&mut lpdwprocessid)) };
@@ -503,33 +1395,37 @@ fn get_window_information(hwnd: HWND) -> WindowInfo {
     let id =
       u32::from_str_radix(hex_str_trimmed, 16).expect("Failed to convert hexadecimal to u32");
     let parent_process: ProcessInfo = get_process_path_and_name(handle, hwnd, lpdwprocessid);
-
-    let mut process_memory_counters = PROCESS_MEMORY_COUNTERS::default();
-
-    unsafe {
-      let _ = GetProcessMemoryInfo(
-        handle,
-        &mut process_memory_counters as *mut _,
-        std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
-      );
-    };
     close_process_handle(handle);
-    let exec_name = parent_process.exec_name.to_lowercase();
-    if exec_name.ne(&"searchhost") {
-      let mut url: String = "".to_owned();
-      // if is_browser(exec_name.as_str()) {
-      //   get_browser_url(hwnd, exec_name).clone_into(&mut url);
-      // }
+    if parent_process.exec_name.to_lowercase().ne(&"searchhost") {
       window_info = WindowInfo {
         id,
         os: os_name(),
         title: get_window_title(hwnd),
+        frame: get_window_frame(hwnd, position.is_full_screen),
         position,
         info: parent_process,
         usage: UsageInfo {
-          memory: process_memory_counters.WorkingSetSize as u32,
+          memory: 0,
+          cpu: 0.0,
         },
-        url,
+        url: "".to_owned(),
+        on_external_display: is_on_external_display(hwnd),
+        z_order,
+        workspace: virtual_desktops.get_workspace(hwnd),
+        content_language: None,
+        monitor: get_window_monitor(hwnd),
+        is_focused: false,
+        is_active: false,
+        opacity: get_window_opacity(hwnd),
+        parent_id: get_window_owner(hwnd),
+        is_minimized: unsafe { IsIconic(hwnd) }.as_bool(),
+        is_visible: unsafe { IsWindowVisible(hwnd) }.as_bool(),
+        role: "".to_owned(),
+        focused_element: None,
+        always_on_top: is_topmost_window(hwnd),
+        // Windows has no bottommost style
+        always_on_bottom: false,
+        native_handle: hwnd.0 as usize as u64,
       };
     }
   }
@@ -537,6 +1433,465 @@ fn get_window_information(hwnd: HWND) -> WindowInfo {
   window_info
 }
 
+/**
+ * Check if the window is pinned above the other windows with `WS_EX_TOPMOST`
+ */
+fn is_topmost_window(hwnd: HWND) -> bool {
+  let mut pwi: WINDOWINFO = WINDOWINFO::default();
+  unsafe { GetWindowInfo(hwnd, &mut pwi) }.is_ok() && pwi.dwExStyle.contains(WS_EX_TOPMOST)
+}
+
+/**
+ * Get opacity of a layered window set with `LWA_ALPHA`, other windows are opaque
+ */
+fn get_window_opacity(hwnd: HWND) -> f64 {
+  let mut pwi: WINDOWINFO = WINDOWINFO::default();
+  let mut alpha: u8 = 255;
+  let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS::default();
+  if unsafe { GetWindowInfo(hwnd, &mut pwi) }.is_ok()
+    && pwi.dwExStyle.contains(WS_EX_LAYERED)
+    && unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), Some(&mut flags)) }.is_ok()
+    && flags.contains(LWA_ALPHA)
+  {
+    alpha as f64 / 255.0
+  } else {
+    1.0
+  }
+}
+
+/**
+ * Get the id of the owner window (GW_OWNER), `None` for unowned windows
+ */
+fn get_window_owner(hwnd: HWND) -> Option<u32> {
+  unsafe { GetWindow(hwnd, GW_OWNER) }
+    .ok()
+    .filter(|owner| !owner.is_invalid())
+    .map(|owner| owner.0 as usize as u32)
+}
+
+/**
+ * Get stacking index of the window from its position in `handles`, enumerated from front to back
+ * (`0` when the window is not enumerated)
+ */
+fn get_z_order(handles: &[HWND], hwnd: HWND) -> u32 {
+  handles
+    .iter()
+    .position(|handle| handle.eq(&hwnd))
+    .map(|index| (handles.len() - 1 - index) as u32)
+    .unwrap_or(0)
+}
+
+const VIRTUAL_DESKTOPS_KEY: &str =
+  "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\VirtualDesktops";
+
+/**
+ * IVirtualDesktopManager and the virtual desktops of explorer, created once for all the windows of an enumeration
+ */
+struct VirtualDesktops {
+  manager: Option<IVirtualDesktopManager>,
+  /**
+   * Ids in the order displayed by the task view with their name
+   */
+  desktops: Vec<(GUID, Option<String>)>,
+}
+
+impl VirtualDesktops {
+  fn new() -> Self {
+    let manager = unsafe {
+      let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+      CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL).ok()
+    };
+    let desktops = get_virtual_desktop_ids()
+      .into_iter()
+      .map(|desktop_id| {
+        let name = get_virtual_desktop_name(&desktop_id);
+        (desktop_id, name)
+      })
+      .collect();
+    Self { manager, desktops }
+  }
+
+  /**
+   * Get virtual desktop of the window with IVirtualDesktopManager,
+   * index and name of the desktop are recovered from explorer registry keys
+   */
+  fn get_workspace(&self, hwnd: HWND) -> Option<WorkspaceInfo> {
+    let desktop_id = unsafe { self.manager.as_ref()?.GetWindowDesktopId(hwnd) }.ok()?;
+    if desktop_id == GUID::zeroed() {
+      return Some(WorkspaceInfo { id: -1, name: None });
+    }
+    let index = self.desktops.iter().position(|(id, _)| *id == desktop_id)?;
+    Some(WorkspaceInfo {
+      id: index as i32,
+      name: self.desktops[index].1.clone(),
+    })
+  }
+
+  /**
+   * To know if the window is shown on the current virtual desktop (pinned windows are on all of them),
+   * `true` when IVirtualDesktopManager is not available
+   */
+  fn is_on_current(&self, hwnd: HWND) -> bool {
+    self
+      .manager
+      .as_ref()
+      .and_then(|manager| unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd) }.ok())
+      .map(|is_on_current| is_on_current.as_bool())
+      .unwrap_or(true)
+  }
+
+  /**
+   * Id of the virtual desktop shown on screen, stored by explorer in the registry on Windows 11.
+   * On Windows 10 it is the desktop of the first window on the current desktop
+   */
+  fn get_current_desktop_id(&self) -> Option<GUID> {
+    if let Some(desktop_id) = read_registry_value(
+      VIRTUAL_DESKTOPS_KEY,
+      "CurrentVirtualDesktop",
+      RRF_RT_REG_BINARY,
+    )
+    .and_then(|value| value.chunks_exact(16).map(guid_from_bytes).next())
+    {
+      return Some(desktop_id);
+    }
+    let manager = self.manager.as_ref()?;
+    let mut current_desktop_id: Option<GUID> = None;
+    enum_desktop_windows(|hwnd| {
+      let is_on_current = unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd) }
+        .map(|is_on_current| is_on_current.as_bool())
+        .unwrap_or(false);
+      // Pinned windows are on all desktops without desktop id
+      if is_on_current {
+        current_desktop_id = unsafe { manager.GetWindowDesktopId(hwnd) }
+          .ok()
+          .filter(|desktop_id| *desktop_id != GUID::zeroed());
+      }
+      current_desktop_id.is_none()
+    });
+    current_desktop_id
+  }
+}
+
+/**
+ * Read a registry value of the current user into a buffer
+ */
+fn read_registry_value(sub_key: &str, value: &str, flags: REG_ROUTINE_FLAGS) -> Option<Vec<u8>> {
+  let sub_key: Vec<u16> = sub_key.encode_utf16().chain(Some(0)).collect();
+  let value: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+  let mut size: u32 = 0;
+  unsafe {
+    RegGetValueW(
+      HKEY_CURRENT_USER,
+      PCWSTR(sub_key.as_ptr()),
+      PCWSTR(value.as_ptr()),
+      flags,
+      None,
+      None,
+      Some(&mut size),
+    )
+    .ok()
+    .ok()?;
+    let mut buffer: Vec<u8> = vec![0u8; size as usize];
+    RegGetValueW(
+      HKEY_CURRENT_USER,
+      PCWSTR(sub_key.as_ptr()),
+      PCWSTR(value.as_ptr()),
+      flags,
+      None,
+      Some(buffer.as_mut_ptr() as *mut c_void),
+      Some(&mut size),
+    )
+    .ok()
+    .ok()?;
+    buffer.truncate(size as usize);
+    Some(buffer)
+  }
+}
+
+/**
+ * Ids of virtual desktops in the order displayed by the task view
+ */
+fn get_virtual_desktop_ids() -> Vec<GUID> {
+  read_registry_value(VIRTUAL_DESKTOPS_KEY, "VirtualDesktopIDs", RRF_RT_REG_BINARY)
+    .unwrap_or_default()
+    .chunks_exact(16)
+    .map(guid_from_bytes)
+    .collect()
+}
+
+/**
+ * Read a GUID stored as 16 little endian bytes
+ */
+fn guid_from_bytes(chunk: &[u8]) -> GUID {
+  GUID::from_values(
+    u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+    u16::from_le_bytes([chunk[4], chunk[5]]),
+    u16::from_le_bytes([chunk[6], chunk[7]]),
+    [
+      chunk[8], chunk[9], chunk[10], chunk[11], chunk[12], chunk[13], chunk[14], chunk[15],
+    ],
+  )
+}
+
+/**
+ * Name of a virtual desktop, only available when the user renamed it
+ */
+fn get_virtual_desktop_name(desktop_id: &GUID) -> Option<String> {
+  let sub_key = format!(
+    "{}\\Desktops\\{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+    VIRTUAL_DESKTOPS_KEY,
+    desktop_id.data1,
+    desktop_id.data2,
+    desktop_id.data3,
+    desktop_id.data4[0],
+    desktop_id.data4[1],
+    desktop_id.data4[2],
+    desktop_id.data4[3],
+    desktop_id.data4[4],
+    desktop_id.data4[5],
+    desktop_id.data4[6],
+    desktop_id.data4[7]
+  );
+  let buffer = read_registry_value(&sub_key, "Name", RRF_RT_REG_SZ)?;
+  let name: Vec<u16> = buffer
+    .chunks_exact(2)
+    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+    .take_while(|c| *c != 0)
+    .collect();
+  let name = String::from_utf16_lossy(&name);
+  if name.is_empty() {
+    None
+  } else {
+    Some(name)
+  }
+}
+
+/**
+ * Recover windows version from `ver` command
+ */
+pub fn os_version() -> String {
+  let output = std::process::Command::new("cmd")
+    .args(["/C", "ver"])
+    .creation_flags(CREATE_NO_WINDOW.0)
+    .output();
+  if let Ok(output) = output {
+    return String::from_utf8_lossy(&output.stdout).trim().to_owned();
+  }
+  "".into()
+}
+
+/**
+ * Session type (`remote` for remote desktop sessions and `console` otherwise)
+ */
+pub fn session_type() -> String {
+  if unsafe { GetSystemMetrics(SM_REMOTESESSION) } != 0 {
+    "remote".to_owned()
+  } else {
+    "console".to_owned()
+  }
+}
+
+/**
+ * Check if the monitor containing the window is external by matching its GDI device name
+ * with the output technology of the active display paths
+ */
+fn is_on_external_display(hwnd: HWND) -> Option<bool> {
+  unsafe {
+    let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL);
+    if hmonitor.is_invalid() {
+      return None;
+    }
+    let mut monitor_info = MONITORINFOEXW::default();
+    monitor_info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    if !GetMonitorInfoW(hmonitor, &mut monitor_info as *mut _ as *mut MONITORINFO).as_bool() {
+      return None;
+    }
+
+    let mut path_count: u32 = 0;
+    let mut mode_count: u32 = 0;
+    if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count).is_err()
+    {
+      return None;
+    }
+    let mut paths: Vec<DISPLAYCONFIG_PATH_INFO> =
+      vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+    let mut modes: Vec<DISPLAYCONFIG_MODE_INFO> =
+      vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+    if QueryDisplayConfig(
+      QDC_ONLY_ACTIVE_PATHS,
+      &mut path_count,
+      paths.as_mut_ptr(),
+      &mut mode_count,
+      modes.as_mut_ptr(),
+      None,
+    )
+    .is_err()
+    {
+      return None;
+    }
+
+    for path in paths.iter().take(path_count as usize) {
+      let mut source_name = DISPLAYCONFIG_SOURCE_DEVICE_NAME::default();
+      source_name.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+      source_name.header.size = std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+      source_name.header.adapterId = path.sourceInfo.adapterId;
+      source_name.header.id = path.sourceInfo.id;
+      if DisplayConfigGetDeviceInfo(&mut source_name.header) == 0
+        && source_name.viewGdiDeviceName == monitor_info.szDevice
+      {
+        let output_technology = path.targetInfo.outputTechnology;
+        return Some(
+          output_technology != DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL
+            && output_technology != DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED
+            && output_technology != DISPLAYCONFIG_OUTPUT_TECHNOLOGY_UDI_EMBEDDED,
+        );
+      }
+    }
+  }
+  None
+}
+
+/**
+ * Get language of the focused element (or the window) from the UIA Culture property
+ */
+fn get_content_language(hwnd: HWND) -> Option<String> {
+  unsafe {
+    if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_err() {
+      return None;
+    }
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_ALL).ok()?;
+    let walker = automation.ControlViewWalker().ok()?;
+    let mut element = automation
+      .GetFocusedElement()
+      .or_else(|_| automation.ElementFromHandle(hwnd))
+      .ok();
+    // The culture is often only set on an ancestor of the focused element
+    while let Some(current) = element {
+      let lcid = current
+        .GetCurrentPropertyValue(UIA_CulturePropertyId)
+        .ok()
+        .and_then(|variant| i32::try_from(&variant).ok())
+        .unwrap_or(0);
+      if lcid != 0 {
+        return lcid_to_language_tag(lcid as u32);
+      }
+      if current.CurrentNativeWindowHandle().ok() == Some(hwnd) {
+        break;
+      }
+      element = walker.GetParentElement(&current).ok();
+    }
+  }
+  None
+}
+
+/**
+ * Get the focused element with UI Automation when it belongs to the process of the active window
+ */
+fn get_focused_element(process_id: u32) -> Option<FocusedElement> {
+  unsafe {
+    if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_err() {
+      return None;
+    }
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_ALL).ok()?;
+    let element = automation.GetFocusedElement().ok()?;
+    if element.CurrentProcessId().ok()? as u32 != process_id {
+      return None;
+    }
+    let bounds = element.CurrentBoundingRectangle().unwrap_or_default();
+    Some(FocusedElement::new(
+      element
+        .CurrentControlType()
+        .map(control_type_role)
+        .unwrap_or_default()
+        .to_owned(),
+      element
+        .CurrentName()
+        .map(|name| name.to_string())
+        .unwrap_or_default(),
+      WindowPosition {
+        x: bounds.left,
+        y: bounds.top,
+        width: bounds.right - bounds.left,
+        height: bounds.bottom - bounds.top,
+        is_full_screen: false,
+      },
+    ))
+  }
+}
+
+/**
+ * AT-SPI role names of UI Automation control types, the localized control type depends on the system language
+ */
+const CONTROL_TYPE_ROLES: [(UIA_CONTROLTYPE_ID, &str); 38] = [
+  (UIA_ButtonControlTypeId, "push button"),
+  (UIA_SplitButtonControlTypeId, "push button"),
+  (UIA_CalendarControlTypeId, "calendar"),
+  (UIA_CheckBoxControlTypeId, "check box"),
+  (UIA_ComboBoxControlTypeId, "combo box"),
+  (UIA_EditControlTypeId, "text"),
+  (UIA_HyperlinkControlTypeId, "link"),
+  (UIA_ImageControlTypeId, "image"),
+  (UIA_ListItemControlTypeId, "list item"),
+  (UIA_ListControlTypeId, "list"),
+  (UIA_MenuControlTypeId, "menu"),
+  (UIA_MenuBarControlTypeId, "menu bar"),
+  (UIA_MenuItemControlTypeId, "menu item"),
+  (UIA_ProgressBarControlTypeId, "progress bar"),
+  (UIA_RadioButtonControlTypeId, "radio button"),
+  (UIA_ScrollBarControlTypeId, "scroll bar"),
+  (UIA_SliderControlTypeId, "slider"),
+  (UIA_SpinnerControlTypeId, "spin button"),
+  (UIA_StatusBarControlTypeId, "status bar"),
+  (UIA_TabControlTypeId, "page tab list"),
+  (UIA_TabItemControlTypeId, "page tab"),
+  (UIA_TextControlTypeId, "label"),
+  (UIA_ToolBarControlTypeId, "tool bar"),
+  (UIA_AppBarControlTypeId, "tool bar"),
+  (UIA_ToolTipControlTypeId, "tool tip"),
+  (UIA_TreeControlTypeId, "tree"),
+  (UIA_TreeItemControlTypeId, "tree item"),
+  (UIA_GroupControlTypeId, "grouping"),
+  (UIA_DataGridControlTypeId, "table"),
+  (UIA_TableControlTypeId, "table"),
+  (UIA_DataItemControlTypeId, "table cell"),
+  (UIA_DocumentControlTypeId, "document frame"),
+  (UIA_PaneControlTypeId, "panel"),
+  (UIA_HeaderControlTypeId, "header"),
+  (UIA_HeaderItemControlTypeId, "column header"),
+  (UIA_SeparatorControlTypeId, "separator"),
+  (UIA_TitleBarControlTypeId, "title bar"),
+  (UIA_WindowControlTypeId, "frame"),
+];
+
+/**
+ * Map a UI Automation control type to its AT-SPI role name, `unknown` when it has no equivalent
+ */
+fn control_type_role(control_type: UIA_CONTROLTYPE_ID) -> &'static str {
+  CONTROL_TYPE_ROLES
+    .iter()
+    .find(|(id, _)| id.eq(&control_type))
+    .map(|(_, role)| *role)
+    .unwrap_or("unknown")
+}
+
+/**
+ * Maximum length of a locale name including the null terminator
+ */
+const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+/**
+ * Convert a locale identifier to a BCP 47 language tag
+ */
+fn lcid_to_language_tag(lcid: u32) -> Option<String> {
+  let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH];
+  let length = unsafe { LCIDToLocaleName(lcid, Some(&mut buffer), 0) };
+  if length > 1 {
+    Some(String::from_utf16_lossy(&buffer[..(length - 1) as usize]))
+  } else {
+    None
+  }
+}
+
 fn get_browser_url(hwnd: HWND, exec_name: String) -> String {
   unsafe {
     if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() {
@@ -677,3 +2032,72 @@ fn is_browser(browser_name: &str) -> bool {
       | "firefox"
   )
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn get_automation_id(hwnd: HWND) -> String {
+    unsafe {
+      if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() {
+        let automation: Result<IUIAutomation, _> =
+          CoCreateInstance(&CUIAutomation, None, CLSCTX_ALL);
+        if let Ok(automation) = automation {
+          if let Ok(element) = automation.ElementFromHandle(hwnd) {
+            if let Ok(automation_id) = element.CurrentAutomationId() {
+              return automation_id.to_string();
+            }
+          }
+        }
+      }
+    }
+    "".to_owned()
+  }
+
+  #[test]
+  fn test_filetime_to_unix_ms() {
+    // 2024-01-01T00:00:00Z
+    let intervals: u64 = (1_704_067_200_000 + 11_644_473_600_000) * 10_000;
+    let filetime = FILETIME {
+      dwLowDateTime: intervals as u32,
+      dwHighDateTime: (intervals >> 32) as u32,
+    };
+    assert_eq!(filetime_to_unix_ms(&filetime), 1_704_067_200_000);
+    assert_eq!(filetime_to_unix_ms(&FILETIME::default()), 0);
+  }
+
+  #[test]
+  fn test_control_type_role() {
+    assert_eq!(control_type_role(UIA_ButtonControlTypeId), "push button");
+    assert_eq!(control_type_role(UIA_EditControlTypeId), "text");
+    assert_eq!(control_type_role(UIA_CustomControlTypeId), "unknown");
+  }
+
+  #[test]
+  fn test_lcid_to_language_tag() {
+    assert_eq!(lcid_to_language_tag(0x0409), Some("en-US".to_owned()));
+    assert_eq!(lcid_to_language_tag(0x040C), Some("fr-FR".to_owned()));
+  }
+
+  #[test]
+  fn test_find_windows_by_ax_identifier() {
+    let api = WindowsAPI {};
+    let mut known: Option<(u32, String)> = None;
+    enum_desktop_windows(|hwnd| {
+      let automation_id = get_automation_id(hwnd);
+      if !automation_id.is_empty() {
+        let window_info = get_window_information(hwnd, false, true, &VirtualDesktops::new(), 0);
+        if window_info.id.ne(&0) {
+          known = Some((window_info.id, automation_id));
+          return false;
+        }
+      }
+      true
+    });
+    let (window_id, automation_id) = known.expect("no open window exposes an AutomationId");
+    let windows = api.find_windows_by_ax_identifier(&automation_id).unwrap();
+    assert!(windows
+      .iter()
+      .any(|window_info| window_info.id == window_id));
+  }
+}
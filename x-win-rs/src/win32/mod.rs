@@ -2,9 +2,50 @@
 
 mod api;
 
+use std::{collections::HashMap, time::Duration};
+
 use crate::common::api::Api;
 use api::WindowsAPI;
 
 pub fn init_platform_api() -> impl Api {
   WindowsAPI {}
 }
+
+pub fn os_version() -> String {
+  api::os_version()
+}
+
+pub fn session_type() -> String {
+  api::session_type()
+}
+
+pub fn window_manager_name() -> String {
+  "dwm".to_owned()
+}
+
+pub fn get_process_cpu_time(process_id: u32) -> Option<Duration> {
+  api::get_process_cpu_time(process_id)
+}
+
+/**
+ * Environment block of other processes is only reachable by reading their memory (PEB),
+ * variables are only recovered for the current process and an empty map is returned for other processes
+ */
+pub fn get_process_env(process_id: u32) -> HashMap<String, String> {
+  if process_id == std::process::id() {
+    std::env::vars_os()
+      .map(|(key, value)| {
+        (
+          key.to_string_lossy().into_owned(),
+          value.to_string_lossy().into_owned(),
+        )
+      })
+      .collect()
+  } else {
+    HashMap::new()
+  }
+}
+
+pub fn get_permissions() -> Vec<(String, bool)> {
+  Vec::new()
+}